@@ -3,7 +3,12 @@
 extern crate alloc;
 extern crate proc_macro;
 
-#[cfg(any(feature = "json", feature = "ron", feature = "binary"))]
+#[cfg(any(
+    feature = "json",
+    feature = "ron",
+    feature = "binary",
+    feature = "toml"
+))]
 #[macro_use]
 mod shared;
 
@@ -22,7 +27,17 @@ mod serde_json;
 #[cfg(feature = "json")]
 use crate::serde_json::*;
 
-#[cfg(any(feature = "json", feature = "ron", feature = "binary"))]
+#[cfg(feature = "toml")]
+mod serde_toml;
+#[cfg(feature = "toml")]
+use crate::serde_toml::*;
+
+#[cfg(any(
+    feature = "json",
+    feature = "ron",
+    feature = "binary",
+    feature = "toml"
+))]
 mod parse;
 
 #[cfg(feature = "binary")]
@@ -30,8 +45,16 @@ mod parse;
 pub fn derive_ser_bin(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse::parse_data(input);
 
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
     let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
 
+    if let Some(module) = shared::attrs_with(input.attributes()) {
+        return derive_ser_bin_with(&module, input.name(), crate_name);
+    }
+
     if let Some(proxy) = shared::attrs_proxy(input.attributes()) {
         return derive_ser_bin_proxy(&proxy, input.name(), crate_name);
     }
@@ -50,8 +73,16 @@ pub fn derive_ser_bin(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn derive_de_bin(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse::parse_data(input);
 
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
     let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
 
+    if let Some(module) = shared::attrs_with(input.attributes()) {
+        return derive_de_bin_with(&module, input.name(), crate_name);
+    }
+
     if let Some(proxy) = shared::attrs_proxy(input.attributes()) {
         return derive_de_bin_proxy(&proxy, input.name(), crate_name);
     }
@@ -71,8 +102,16 @@ pub fn derive_de_bin(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn derive_ser_ron(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse::parse_data(input);
 
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
     let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
 
+    if let Some(module) = shared::attrs_with(input.attributes()) {
+        return derive_ser_ron_with(&module, input.name(), crate_name);
+    }
+
     if let Some(proxy) = shared::attrs_proxy(input.attributes()) {
         return derive_ser_ron_proxy(&proxy, input.name(), crate_name);
     }
@@ -91,8 +130,16 @@ pub fn derive_ser_ron(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn derive_de_ron(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse::parse_data(input);
 
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
     let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
 
+    if let Some(module) = shared::attrs_with(input.attributes()) {
+        return derive_de_ron_with(&module, input.name(), crate_name);
+    }
+
     if let Some(proxy) = shared::attrs_proxy(input.attributes()) {
         return derive_de_ron_proxy(&proxy, input.name(), crate_name);
     }
@@ -111,8 +158,20 @@ pub fn derive_de_ron(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn derive_ser_json(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse::parse_data(input);
 
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
+    if let Some(err) = check_field_attrs(&input) {
+        return err;
+    }
+
     let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
 
+    if let Some(module) = shared::attrs_with(input.attributes()) {
+        return derive_ser_json_with(&module, input.name(), crate_name);
+    }
+
     if let Some(proxy) = shared::attrs_proxy(input.attributes()) {
         return derive_ser_json_proxy(&proxy, input.name(), crate_name);
     }
@@ -133,8 +192,24 @@ pub fn derive_ser_json(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 pub fn derive_de_json(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse::parse_data(input);
 
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
+    if let Some(err) = check_field_attrs(&input) {
+        return err;
+    }
+
+    if let Some(err) = check_deny_unknown_fields(&input) {
+        return err;
+    }
+
     let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
 
+    if let Some(module) = shared::attrs_with(input.attributes()) {
+        return derive_de_json_with(&module, input.name(), crate_name);
+    }
+
     if let Some(proxy) = shared::attrs_proxy(input.attributes()) {
         return derive_de_json_proxy(&proxy, input.name(), crate_name);
     }
@@ -147,3 +222,39 @@ pub fn derive_de_json(input: proc_macro::TokenStream) -> proc_macro::TokenStream
         parse::Data::Union(_) => unimplemented!("Unions are not supported"),
     }
 }
+
+#[cfg(feature = "toml")]
+#[proc_macro_derive(SerToml, attributes(nserde))]
+pub fn derive_ser_toml(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse::parse_data(input);
+
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
+    let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
+
+    match &input {
+        parse::Data::Struct(struct_) if struct_.named => {
+            derive_ser_toml_struct(struct_, crate_name)
+        }
+        _ => unimplemented!("Only structs with named fields are supported"),
+    }
+}
+
+#[cfg(feature = "toml")]
+#[proc_macro_derive(DeToml, attributes(nserde))]
+pub fn derive_de_toml(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse::parse_data(input);
+
+    if let Some(err) = shared::check_renames(&input) {
+        return err;
+    }
+
+    let crate_name = shared::attrs_crate(input.attributes()).unwrap_or("nanoserde");
+
+    match &input {
+        parse::Data::Struct(struct_) if struct_.named => derive_de_toml_struct(struct_, crate_name),
+        _ => unimplemented!("Only structs with named fields are supported"),
+    }
+}
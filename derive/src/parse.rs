@@ -44,6 +44,9 @@ pub struct Field {
     pub vis: Visibility,
     pub field_name: Option<String>,
     pub ty: Type,
+    /// The attributes of each position of a tuple variant, empty otherwise.
+    #[allow(unused)]
+    pub tuple_attributes: Vec<Vec<Attribute>>,
 }
 
 #[derive(Debug, Clone)]
@@ -142,6 +145,10 @@ pub struct Struct {
 }
 
 #[derive(Debug)]
+#[cfg_attr(
+    not(any(feature = "json", feature = "ron", feature = "binary")),
+    allow(dead_code)
+)]
 pub struct Enum {
     pub name: String,
     pub variants: Vec<Field>,
@@ -203,12 +210,22 @@ impl Generic {
         }
     }
 
-    #[cfg(any(feature = "binary", feature = "json"))]
+    #[cfg(any(
+        feature = "binary",
+        feature = "json",
+        feature = "ron",
+        feature = "toml"
+    ))]
     pub fn ident_only(&self) -> String {
         format!("{}{}", self.lifetime_prefix(), self.full())
     }
 
-    #[cfg(any(feature = "binary", feature = "json"))]
+    #[cfg(any(
+        feature = "binary",
+        feature = "json",
+        feature = "ron",
+        feature = "toml"
+    ))]
     pub fn full_with_const(&self, extra_bounds: &[&str], bounds: bool) -> String {
         let bounds = match (bounds, &self) {
             (true, Generic::Lifetime { .. }) => self.get_bounds().join(" + "),
@@ -422,7 +439,7 @@ impl Category {
 }
 
 impl Type {
-    #[cfg(any(feature = "ron", feature = "json"))]
+    #[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
     pub fn base(&self) -> String {
         let mut base = match &self.ref_type {
             Some(inner) => match inner {
@@ -1084,7 +1101,16 @@ fn next_attribute<T: Iterator<Item = TokenTree>>(
             _ => (),
         }
 
-        let value = next_literal(&mut args_group).expect("Expecting argument value");
+        // `name = const { EXPR }` keeps the expression as written, after a "const" marker token
+        let value = match next_ident(&mut args_group) {
+            Some(keyword) if keyword == "const" => {
+                let expr = next_group(&mut args_group).expect("Expecting `{ EXPR }` after `const`");
+                attr_tokens.push(keyword);
+                expr.stream().to_string()
+            }
+            Some(other) => panic!("Expecting argument value, got `{}`", other),
+            None => next_literal(&mut args_group).expect("Expecting argument value"),
+        };
 
         attr_tokens.push(value.clone());
 
@@ -1155,6 +1181,7 @@ fn next_fields<T: Iterator<Item = TokenTree> + Clone>(
             vis: Visibility::Public,
             field_name,
             ty,
+            tuple_attributes: vec![],
         });
     }
     fields
@@ -1234,7 +1261,31 @@ fn next_enum<T: Iterator<Item = TokenTree> + Clone>(source: &mut Peekable<T>) ->
         let attributes = next_attributes_list(&mut body);
 
         let variant_name = next_ident(&mut body).expect("Unnamed variants are not supported");
-        let ty = next_type(&mut body);
+        // the positions of a tuple variant may have attributes of their own
+        let mut tuple_attributes = vec![];
+        let ty = match body.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                let mut positions = group.stream().into_iter().peekable();
+                body.next();
+                let fields = next_fields(&mut positions, false);
+                let contents: Vec<Type> = fields
+                    .into_iter()
+                    .map(|field| {
+                        tuple_attributes.push(field.attributes);
+                        field.ty
+                    })
+                    .collect();
+                Some(Type {
+                    ident: Category::Tuple {
+                        contents: contents.clone(),
+                    },
+                    wraps: Some(contents),
+                    ref_type: None,
+                    as_other: None,
+                })
+            }
+            _ => next_type(&mut body),
+        };
         let Some(ty) = ty else {
             variants.push(Field {
                 ty: Type {
@@ -1246,6 +1297,7 @@ fn next_enum<T: Iterator<Item = TokenTree> + Clone>(source: &mut Peekable<T>) ->
                 attributes,
                 vis: Visibility::Public,
                 field_name: Some(variant_name),
+                tuple_attributes,
             });
             let _maybe_comma = next_exact_punct(&mut body, ",");
             continue;
@@ -1257,6 +1309,7 @@ fn next_enum<T: Iterator<Item = TokenTree> + Clone>(source: &mut Peekable<T>) ->
                 ty,
                 attributes,
                 vis: Visibility::Public,
+                tuple_attributes,
             });
         }
 
@@ -1525,7 +1578,8 @@ pub fn parse_data(input: TokenStream) -> Data {
             res = Data::Struct(struct_);
         }
         "enum" => {
-            let enum_ = next_enum(&mut source);
+            let mut enum_ = next_enum(&mut source);
+            enum_.attributes = attributes;
             res = Data::Enum(enum_);
         }
         "union" => unimplemented!("Unions are not supported"),
@@ -36,12 +36,55 @@ pub fn derive_de_bin_proxy(proxy_type: &str, type_: &str, crate_name: &str) -> T
     .unwrap()
 }
 
+pub fn derive_ser_bin_with(module: &str, type_: &str, crate_name: &str) -> TokenStream {
+    format!(
+        "impl {}::SerBin for {} {{
+            fn ser_bin(&self, s: &mut Vec<u8>) {{
+                {}::ser_bin(self, s)
+            }}
+        }}",
+        crate_name, type_, module
+    )
+    .parse()
+    .unwrap()
+}
+
+pub fn derive_de_bin_with(module: &str, type_: &str, crate_name: &str) -> TokenStream {
+    format!(
+        "impl {}::DeBin for {} {{
+            fn de_bin(o:&mut usize, d:&[u8]) -> ::core::result::Result<Self, {}::DeBinErr> {{
+                {}::de_bin(o, d)
+            }}
+        }}",
+        crate_name, type_, crate_name, module
+    )
+    .parse()
+    .unwrap()
+}
+
 pub fn derive_ser_bin_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
     let mut body = String::new();
+    let mut len = String::from("0");
     let (generic_w_bounds, generic_no_bounds) =
         struct_bounds_strings(struct_, "SerBin", crate_name);
 
     for field in &struct_.fields {
+        // a plain `skip` is not honored by binary, it would change the layout of existing data
+        if crate::shared::attrs_skip_serializing(&field.attributes)
+            && !crate::shared::attrs_skip(&field.attributes)
+        {
+            continue;
+        }
+        // conditionally skipped fields are prefixed with a presence byte
+        let skip_if = crate::shared::attrs_skip_serializing_if(&field.attributes);
+        if let Some(predicate) = &skip_if {
+            l!(
+                body,
+                "if {}(&self.{}) {{ s.push(0); }} else {{ s.push(1);",
+                predicate,
+                field.field_name.as_ref().unwrap()
+            );
+        }
         if let Some(proxy) = crate::shared::attrs_proxy(&field.attributes) {
             l!(
                 body,
@@ -57,12 +100,28 @@ pub fn derive_ser_bin_struct(struct_: &Struct, crate_name: &str) -> TokenStream
                 field.field_name.as_ref().unwrap()
             );
         }
+        if skip_if.is_some() {
+            l!(body, "}");
+            l!(len, " + 1");
+        }
+        // proxies would have to be built to know their size, leave them out of the hint
+        if crate::shared::attrs_proxy(&field.attributes).is_none() {
+            l!(
+                len,
+                " + self.{}.ser_bin_len()",
+                field.field_name.as_ref().unwrap()
+            );
+        }
     }
     format!(
         "impl{} {}::SerBin for {}{} {{
             fn ser_bin(&self, s: &mut Vec<u8>) {{
                 {}
             }}
+
+            fn ser_bin_len(&self) -> usize {{
+                {}
+            }}
         }}",
         generic_w_bounds,
         crate_name,
@@ -71,7 +130,8 @@ pub fn derive_ser_bin_struct(struct_: &Struct, crate_name: &str) -> TokenStream
             .as_ref()
             .expect("Shouldnt have an anonymous struct here"),
         generic_no_bounds,
-        body
+        body,
+        len
     )
     .parse()
     .unwrap()
@@ -82,12 +142,14 @@ pub fn derive_ser_bin_struct_unnamed(struct_: &Struct, crate_name: &str) -> Toke
     let (generic_w_bounds, generic_no_bounds) =
         struct_bounds_strings(struct_, "SerBin", crate_name);
 
+    let mut len = String::from("0");
     for (n, field) in struct_.fields.iter().enumerate() {
         if let Some(proxy) = crate::shared::attrs_proxy(&field.attributes) {
             l!(body, "let proxy: {} = Into::into(&self.{});", proxy, n);
             l!(body, "proxy.ser_bin(s);");
         } else {
             l!(body, "self.{}.ser_bin(s);", n);
+            l!(len, " + self.{}.ser_bin_len()", n);
         }
     }
     format!(
@@ -95,6 +157,10 @@ pub fn derive_ser_bin_struct_unnamed(struct_: &Struct, crate_name: &str) -> Toke
             fn ser_bin(&self, s: &mut Vec<u8>) {{
                 {}
             }}
+
+            fn ser_bin_len(&self) -> usize {{
+                {}
+            }}
         }}",
         generic_w_bounds,
         crate_name,
@@ -103,34 +169,91 @@ pub fn derive_ser_bin_struct_unnamed(struct_: &Struct, crate_name: &str) -> Toke
             .as_ref()
             .expect("Shouldnt have an anonymous struct here"),
         generic_no_bounds,
-        body
+        body,
+        len
     )
     .parse()
     .unwrap()
 }
 
+/// `#[nserde(unchecked_utf8 = "unsafe")]` makes the safe `DeBin` impl skip the
+/// UTF-8 validation of a `String` field, so the caller has to spell out the
+/// `unsafe` to take on the contract of `de_bin_string_unchecked`.
+fn check_unchecked_utf8(struct_: &Struct) -> Result<(), TokenStream> {
+    for field in &struct_.fields {
+        match crate::shared::attrs_unchecked_utf8(&field.attributes) {
+            Some(false) => {
+                return Err("compile_error!(\"#[nserde(unchecked_utf8)] skips UTF-8 validation, acknowledge it with #[nserde(unchecked_utf8 = \\\"unsafe\\\")]\");"
+                    .parse()
+                    .unwrap());
+            }
+            Some(true)
+                if !matches!(
+                    field.ty.full().as_str(),
+                    "String" | "std::string::String" | "alloc::string::String"
+                ) =>
+            {
+                return Err("compile_error!(\"#[nserde(unchecked_utf8 = \\\"unsafe\\\")] is only supported on String fields\");"
+                    .parse()
+                    .unwrap());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 pub fn derive_de_bin_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
+    if let Err(err) = check_unchecked_utf8(struct_) {
+        return err;
+    }
     let mut body = String::new();
     let (generic_w_bounds, generic_no_bounds) = struct_bounds_strings(struct_, "DeBin", crate_name);
 
     for field in &struct_.fields {
-        if let Some(proxy) = crate::shared::attrs_proxy(&field.attributes) {
-            l!(body, "{}: {{", field.field_name.as_ref().unwrap());
+        // only the one-directional skips, like in `derive_ser_bin_struct`
+        let one_way = !crate::shared::attrs_skip(&field.attributes);
+        if one_way && crate::shared::attrs_skip_serializing(&field.attributes) {
             l!(
                 body,
-                "let proxy: {} = {}::DeBin::de_bin(o, d)?;",
-                proxy,
-                crate_name
+                "{}: Default::default(),",
+                field.field_name.as_ref().unwrap()
             );
-            l!(body, "Into::into(&proxy)");
-            l!(body, "},")
+            continue;
+        }
+        let value = if let Some(proxy) = crate::shared::attrs_proxy(&field.attributes) {
+            format!(
+                "{{let proxy: {} = {}::DeBin::de_bin(o, d)?; Into::into(&proxy)}}",
+                proxy, crate_name
+            )
+        } else if crate::shared::attrs_unchecked_utf8(&field.attributes).is_some() {
+            format!(
+                "unsafe {{ {}::de_bin_string_unchecked(o, d)? }}",
+                crate_name
+            )
         } else {
+            format!("{}::DeBin::de_bin(o, d)?", crate_name)
+        };
+        // the value is still written, so it is read to stay in step with the data
+        let value = if one_way && crate::shared::attrs_skip_deserializing(&field.attributes) {
+            format!(
+                "{{let _: {} = {}; Default::default()}}",
+                field.ty.full(),
+                value
+            )
+        } else {
+            value
+        };
+        if crate::shared::attrs_skip_serializing_if(&field.attributes).is_some() {
             l!(
                 body,
-                "{}: {}::DeBin::de_bin(o, d)?,",
+                "{}: if <bool as {}::DeBin>::de_bin(o, d)? {{ {} }} else {{ Default::default() }},",
                 field.field_name.as_ref().unwrap(),
-                crate_name
+                crate_name,
+                value
             );
+        } else {
+            l!(body, "{}: {},", field.field_name.as_ref().unwrap(), value);
         }
     }
 
@@ -157,6 +280,9 @@ pub fn derive_de_bin_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
 }
 
 pub fn derive_de_bin_struct_unnamed(struct_: &Struct, crate_name: &str) -> TokenStream {
+    if let Err(err) = check_unchecked_utf8(struct_) {
+        return err;
+    }
     let mut body = String::new();
     let (generic_w_bounds, generic_no_bounds) = struct_bounds_strings(struct_, "DeBin", crate_name);
 
@@ -171,6 +297,13 @@ pub fn derive_de_bin_struct_unnamed(struct_: &Struct, crate_name: &str) -> Token
             );
             l!(body, "Into::into(&proxy)");
             l!(body, "},")
+        } else if crate::shared::attrs_unchecked_utf8(&field.attributes).is_some() {
+            l!(
+                body,
+                "{}: unsafe {{ {}::de_bin_string_unchecked(o, d)? }},",
+                n,
+                crate_name
+            );
         } else {
             l!(body, "{}: {}::DeBin::de_bin(o, d)?,", n, crate_name);
         }
@@ -202,8 +335,13 @@ pub fn derive_ser_bin_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
     let mut r = String::new();
     let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "SerBin", crate_name);
 
-    for (index, variant) in enum_.variants.iter().enumerate() {
-        let lit = format!("{}u16", index);
+    let tags = match crate::shared::bin_tags(enum_) {
+        Ok(tags) => tags,
+        Err(err) => return err,
+    };
+
+    for (variant, tag) in enum_.variants.iter().zip(tags) {
+        let lit = format!("{}u16", tag);
         let ident = variant
             .field_name
             .as_ref()
@@ -281,8 +419,13 @@ pub fn derive_de_bin_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
     let mut r = String::new();
     let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "DeBin", crate_name);
 
-    for (index, variant) in enum_.variants.iter().enumerate() {
-        let lit = format!("{}u16", index);
+    let tags = match crate::shared::bin_tags(enum_) {
+        Ok(tags) => tags,
+        Err(err) => return err,
+    };
+
+    for (variant, tag) in enum_.variants.iter().zip(tags) {
+        let lit = format!("{}u16", tag);
 
         match &variant.ty {
             Type {
@@ -25,8 +25,165 @@ pub fn derive_ser_json_proxy(proxy_type: &str, type_: &str, crate_name: &str) ->
     .unwrap()
 }
 
-fn ser_proxy_guard(fieldname: &str, field: &Field) -> String {
-    if let Some(proxy) = crate::shared::attrs_proxy(&field.attributes) {
+/// Rejects a field attribute on a field type it doesn't support, returning the
+/// `compile_error!` to emit instead of the impl. The helpers below rely on it.
+pub fn check_field_attrs(input: &crate::parse::Data) -> Option<TokenStream> {
+    use crate::parse::Data;
+
+    fn field_error(field: &Field) -> Option<String> {
+        let name = field.field_name.as_deref().unwrap_or_default();
+        if let Some(format) = shared::attrs_duration(&field.attributes) {
+            if format != "iso8601" {
+                return Some(format!(
+                    "#[nserde(duration = \\\"{}\\\")] on {} is not supported, the only duration format is \\\"iso8601\\\"",
+                    format, name
+                ));
+            }
+        }
+        if shared::attrs_float_as_string(&field.attributes)
+            && !matches!(option_inner(&field.ty).base().as_str(), "f32" | "f64")
+        {
+            return Some(format!(
+                "#[nserde(float_as_string)] on {} only supports f32 and f64 fields",
+                name
+            ));
+        }
+        if shared::attrs_tagged_trait(&field.attributes) && boxed_dyn(&field.ty).is_none() {
+            return Some(format!(
+                "#[nserde(tagged_trait)] on {} only supports Box<dyn Trait> fields",
+                name
+            ));
+        }
+        if shared::attrs_tuple_defaults(&field.attributes) && !field.ty.full().starts_with('(') {
+            return Some(format!(
+                "#[nserde(tuple_defaults)] on {} only supports tuple fields",
+                name
+            ));
+        }
+        match &field.ty.ident {
+            Category::AnonymousStruct { contents } => contents.fields.iter().find_map(field_error),
+            _ => None,
+        }
+    }
+
+    let fields = match input {
+        Data::Struct(struct_) => &struct_.fields,
+        Data::Enum(enum_) => &enum_.variants,
+        Data::Union(_) => return None,
+    };
+    fields
+        .iter()
+        .find_map(field_error)
+        .map(|msg| format!("compile_error!(\"{}\");", msg).parse().unwrap())
+}
+
+/// Rejects `#[nserde(deny_unknown_fields)]` on a struct or variant with a flattened
+/// field, which takes every key the other fields don't, returning the `compile_error!`
+/// to emit instead of the impl.
+pub fn check_deny_unknown_fields(input: &crate::parse::Data) -> Option<TokenStream> {
+    use crate::parse::Data;
+
+    let has_flatten = |fields: &[Field]| {
+        fields
+            .iter()
+            .any(|field| shared::attrs_flatten(&field.attributes))
+    };
+    let name = match input {
+        Data::Struct(struct_)
+            if shared::attrs_deny_unknown_fields(&struct_.attributes)
+                && has_flatten(&struct_.fields) =>
+        {
+            input.name().to_string()
+        }
+        Data::Enum(enum_) => enum_
+            .variants
+            .iter()
+            .find_map(|variant| match &variant.ty.ident {
+                Category::AnonymousStruct { contents }
+                    if variant_deny_unknown_fields(enum_, variant)
+                        && has_flatten(&contents.fields) =>
+                {
+                    Some(format!(
+                        "{}::{}",
+                        enum_.name,
+                        variant.field_name.as_deref()?
+                    ))
+                }
+                _ => None,
+            })?,
+        _ => return None,
+    };
+    Some(
+        format!(
+            "compile_error!(\"#[nserde(deny_unknown_fields)] on {} can't be combined with #[nserde(flatten)]\");",
+            name
+        )
+        .parse()
+        .unwrap(),
+    )
+}
+
+/// `T` of an `Option<T>`, any other type as is.
+fn option_inner(ty: &Type) -> &Type {
+    match (ty.base().as_str(), &ty.wraps) {
+        ("Option", Some(wraps)) if wraps.len() == 1 => &wraps[0],
+        _ => ty,
+    }
+}
+
+/// The `dyn Trait` of a `Box<dyn Trait>` or an `Option<Box<dyn Trait>>`.
+fn boxed_dyn(ty: &Type) -> Option<String> {
+    let boxed = option_inner(ty);
+    match (boxed.base().as_str(), &boxed.wraps) {
+        ("Box", Some(wraps)) if wraps.len() == 1 && wraps[0].full().starts_with("dyn ") => {
+            Some(wraps[0].full())
+        }
+        _ => None,
+    }
+}
+
+/// The `proxy` of a field, `#[nserde(duration = "..")]` and
+/// `#[nserde(float_as_string)]` are implemented with proxies provided by the crate.
+fn json_proxy(field: &Field, crate_name: &str) -> Option<String> {
+    if shared::attrs_duration(&field.attributes).is_some() {
+        return Some(format!("{}::Iso8601Duration", crate_name));
+    }
+    if shared::attrs_float_as_string(&field.attributes) {
+        return Some(format!(
+            "{}::FloatAsString<{}>",
+            crate_name,
+            option_inner(&field.ty).base()
+        ));
+    }
+    shared::attrs_proxy(&field.attributes)
+}
+
+/// The `dyn Trait` of a `#[nserde(tagged_trait)]` field, a `Box<dyn Trait>`
+/// or an `Option<Box<dyn Trait>>`.
+fn tagged_trait(field: &Field) -> Option<String> {
+    if !shared::attrs_tagged_trait(&field.attributes) {
+        return None;
+    }
+    boxed_dyn(&field.ty)
+}
+
+/// An `Option<Option<T>>` field keeps an explicit `null` apart from a missing key.
+fn nested_option(field: &Field) -> bool {
+    match (field.ty.base().as_str(), &field.ty.wraps) {
+        ("Option", Some(wraps)) if wraps.len() == 1 => wraps[0].base() == "Option",
+        _ => false,
+    }
+}
+
+fn ser_proxy_guard(fieldname: &str, field: &Field, crate_name: &str) -> String {
+    if tagged_trait(field).is_some() {
+        return if field.ty.base() == "Option" {
+            format!("{{{fieldname}.as_ref().map(|f| {crate_name}::TaggedTraitRef(&**f))}}")
+        } else {
+            format!("{crate_name}::TaggedTraitRef(&*{fieldname})")
+        };
+    }
+    if let Some(proxy) = json_proxy(field, crate_name) {
         if field.ty.base() == "Option" {
             format!(
                 "{{{fieldname}.as_ref().map(|f| {{let proxy: {proxy} = Into::into(f);proxy}})}}"
@@ -39,73 +196,193 @@ fn ser_proxy_guard(fieldname: &str, field: &Field) -> String {
     }
 }
 
-pub fn derive_ser_json_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
+/// Serializes `field`, bound to `value`, with its `serialize_with` function if
+/// it has one, otherwise through `ser_proxy_guard`.
+fn ser_json_field_value(value: &str, field: &Field, crate_name: &str) -> String {
+    match shared::attrs_serialize_with(&field.attributes) {
+        Some(function) => format!("{}(&{}, d+1, s);", function, value),
+        None => format!(
+            "{}.ser_json(d+1, s);",
+            ser_proxy_guard(value, field, crate_name)
+        ),
+    }
+}
+
+/// The value serialized for the position of a tuple variant bound to `name`,
+/// through its `proxy` if it has one, like `ser_proxy_guard` does for fields.
+fn ser_json_tuple_value(name: &str, attributes: &[crate::parse::Attribute], ty: &Type) -> String {
+    match shared::attrs_proxy(attributes) {
+        Some(proxy) if ty.base() == "Option" => {
+            format!("{{{name}.as_ref().map(|f| {{let proxy: {proxy} = Into::into(f);proxy}})}}")
+        }
+        Some(proxy) => format!("{{let proxy: {proxy} = Into::into({name});proxy}}"),
+        None => name.to_string(),
+    }
+}
+
+/// Deserializes the value of a tuple position, through its `proxy` if it has one.
+fn de_json_tuple_value(
+    attributes: &[crate::parse::Attribute],
+    ty: &Type,
+    crate_name: &str,
+) -> String {
+    match shared::attrs_proxy(attributes) {
+        Some(proxy) if ty.base() == "Option" => format!(
+            "<Option<{proxy}> as {crate_name}::DeJson>::de_json(s, i)?.as_ref().map(From::<&{proxy}>::from)"
+        ),
+        Some(proxy) => {
+            format!("From::<&{proxy}>::from(&<{proxy} as {crate_name}::DeJson>::de_json(s, i)?)")
+        }
+        None => format!("{crate_name}::DeJson::de_json(s, i)?"),
+    }
+}
+
+fn ser_json_struct_fields<'a>(
+    struct_: &Struct,
+    fields: impl Iterator<Item = &'a Field>,
+    crate_name: &str,
+) -> String {
     let mut s = String::new();
-    let (generic_w_bounds, generic_no_bounds) =
-        struct_bounds_strings(struct_, "SerJson", crate_name);
 
     l!(s, "let mut first_field_was_serialized = false;");
 
-    if !struct_.fields.is_empty() {
-        for field in struct_.fields.iter() {
-            let struct_fieldname = field.field_name.clone().unwrap();
-            let json_fieldname =
-                shared::attrs_rename(&field.attributes).unwrap_or_else(|| struct_fieldname.clone());
-            let skip = shared::attrs_skip(&field.attributes);
-            if skip {
-                continue;
-            }
-            let proxied_field = ser_proxy_guard(&format!("self.{struct_fieldname}"), field);
+    for field in fields {
+        let struct_fieldname = field.field_name.clone().unwrap();
+        let json_fieldname =
+            shared::attrs_rename(&field.attributes).unwrap_or_else(|| struct_fieldname.clone());
+        if shared::attrs_skip_serializing(&field.attributes) {
+            continue;
+        }
+        let proxied_field = ser_proxy_guard(&format!("self.{struct_fieldname}"), field, crate_name);
+        let skip_if = shared::attrs_skip_serializing_if(&field.attributes);
+        // the predicate guards the whole field, so it takes precedence over
+        // `serialize_none_as_null`: a skipped `None` is never written as `null`
+        if let Some(predicate) = &skip_if {
+            l!(s, "if !{}(&self.{}) {{", predicate, struct_fieldname);
+        }
+        // sorting reuses the canonical mode, only switched on for this field
+        let sort_keys = shared::attrs_sort_keys(&field.attributes)
+            || shared::attrs_sort_keys(&struct_.attributes);
+        if sort_keys {
+            l!(
+                s,
+                "let __nserde_canonical = ::core::mem::replace(&mut s.canonical, true);"
+            );
+        }
 
-            if field.ty.base() == "Option" {
-                let proxy_attr = crate::shared::attrs_proxy(&field.attributes);
-                let struct_null_on_none = shared::attrs_serialize_none_as_null(&struct_.attributes);
-                let field_null_on_none = shared::attrs_serialize_none_as_null(&field.attributes);
-                let null_on_none =
-                    (field_null_on_none || struct_null_on_none) && proxy_attr.is_none();
-                let field_header = &format!(
-                    "if first_field_was_serialized {{
-                                                 s.conl();
-                                             }};
-                                             first_field_was_serialized = true;
-                                             s.field(d+1, \"{}\");",
-                    json_fieldname
-                );
-                l!(
-                    s,
-                    "{}
-                    if let Some(t) = &{} {{
-                        {}
-                        t.ser_json(d+1, s);
-                    }} {}",
-                    if null_on_none { field_header } else { "" },
-                    proxied_field,
-                    if null_on_none { "" } else { field_header },
-                    if null_on_none {
-                        "else {{
-                            Option::<i32>::ser_json(&None, d+1, s);
-                        }}"
-                    } else {
-                        ""
-                    }
-                );
-            } else {
-                l!(
-                    s,
-                    "if first_field_was_serialized {{
-                        s.conl();
-                    }};
+        if shared::attrs_flatten(&field.attributes) {
+            l!(
+                s,
+                "if s.flatten(d, &{}, first_field_was_serialized) {{
                     first_field_was_serialized = true;
-                    s.field(d+1,\"{}\");
-                    {}.ser_json(d+1, s);",
-                    json_fieldname,
-                    proxied_field
-                );
-            }
+                }}",
+                proxied_field
+            );
+        } else if let Some(function) = shared::attrs_serialize_with(&field.attributes) {
+            // the function gets the whole field, so an `Option` is written even if `None`
+            l!(
+                s,
+                "if first_field_was_serialized {{
+                    s.conl();
+                }};
+                first_field_was_serialized = true;
+                s.field(d+1,\"{}\");
+                {}(&self.{}, d+1, s);",
+                json_fieldname,
+                function,
+                struct_fieldname
+            );
+        } else if field.ty.base() == "Option" {
+            let proxy_attr = json_proxy(field, crate_name);
+            let struct_null_on_none = shared::attrs_serialize_none_as_null(&struct_.attributes);
+            let field_null_on_none = shared::attrs_serialize_none_as_null(&field.attributes);
+            // an outer `None` is the missing key, `Some(None)` is written as `null`
+            let null_on_none = (field_null_on_none || struct_null_on_none)
+                && proxy_attr.is_none()
+                && !nested_option(field);
+            let field_header = &format!(
+                "if first_field_was_serialized {{
+                                             s.conl();
+                                         }};
+                                         first_field_was_serialized = true;
+                                         s.field(d+1, \"{}\");",
+                json_fieldname
+            );
+            l!(
+                s,
+                "{}
+                if let Some(t) = &{} {{
+                    {}
+                    t.ser_json(d+1, s);
+                }} {}",
+                if null_on_none { field_header } else { "" },
+                proxied_field,
+                if null_on_none { "" } else { field_header },
+                if null_on_none {
+                    "else {{
+                        Option::<i32>::ser_json(&None, d+1, s);
+                    }}"
+                } else {
+                    ""
+                }
+            );
+        } else {
+            l!(
+                s,
+                "if first_field_was_serialized {{
+                    s.conl();
+                }};
+                first_field_was_serialized = true;
+                s.field(d+1,\"{}\");
+                {}.ser_json(d+1, s);",
+                json_fieldname,
+                proxied_field
+            );
+        }
+
+        if sort_keys {
+            l!(s, "s.canonical = __nserde_canonical;");
+        }
+        if skip_if.is_some() {
+            l!(s, "}");
         }
     }
 
-    format!(
+    s
+}
+
+pub fn derive_ser_json_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
+    let (generic_w_bounds, generic_no_bounds) =
+        struct_bounds_strings(struct_, "SerJson", crate_name);
+
+    if shared::attrs_transparent(&struct_.attributes) {
+        let index = match shared::transparent_field(struct_) {
+            Ok(index) => index,
+            Err(err) => return err,
+        };
+        return format!(
+            "
+            impl{} {}::SerJson for {}{} {{
+                fn ser_json(&self, d: usize, s: &mut {}::SerJsonState) {{
+                    self.{}.ser_json(d, s);
+                }}
+            }}",
+            generic_w_bounds,
+            crate_name,
+            struct_.name.as_ref().unwrap(),
+            generic_no_bounds,
+            crate_name,
+            shared::struct_field_access(struct_, index)
+        )
+        .parse()
+        .unwrap();
+    }
+    let struct_name = struct_
+        .name
+        .as_ref()
+        .expect("Cannot implement for anonymous struct");
+
+    let mut r = format!(
         "
         impl{} {}::SerJson for {}{} {{
             fn ser_json(&self, d: usize, s: &mut {}::SerJsonState) {{
@@ -117,21 +394,99 @@ pub fn derive_ser_json_struct(struct_: &Struct, crate_name: &str) -> TokenStream
     ",
         generic_w_bounds,
         crate_name,
-        struct_
-            .name
-            .as_ref()
-            .expect("Cannot implement for anonymous struct"),
+        struct_name,
         generic_no_bounds,
         crate_name,
-        s
-    )
-    .parse()
-    .unwrap()
+        ser_json_struct_fields(struct_, struct_.fields.iter(), crate_name)
+    );
+
+    // fields tagged with #[nserde(group = "..")] get an extra serializer
+    // emitting only the fields of the requested group
+    let mut groups: Vec<String> = vec![];
+    for field in &struct_.fields {
+        for group in shared::attrs_groups(&field.attributes) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+    }
+    if !groups.is_empty() {
+        let mut arms = String::new();
+        for group in &groups {
+            let fields = struct_.fields.iter().filter(|field| {
+                shared::attrs_groups(&field.attributes)
+                    .iter()
+                    .any(|g| g == group)
+            });
+            l!(
+                arms,
+                "\"{}\" => {{ {} }},",
+                group,
+                ser_json_struct_fields(struct_, fields, crate_name)
+            );
+        }
+        l!(
+            r,
+            "
+            impl{} {}{} {{
+                /// Serialize only the fields tagged with `#[nserde(group = \"...\")]`
+                /// for the given group to a JSON string.
+                pub fn serialize_json_group(&self, group: &str) -> String {{
+                    let mut state = {}::SerJsonState::new(String::new());
+                    let (d, s) = (0, &mut state);
+                    s.st_pre();
+                    match group {{
+                        {}
+                        _ => {{}}
+                    }}
+                    s.st_post(d);
+                    state.out
+                }}
+            }}",
+            generic_w_bounds,
+            struct_name,
+            generic_no_bounds,
+            crate_name,
+            arms
+        );
+    }
+
+    if shared::attrs_introspect(&struct_.attributes) {
+        // flattened fields are left out, their keys depend on the flattened type
+        let names = struct_
+            .fields
+            .iter()
+            .filter(|field| {
+                !shared::attrs_skip_serializing(&field.attributes)
+                    && !shared::attrs_flatten(&field.attributes)
+            })
+            .map(|field| {
+                let name = shared::attrs_rename(&field.attributes)
+                    .unwrap_or_else(|| field.field_name.clone().unwrap());
+                format!("\"{}\"", name)
+            })
+            .collect::<Vec<_>>();
+        l!(
+            r,
+            "
+            impl{} {}{} {{
+                /// The keys of the JSON object, in the order they are serialized.
+                pub const NSERDE_FIELDS: &'static [&'static str] = &[{}];
+            }}",
+            generic_w_bounds,
+            struct_name,
+            generic_no_bounds,
+            names.join(", ")
+        );
+    }
+
+    r.parse().unwrap()
 }
 
 pub fn derive_de_json_named(
     name: &str,
     defaults: bool,
+    deny_unknown_fields: bool,
     fields: &[Field],
     crate_name: &str,
 ) -> TokenStream {
@@ -139,45 +494,43 @@ pub fn derive_de_json_named(
     let mut struct_field_names = Vec::new();
     let mut json_field_names = Vec::new();
     let mut matches = Vec::new();
+    let mut ignored = Vec::new();
     let mut unwraps = Vec::new();
 
     let container_attr_default = defaults;
 
+    // keys not matching any field are collected for the flattened fields
+    let has_flatten = fields.iter().any(|field| {
+        shared::attrs_flatten(&field.attributes)
+            && !shared::attrs_skip_deserializing(&field.attributes)
+    });
+
+    let expected_keys = fields
+        .iter()
+        .filter(|field| {
+            !shared::attrs_skip_deserializing(&field.attributes)
+                && !shared::attrs_flatten(&field.attributes)
+        })
+        .map(|field| {
+            let json_fieldname = shared::attrs_rename(&field.attributes)
+                .unwrap_or_else(|| field.field_name.as_ref().unwrap().to_string());
+            format!("\"{}\",", json_fieldname)
+        })
+        .collect::<String>();
+
     for field in fields {
         let struct_fieldname = field.field_name.as_ref().unwrap().to_string();
         let localvar = format!("_{}", struct_fieldname);
-        let field_attr_default = shared::attrs_default(&field.attributes);
-        let field_attr_default_with = shared::attrs_default_with(&field.attributes);
-        let default_val = if let Some(v) = field_attr_default {
-            if let Some(mut val) = v {
-                if field.ty.base() == "String"
-                    || field.ty.wraps.as_ref().map_or(false, |wrapped| {
-                        wrapped.iter().any(|ty| ty.base() == "String")
-                    })
-                {
-                    val = format!("\"{}\".to_string()", val)
-                }
-                if field.ty.base() == "Option" {
-                    val = format!("Some({})", val);
-                }
-                Some(val)
-            } else if field.ty.base() != "Option" {
-                Some(String::from("Default::default()"))
-            } else {
-                Some(String::from("None"))
-            }
-        } else if let Some(mut v) = field_attr_default_with {
-            v.push_str("()");
-            Some(v)
-        } else {
-            None
-        };
+        let default_val = shared::default_val(&field.attributes, &field.ty);
         let json_fieldname =
             shared::attrs_rename(&field.attributes).unwrap_or(struct_fieldname.clone());
-        let proxy = crate::shared::attrs_proxy(&field.attributes);
-        let skip = crate::shared::attrs_skip(&field.attributes);
+        let proxy = json_proxy(field, crate_name);
+        let skip = shared::attrs_skip_deserializing(&field.attributes);
+        // a conditionally skipped or never written field may be missing from the input
+        let skip_if = shared::attrs_skip_serializing_if(&field.attributes).is_some()
+            || shared::attrs_skip_serializing(&field.attributes);
 
-        let proxified_t = if let Some(proxy) = proxy {
+        let proxified_t = if let Some(proxy) = &proxy {
             if field.ty.base() == "Option" {
                 format!("Some(From::<&{proxy}>::from(&t))")
             } else {
@@ -187,7 +540,12 @@ pub fn derive_de_json_named(
             "t".to_string()
         };
 
-        if !skip {
+        if !skip && shared::attrs_flatten(&field.attributes) {
+            unwraps.push(format!(
+                "s.parse_captured(&__nserde_flatten, {}::DeJson::de_json)?",
+                crate_name
+            ));
+        } else if !skip {
             if field.ty.base() == "Option" {
                 unwraps.push(format!(
                     "{{if let Some(t) = {} {{ {} }} else {{ {} }} }}",
@@ -195,7 +553,7 @@ pub fn derive_de_json_named(
                     proxified_t,
                     default_val.unwrap_or_else(|| String::from("None"))
                 ));
-            } else if container_attr_default || default_val.is_some() {
+            } else if container_attr_default || default_val.is_some() || skip_if {
                 unwraps.push(format!(
                     "{{if let Some(t) = {} {{ {} }} else {{ {} }} }}",
                     localvar,
@@ -204,13 +562,49 @@ pub fn derive_de_json_named(
                 ));
             } else {
                 unwraps.push(format!(
-                    "{{if let Some(t) = {} {{ {} }} else {{return Err(s.err_nf(\"{}\"))}} }}",
-                    localvar, proxified_t, struct_fieldname
+                    "{{if let Some(t) = {} {{ {} }} else {{return Err(s.err_nf_keys(\"{}\", &[{}]))}} }}",
+                    localvar, proxified_t, json_fieldname, expected_keys
                 ));
             }
-            matches.push((json_fieldname.clone(), localvar.clone()));
+            let mut pattern = format!("\"{}\"", json_fieldname);
+            for alias in shared::attrs_aliases(&field.attributes) {
+                pattern.push_str(&format!(" | \"{}\"", alias));
+            }
+            let de_expr = if shared::attrs_tuple_defaults(&field.attributes) {
+                format!(
+                    "<{}::TupleDefaults<{}> as {}::DeJson>::de_json(s, i) ?.0",
+                    crate_name,
+                    field.ty.full(),
+                    crate_name
+                )
+            } else if let Some(function) = shared::attrs_deserialize_with(&field.attributes) {
+                format!("{}(s, i) ?", function)
+            } else if let Some(dyn_trait) = tagged_trait(field) {
+                let tagged = format!(
+                    "<{} as {}::JsonTaggedTrait>::de_json_tagged(s, i) ?",
+                    dyn_trait, crate_name
+                );
+                if field.ty.base() == "Option" {
+                    format!(
+                        "if s.tok == {}::DeJsonTok::Null {{ s.next_tok(i) ?; None }} else {{ Some({}) }}",
+                        crate_name, tagged
+                    )
+                } else {
+                    tagged
+                }
+            } else if nested_option(field) && proxy.is_none() {
+                // `null` is `Some(None)`, only a missing key is `None`
+                format!("Some({}::DeJson::de_json(s, i) ?)", crate_name)
+            } else {
+                format!("{}::DeJson::de_json(s, i) ?", crate_name)
+            };
+            matches.push((pattern, localvar.clone(), de_expr));
             local_vars.push(localvar);
         } else {
+            if !shared::attrs_skip(&field.attributes) {
+                // still written, so its key is known even with `deny_unknown_fields`
+                ignored.push(format!("\"{}\"", json_fieldname));
+            }
             unwraps.push(default_val.unwrap_or_else(|| String::from("Default::default()")));
         }
 
@@ -222,31 +616,56 @@ pub fn derive_de_json_named(
     for local_var in &local_vars {
         l!(r, "let mut {} = None;", local_var);
     }
+    if has_flatten {
+        l!(
+            r,
+            "let mut __nserde_flatten = ::core::default::Default::default();"
+        );
+    }
     l!(r, "s.curly_open(i) ?;");
-    l!(r, "while let Some(_) = s.next_str() {");
+    l!(r, "while let Some(_) = s.next_key() {");
 
-    if !json_field_names.is_empty() {
+    if !json_field_names.is_empty() || deny_unknown_fields {
         l!(r, "match AsRef::<str>::as_ref(&s.strbuf) {");
-        for (json_field_name, local_var) in matches.iter() {
+        for (pattern, local_var, de_expr) in matches.iter() {
             l!(
                 r,
-                "\"{}\" => {{s.next_colon(i) ?;{} = Some({}::DeJson::de_json(s, i) ?)}},",
-                json_field_name,
+                "{} => {{s.next_colon(i) ?;{} = Some({})}},",
+                pattern,
                 local_var,
-                crate_name
+                de_expr
+            );
+        }
+        for pattern in &ignored {
+            l!(
+                r,
+                "{} => {{s.next_colon(i)?; s.whole_field(i)?; }},",
+                pattern
+            );
+        }
+        if has_flatten {
+            l!(r, "_ => s.capture_field(i, &mut __nserde_flatten)?,");
+        } else if deny_unknown_fields {
+            l!(
+                r,
+                "_ => return ::core::result::Result::Err(s.err_exp_keys(&s.strbuf, &[{}])),",
+                expected_keys
             );
+        } else {
+            l!(r, "_ => {s.next_colon(i)?; s.whole_field(i)?; }");
         }
-        // TODO: maybe introduce "exhaustive" attribute?
-        // l!(
-        //     r,
-        //     "_ => return ::core::result::Result::Err(s.err_exp(&s.strbuf))"
-        // );
-        l!(r, "_ => {s.next_colon(i)?; s.whole_field(i)?; }");
         l!(r, "}");
     }
     l!(r, "s.eat_comma_curly(i) ?");
     l!(r, "}");
     l!(r, "s.curly_close(i) ?;");
+    if has_flatten {
+        l!(
+            r,
+            "{}::DeJsonState::finish_capture(&mut __nserde_flatten);",
+            crate_name
+        );
+    }
     l!(r, "{} {{", name);
     for (field_name, unwrap) in struct_field_names.iter().zip(unwraps.iter()) {
         l!(r, "{}: {},", field_name, unwrap);
@@ -271,23 +690,75 @@ pub fn derive_de_json_proxy(proxy_type: &str, type_: &str, crate_name: &str) ->
     .unwrap()
 }
 
+pub fn derive_ser_json_with(module: &str, type_: &str, crate_name: &str) -> TokenStream {
+    format!(
+        "impl {}::SerJson for {} {{
+            fn ser_json(&self, d: usize, s: &mut {}::SerJsonState) {{
+                {}::ser_json(self, d, s)
+            }}
+        }}",
+        crate_name, type_, crate_name, module
+    )
+    .parse()
+    .unwrap()
+}
+
+pub fn derive_de_json_with(module: &str, type_: &str, crate_name: &str) -> TokenStream {
+    format!(
+        "impl {}::DeJson for {} {{
+            fn de_json(s: &mut {}::DeJsonState, i: &mut core::str::Chars) -> ::core::result::Result<Self, {}::DeJsonErr> {{
+                {}::de_json(s, i)
+            }}
+        }}",
+        crate_name, type_, crate_name, crate_name, module
+    )
+    .parse()
+    .unwrap()
+}
+
 pub fn derive_de_json_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
-    let body = derive_de_json_named(
-        struct_
-            .name
-            .as_ref()
-            .expect("Cannot implement for anonymous struct"),
-        shared::attrs_default(&struct_.attributes).is_some()
-            || shared::attrs_default_with(&struct_.attributes).is_some(),
-        &struct_.fields[..],
-        crate_name,
-    );
+    let body = if shared::attrs_transparent(&struct_.attributes) {
+        match shared::transparent_field(struct_) {
+            Ok(index) => shared::struct_with_one_field(
+                struct_,
+                index,
+                &format!("{}::DeJson::de_json(s, i)?", crate_name),
+            )
+            .parse()
+            .unwrap(),
+            Err(err) => return err,
+        }
+    } else {
+        let defaults = shared::attrs_default(&struct_.attributes).is_some()
+            || shared::attrs_default_with(&struct_.attributes).is_some();
+        let body = derive_de_json_named(
+            struct_
+                .name
+                .as_ref()
+                .expect("Cannot implement for anonymous struct"),
+            defaults,
+            shared::attrs_deny_unknown_fields(&struct_.attributes),
+            &struct_.fields[..],
+            crate_name,
+        );
+        if defaults {
+            format!(
+                "{} {}",
+                shared::container_default_assertions(&struct_.fields),
+                body
+            )
+            .parse()
+            .unwrap()
+        } else {
+            body
+        }
+    };
     let (generic_w_bounds, generic_no_bounds) =
         struct_bounds_strings(struct_, "DeJson", crate_name);
 
     format!(
         "impl{} {}::DeJson for {}{} {{
-            #[allow(clippy::ignored_unit_patterns)]
+            #[allow(clippy::ignored_unit_patterns, clippy::question_mark)]
             fn de_json(s: &mut {}::DeJsonState, i: &mut core::str::Chars) -> ::core::result::Result<Self,
             {}::DeJsonErr> {{
                 ::core::result::Result::Ok({{ {} }})
@@ -297,7 +768,19 @@ pub fn derive_de_json_struct(struct_: &Struct, crate_name: &str) -> TokenStream
 }
 
 pub fn derive_ser_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
+    if let Some(tag) = shared::attrs_tag(&enum_.attributes) {
+        return derive_ser_json_enum_tagged(enum_, &tag, crate_name);
+    }
+    if shared::attrs_untagged(&enum_.attributes) {
+        return derive_ser_json_enum_untagged(enum_, crate_name);
+    }
+    if shared::attrs_array_tagged(&enum_.attributes) {
+        return derive_ser_json_enum_array_tagged(enum_, crate_name);
+    }
+
     let mut r = String::new();
+    let unit_as_object = shared::attrs_unit_as_object(&enum_.attributes);
+    let tuple_as_array = shared::attrs_tuple_variant_as_array(&enum_.attributes);
 
     for variant in enum_.variants.iter() {
         let field_name = variant.field_name.clone().unwrap();
@@ -311,12 +794,25 @@ pub fn derive_ser_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 ..
             } => {
                 // unit variant
-                l!(
-                    r,
-                    "Self::{} => s.label(\"{}\"),",
-                    &field_name,
-                    json_variant_name
-                );
+                if unit_as_object {
+                    l!(
+                        r,
+                        "Self::{} => {{
+                                s.out.push('{{');
+                                s.label(\"{}\");
+                                s.out.push_str(\":null}}\");
+                            }}",
+                        &field_name,
+                        json_variant_name
+                    );
+                } else {
+                    l!(
+                        r,
+                        "Self::{} => s.label(\"{}\"),",
+                        &field_name,
+                        json_variant_name
+                    );
+                }
             }
 
             Type {
@@ -328,39 +824,29 @@ pub fn derive_ser_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 let last = contents.fields.len().saturating_sub(1);
                 for (index, field) in contents.fields.iter().enumerate() {
                     if let Some(name) = &&field.field_name {
-                        let proxied_field = ser_proxy_guard(name, field);
+                        let field_value = ser_json_field_value(name, field, crate_name);
                         if index == last {
                             if field.ty.base() == "Option" {
                                 l!(
                                     items,
-                                    "if {}.is_some(){{s.field(d+1, \"{}\");{}.ser_json(d+1, s);}}",
+                                    "if {}.is_some(){{s.field(d+1, \"{}\");{}}}",
                                     name,
                                     name,
-                                    proxied_field
+                                    field_value
                                 )
                             } else {
-                                l!(
-                                    items,
-                                    "s.field(d+1, \"{}\");{}.ser_json(d+1, s);",
-                                    name,
-                                    proxied_field
-                                )
+                                l!(items, "s.field(d+1, \"{}\");{}", name, field_value)
                             }
                         } else if field.ty.base() == "Option" {
-                            l!(
-                                    items,
-                                    "if {}.is_some(){{s.field(d+1, \"{}\");{}.ser_json(d+1, s);s.conl();}}",
-                                    name,
-                                    name,
-                                    proxied_field
-                                );
-                        } else {
                             l!(
                                 items,
-                                "s.field(d+1, \"{}\");{}.ser_json(d+1, s);s.conl();",
+                                "if {}.is_some(){{s.field(d+1, \"{}\");{}s.conl();}}",
                                 name,
-                                proxied_field
+                                name,
+                                field_value
                             );
+                        } else {
+                            l!(items, "s.field(d+1, \"{}\");{}s.conl();", name, field_value);
                         }
                         field_names.push(name.clone());
                     }
@@ -389,15 +875,34 @@ pub fn derive_ser_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 let mut names = Vec::new();
                 let mut inner = String::new();
                 let last = contents.len() - 1;
-                for (index, _) in contents.iter().enumerate() {
+                for (index, ty) in contents.iter().enumerate() {
                     let field_name = format!("f{}", index);
-                    names.push(field_name.clone());
+                    let value =
+                        ser_json_tuple_value(&field_name, tuple_attributes(variant, index), ty);
+                    names.push(field_name);
                     if index != last {
-                        l!(inner, "{}.ser_json(d, s); s.out.push(',');", field_name);
+                        l!(inner, "{}.ser_json(d, s); s.conl();", value);
                     } else {
-                        l!(inner, "{}.ser_json(d, s);", field_name);
+                        l!(inner, "{}.ser_json(d, s);", value);
                     }
                 }
+                if tuple_as_array {
+                    l!(
+                        r,
+                        "Self::{} ({}) => {{
+                                s.out.push('[');
+                                s.label(\"{}\");
+                                s.conl();
+                                {}
+                                s.out.push(']');
+                            }}",
+                        &field_name,
+                        names.join(","),
+                        json_variant_name,
+                        inner
+                    );
+                    continue;
+                }
                 l!(
                     r,
                     "Self::{}  ({}) => {{
@@ -437,9 +942,22 @@ pub fn derive_ser_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
 }
 
 pub fn derive_de_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
+    if let Some(tag) = shared::attrs_tag(&enum_.attributes) {
+        return derive_de_json_enum_tagged(enum_, &tag, crate_name);
+    }
+    if shared::attrs_untagged(&enum_.attributes) {
+        return derive_de_json_enum_untagged(enum_, crate_name);
+    }
+    if shared::attrs_array_tagged(&enum_.attributes) {
+        return derive_de_json_enum_array_tagged(enum_, crate_name);
+    }
+
     let mut r_units = String::new();
     let mut r_rest = String::new();
+    let mut r_arrays = String::new();
     let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "DeJson", crate_name);
+    let unit_as_object = shared::attrs_unit_as_object(&enum_.attributes);
+    let tuple_as_array = shared::attrs_tuple_variant_as_array(&enum_.attributes);
 
     for variant in &enum_.variants {
         let field_name = variant.field_name.clone().unwrap();
@@ -459,6 +977,15 @@ pub fn derive_de_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                     json_variant_name,
                     &field_name
                 );
+                if unit_as_object {
+                    l!(
+                        r_rest,
+                        "\"{}\" => {{<() as {}::DeJson>::de_json(s, i)?; Self::{}}},",
+                        json_variant_name,
+                        crate_name,
+                        &field_name
+                    );
+                }
             }
             Type {
                 ident: Category::AnonymousStruct { contents },
@@ -467,6 +994,7 @@ pub fn derive_de_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 let body = derive_de_json_named(
                     &format!("Self::{}", &field_name),
                     false,
+                    variant_deny_unknown_fields(enum_, variant),
                     &contents.fields,
                     crate_name,
                 );
@@ -477,12 +1005,23 @@ pub fn derive_de_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 ..
             } => {
                 let mut field_names = String::new();
-                for _ in contents.iter() {
+                for (index, ty) in contents.iter().enumerate() {
+                    field_names.push_str(&de_json_tuple_position(
+                        tuple_attributes(variant, index),
+                        ty,
+                        true,
+                        crate_name,
+                    ));
+                }
+                if tuple_as_array {
                     l!(
-                        field_names,
-                        "{{let r = {}::DeJson::de_json(s,i)?;s.eat_comma_block(i)?;r}},",
-                        crate_name
+                        r_arrays,
+                        "\"{}\" => Self::{}({}),",
+                        json_variant_name,
+                        &field_name,
+                        field_names
                     );
+                    continue;
                 }
                 l!(
                     r_rest,
@@ -500,7 +1039,7 @@ pub fn derive_de_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
 
     let mut r = format!(
         "impl{} {}::DeJson for {}{} {{
-            #[allow(clippy::ignored_unit_patterns)]
+            #[allow(clippy::ignored_unit_patterns, clippy::question_mark)]
             fn de_json(s: &mut {}::DeJsonState, i: &mut core::str::Chars) -> ::core::result::Result<Self, {}::DeJsonErr> {{
                 match s.tok {{",
         generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, crate_name
@@ -524,6 +1063,29 @@ pub fn derive_de_json_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
         ))
     }
 
+    // the tag is taken out of `strbuf` before the fields, which may be strings themselves
+    if !r_arrays.is_empty() {
+        r.push_str(&format!(
+            "
+                    {}::DeJsonTok::BlockOpen => {{
+                        s.block_open(i)?;
+                        s.string(i)?;
+                        let __nserde_tag = ::core::mem::take(&mut s.strbuf);
+                        match s.tok {{
+                            {}::DeJsonTok::Comma => s.next_tok(i)?,
+                            _ => return ::core::result::Result::Err(s.err_token(\",\")),
+                        }}
+                        let r = match __nserde_tag.as_ref() {{
+                            {}
+                            _ => return ::core::result::Result::Err(s.err_enum(&__nserde_tag))
+                        }};
+                        s.block_close(i)?;
+                        ::core::result::Result::Ok(r)
+                    }},",
+            crate_name, crate_name, r_arrays,
+        ))
+    }
+
     if !r_units.is_empty() {
         r.push_str(&format!(
             "
@@ -559,8 +1121,8 @@ pub fn derive_ser_json_struct_unnamed(struct_: &Struct, crate_name: &str) -> Tok
 
     // encode empty struct as {}
     if struct_.fields.is_empty() {
-        l!(body, "s.out.push('}');");
         l!(body, "s.out.push('{');");
+        l!(body, "s.out.push('}');");
     }
     // if its a newtype struct and it should be transparent - skip any curles
     // and skip "container"
@@ -601,6 +1163,30 @@ pub fn derive_ser_json_struct_unnamed(struct_: &Struct, crate_name: &str) -> Tok
     .unwrap()
 }
 
+/// Deserializes one position of a tuple struct or variant, followed by its comma
+/// if `comma`. A position with a default may be missing from the end of the array.
+fn de_json_tuple_position(
+    attributes: &[crate::parse::Attribute],
+    ty: &Type,
+    comma: bool,
+    crate_name: &str,
+) -> String {
+    let mut de = format!(
+        "let r = {};",
+        de_json_tuple_value(attributes, ty, crate_name)
+    );
+    if comma {
+        de.push_str("s.eat_comma_block(i)?;");
+    }
+    match shared::default_val(attributes, ty) {
+        Some(default) => format!(
+            "{{if s.tok == {}::DeJsonTok::BlockClose {{ {} }} else {{ {} r }} }},",
+            crate_name, default, de
+        ),
+        None => format!("{{ {} r }},", de),
+    }
+}
+
 pub fn derive_de_json_struct_unnamed(struct_: &Struct, crate_name: &str) -> TokenStream {
     let mut body = String::new();
     let (generic_w_bounds, generic_no_bounds) =
@@ -608,13 +1194,13 @@ pub fn derive_de_json_struct_unnamed(struct_: &Struct, crate_name: &str) -> Toke
 
     let transparent = shared::attrs_transparent(&struct_.attributes);
 
-    for _ in &struct_.fields {
-        l!(body, "{{ let r = {}::DeJson::de_json(s, i)?;", crate_name);
-        if struct_.fields.len() != 1 {
-            l!(body, "  s.eat_comma_block(i)?;");
-        }
-        l!(body, "  r");
-        l!(body, "},");
+    for field in &struct_.fields {
+        body.push_str(&de_json_tuple_position(
+            &field.attributes,
+            &field.ty,
+            struct_.fields.len() != 1,
+            crate_name,
+        ));
     }
 
     // no fields - was encoded as {}
@@ -646,3 +1232,513 @@ pub fn derive_de_json_struct_unnamed(struct_: &Struct, crate_name: &str) -> Toke
         }}",generic_w_bounds, crate_name, struct_.name.as_ref().expect("Cannot implement for anonymous struct"), generic_no_bounds, crate_name, crate_name,body
     ).parse().unwrap()
 }
+
+/// The pattern matching `variant` and the code serializing its content alone,
+/// without the variant name, as done by untagged and array tagged enums.
+fn ser_json_variant_content(variant: &Field, crate_name: &str) -> (String, String) {
+    let field_name = variant.field_name.clone().unwrap();
+
+    match &variant.ty {
+        Type {
+            wraps: None,
+            ident: Category::None,
+            ..
+        } => (
+            format!("Self::{}", field_name),
+            "s.out.push_str(\"null\");".to_string(),
+        ),
+        Type {
+            ident: Category::AnonymousStruct { contents },
+            ..
+        } => {
+            let mut field_names = vec![];
+            let mut items = String::new();
+            l!(items, "let mut first_field_was_serialized = false;");
+            for field in &contents.fields {
+                let name = field.field_name.clone().unwrap();
+                let json_fieldname =
+                    shared::attrs_rename(&field.attributes).unwrap_or_else(|| name.clone());
+                let field_value = ser_json_field_value(&name, field, crate_name);
+                let guard = if field.ty.base() == "Option" {
+                    format!("if {}.is_some()", name)
+                } else {
+                    String::new()
+                };
+                l!(
+                    items,
+                    "{} {{
+                        if first_field_was_serialized {{
+                            s.conl();
+                        }}
+                        first_field_was_serialized = true;
+                        s.field(d+1, \"{}\");
+                        {}
+                    }}",
+                    guard,
+                    json_fieldname,
+                    field_value
+                );
+                field_names.push(name);
+            }
+            (
+                format!("Self::{} {{ {} }}", field_name, field_names.join(",")),
+                format!("s.st_pre(); {} s.st_post(d);", items),
+            )
+        }
+        Type {
+            ident: Category::Tuple { contents },
+            ..
+        } if contents.len() == 1 => (
+            format!("Self::{}(f0)", field_name),
+            format!(
+                "{}.ser_json(d, s);",
+                ser_json_tuple_value("f0", tuple_attributes(variant, 0), &contents[0])
+            ),
+        ),
+        Type {
+            ident: Category::Tuple { contents },
+            ..
+        } => {
+            let names = (0..contents.len())
+                .map(|index| format!("f{}", index))
+                .collect::<Vec<_>>();
+            let inner = names
+                .iter()
+                .zip(contents)
+                .enumerate()
+                .map(|(index, (name, ty))| {
+                    format!(
+                        "{}.ser_json(d, s);",
+                        ser_json_tuple_value(name, tuple_attributes(variant, index), ty)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("s.conl();");
+            (
+                format!("Self::{}({})", field_name, names.join(",")),
+                format!("s.out.push('['); {} s.out.push(']');", inner),
+            )
+        }
+        v => {
+            unimplemented!("Unexpected type in enum: {:?}", v)
+        }
+    }
+}
+
+/// The attributes of the position `index` of the tuple `variant`.
+fn tuple_attributes(variant: &Field, index: usize) -> &[crate::parse::Attribute] {
+    variant.tuple_attributes.get(index).map_or(&[], |a| &a[..])
+}
+
+/// Whether unknown fields of the struct `variant` are an error, either for the
+/// whole enum or just this variant.
+fn variant_deny_unknown_fields(enum_: &Enum, variant: &Field) -> bool {
+    shared::attrs_deny_unknown_fields(&enum_.attributes)
+        || shared::attrs_deny_unknown_fields(&variant.attributes)
+}
+
+/// The code deserializing the content written by `ser_json_variant_content` into `variant`.
+fn de_json_variant_content(enum_: &Enum, variant: &Field, crate_name: &str) -> String {
+    let field_name = variant.field_name.clone().unwrap();
+
+    match &variant.ty {
+        Type {
+            wraps: None,
+            ident: Category::None,
+            ..
+        } => {
+            format!(
+                "<() as {}::DeJson>::de_json(s, i)?; Self::{}",
+                crate_name, field_name
+            )
+        }
+        Type {
+            ident: Category::AnonymousStruct { contents },
+            ..
+        } => derive_de_json_named(
+            &format!("Self::{}", &field_name),
+            false,
+            variant_deny_unknown_fields(enum_, variant),
+            &contents.fields,
+            crate_name,
+        )
+        .to_string(),
+        Type {
+            ident: Category::Tuple { contents },
+            ..
+        } if contents.len() == 1 => {
+            format!(
+                "Self::{}({})",
+                field_name,
+                de_json_tuple_value(tuple_attributes(variant, 0), &contents[0], crate_name)
+            )
+        }
+        Type {
+            ident: Category::Tuple { contents },
+            ..
+        } => {
+            let mut fields = String::new();
+            for (index, ty) in contents.iter().enumerate() {
+                fields.push_str(&de_json_tuple_position(
+                    tuple_attributes(variant, index),
+                    ty,
+                    true,
+                    crate_name,
+                ));
+            }
+            format!(
+                "s.block_open(i)?; let r = Self::{}({}); s.block_close(i)?; r",
+                field_name, fields
+            )
+        }
+        v => {
+            unimplemented!("Unexpected type in enum: {:?}", v)
+        }
+    }
+}
+
+fn derive_ser_json_enum_untagged(enum_: &Enum, crate_name: &str) -> TokenStream {
+    let mut r = String::new();
+    let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "SerJson", crate_name);
+
+    for variant in &enum_.variants {
+        let (pattern, content) = ser_json_variant_content(variant, crate_name);
+        l!(r, "{} => {{ {} }},", pattern, content);
+    }
+
+    format!(
+        "
+        impl{} {}::SerJson for {}{} {{
+            fn ser_json(&self, d: usize, s: &mut {}::SerJsonState) {{
+                match self {{
+                    {}
+                }}
+            }}
+        }}",
+        generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, r
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_de_json_enum_untagged(enum_: &Enum, crate_name: &str) -> TokenStream {
+    let mut r = String::new();
+    let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "DeJson", crate_name);
+
+    for variant in &enum_.variants {
+        let body = de_json_variant_content(enum_, variant, crate_name);
+
+        // every variant gets its own state over the captured value, so a
+        // failed attempt doesn't affect the next one
+        l!(
+            r,
+            "let __nserde_variant = s.parse_captured(
+                &__nserde_value,
+                |s: &mut {}::DeJsonState, i: &mut core::str::Chars| -> ::core::result::Result<Self, {}::DeJsonErr> {{
+                    ::core::result::Result::Ok({{ {} }})
+                }},
+            );
+            if let ::core::result::Result::Ok(value) = __nserde_variant {{
+                return ::core::result::Result::Ok(value);
+            }}",
+            crate_name,
+            crate_name,
+            body
+        );
+    }
+
+    format!(
+        "impl{} {}::DeJson for {}{} {{
+            #[allow(clippy::ignored_unit_patterns, clippy::question_mark)]
+            fn de_json(s: &mut {}::DeJsonState, i: &mut core::str::Chars) -> ::core::result::Result<Self, {}::DeJsonErr> {{
+                let mut __nserde_value = ::core::default::Default::default();
+                s.capture_value(i, &mut __nserde_value)?;
+                {}
+                ::core::result::Result::Err(s.err_parse(\"any variant of untagged enum {}\"))
+            }}
+        }}",
+        generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, crate_name, r, enum_.name
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_ser_json_enum_array_tagged(enum_: &Enum, crate_name: &str) -> TokenStream {
+    let mut r = String::new();
+    let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "SerJson", crate_name);
+
+    for variant in &enum_.variants {
+        let field_name = variant.field_name.clone().unwrap();
+        let json_variant_name = shared::attrs_rename(&variant.attributes).unwrap_or(field_name);
+        let (pattern, content) = ser_json_variant_content(variant, crate_name);
+        l!(
+            r,
+            "{} => {{
+                s.out.push('[');
+                s.label(\"{}\");
+                s.conl();
+                {}
+                s.out.push(']');
+            }},",
+            pattern,
+            json_variant_name,
+            content
+        );
+    }
+
+    format!(
+        "
+        impl{} {}::SerJson for {}{} {{
+            fn ser_json(&self, d: usize, s: &mut {}::SerJsonState) {{
+                match self {{
+                    {}
+                }}
+            }}
+        }}",
+        generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, r
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_de_json_enum_array_tagged(enum_: &Enum, crate_name: &str) -> TokenStream {
+    let mut r = String::new();
+    let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "DeJson", crate_name);
+
+    for variant in &enum_.variants {
+        let field_name = variant.field_name.clone().unwrap();
+        let json_variant_name = shared::attrs_rename(&variant.attributes).unwrap_or(field_name);
+        l!(
+            r,
+            "\"{}\" => {{ {} }},",
+            json_variant_name,
+            de_json_variant_content(enum_, variant, crate_name)
+        );
+    }
+
+    // the tag is taken out of `strbuf` before the content, which may be a string itself
+    format!(
+        "impl{} {}::DeJson for {}{} {{
+            #[allow(clippy::ignored_unit_patterns, clippy::question_mark)]
+            fn de_json(s: &mut {}::DeJsonState, i: &mut core::str::Chars) -> ::core::result::Result<Self, {}::DeJsonErr> {{
+                s.block_open(i)?;
+                s.string(i)?;
+                let __nserde_tag = ::core::mem::take(&mut s.strbuf);
+                match s.tok {{
+                    {}::DeJsonTok::Comma => s.next_tok(i)?,
+                    _ => return ::core::result::Result::Err(s.err_token(\",\")),
+                }}
+                let r = match __nserde_tag.as_ref() {{
+                    {}
+                    _ => return ::core::result::Result::Err(s.err_enum(&__nserde_tag)),
+                }};
+                s.block_close(i)?;
+                ::core::result::Result::Ok(r)
+            }}
+        }}",
+        generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, crate_name, crate_name, r
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_ser_json_enum_tagged(enum_: &Enum, tag: &str, crate_name: &str) -> TokenStream {
+    let mut r = String::new();
+    let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "SerJson", crate_name);
+
+    for variant in enum_.variants.iter() {
+        let field_name = variant.field_name.clone().unwrap();
+        let json_variant_name =
+            shared::attrs_rename(&variant.attributes).unwrap_or(field_name.clone());
+
+        match &variant.ty {
+            Type {
+                wraps: None,
+                ident: Category::None,
+                ..
+            } => {
+                l!(
+                    r,
+                    "Self::{} => {{
+                        s.st_pre();
+                        s.field(d+1, \"{}\");
+                        s.label(\"{}\");
+                        s.st_post(d);
+                    }},",
+                    field_name,
+                    tag,
+                    json_variant_name
+                );
+            }
+            Type {
+                ident: Category::AnonymousStruct { contents },
+                ..
+            } => {
+                let mut field_names = vec![];
+                let mut items = String::new();
+                for field in &contents.fields {
+                    let name = field.field_name.clone().unwrap();
+                    let json_fieldname =
+                        shared::attrs_rename(&field.attributes).unwrap_or_else(|| name.clone());
+                    let field_value = ser_json_field_value(&name, field, crate_name);
+                    if field.ty.base() == "Option" {
+                        l!(
+                            items,
+                            "if {}.is_some() {{ s.conl(); s.field(d+1, \"{}\"); {} }}",
+                            name,
+                            json_fieldname,
+                            field_value
+                        );
+                    } else {
+                        l!(
+                            items,
+                            "s.conl(); s.field(d+1, \"{}\"); {}",
+                            json_fieldname,
+                            field_value
+                        );
+                    }
+                    field_names.push(name);
+                }
+                l!(
+                    r,
+                    "Self::{} {{ {} }} => {{
+                        s.st_pre();
+                        s.field(d+1, \"{}\");
+                        s.label(\"{}\");
+                        {}
+                        s.st_post(d);
+                    }},",
+                    field_name,
+                    field_names.join(","),
+                    tag,
+                    json_variant_name,
+                    items
+                );
+            }
+            Type {
+                ident: Category::Tuple { .. },
+                ..
+            } => {
+                return format!(
+                    "compile_error!(\"#[nserde(tag = \\\"{}\\\")] does not support the tuple variant {}::{}\");",
+                    tag, enum_.name, field_name
+                )
+                .parse()
+                .unwrap();
+            }
+            v => {
+                unimplemented!("Unexpected type in enum: {:?}", v)
+            }
+        }
+    }
+
+    format!(
+        "
+        impl{} {}::SerJson for {}{} {{
+            fn ser_json(&self, d: usize, s: &mut {}::SerJsonState) {{
+                match self {{
+                    {}
+                }}
+            }}
+        }}",
+        generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, r
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_de_json_enum_tagged(enum_: &Enum, tag: &str, crate_name: &str) -> TokenStream {
+    let mut r = String::new();
+    let (generic_w_bounds, generic_no_bounds) = enum_bounds_strings(enum_, "DeJson", crate_name);
+
+    for variant in &enum_.variants {
+        let field_name = variant.field_name.clone().unwrap();
+        let json_variant_name =
+            shared::attrs_rename(&variant.attributes).unwrap_or(field_name.clone());
+
+        match &variant.ty {
+            Type {
+                wraps: None,
+                ident: Category::None,
+                ..
+            } => {
+                l!(r, "\"{}\" => Self::{},", json_variant_name, field_name);
+            }
+            Type {
+                ident: Category::AnonymousStruct { contents },
+                ..
+            } => {
+                let body = derive_de_json_named(
+                    &format!("Self::{}", &field_name),
+                    false,
+                    variant_deny_unknown_fields(enum_, variant),
+                    &contents.fields,
+                    crate_name,
+                );
+                // the remaining fields were captured while looking for the
+                // tag, deserialize them from the captured object
+                l!(
+                    r,
+                    "\"{}\" => s.parse_captured(
+                        &__nserde_fields,
+                        |s: &mut {}::DeJsonState, i: &mut core::str::Chars| -> ::core::result::Result<Self, {}::DeJsonErr> {{
+                            ::core::result::Result::Ok({{ {} }})
+                        }},
+                    )?,",
+                    json_variant_name,
+                    crate_name,
+                    crate_name,
+                    body
+                );
+            }
+            Type {
+                ident: Category::Tuple { .. },
+                ..
+            } => {
+                return format!(
+                    "compile_error!(\"#[nserde(tag = \\\"{}\\\")] does not support the tuple variant {}::{}\");",
+                    tag, enum_.name, field_name
+                )
+                .parse()
+                .unwrap();
+            }
+            v => {
+                unimplemented!("Unexpected type in enum: {:?}", v)
+            }
+        }
+    }
+
+    format!(
+        "impl{} {}::DeJson for {}{} {{
+            #[allow(clippy::ignored_unit_patterns, clippy::question_mark)]
+            fn de_json(s: &mut {}::DeJsonState, i: &mut core::str::Chars) -> ::core::result::Result<Self, {}::DeJsonErr> {{
+                let mut __nserde_tag = None;
+                let mut __nserde_fields = ::core::default::Default::default();
+                s.curly_open(i)?;
+                while let Some(_) = s.next_key() {{
+                    if AsRef::<str>::as_ref(&s.strbuf) == \"{}\" {{
+                        s.next_colon(i)?;
+                        __nserde_tag = Some(s.as_string()?);
+                        s.next_tok(i)?;
+                    }} else {{
+                        s.capture_field(i, &mut __nserde_fields)?;
+                    }}
+                    s.eat_comma_curly(i)?;
+                }}
+                s.curly_close(i)?;
+                {}::DeJsonState::finish_capture(&mut __nserde_fields);
+                let __nserde_tag = match __nserde_tag {{
+                    Some(tag) => tag,
+                    None => return ::core::result::Result::Err(s.err_nf(\"{}\")),
+                }};
+                ::core::result::Result::Ok(match AsRef::<str>::as_ref(&__nserde_tag) {{
+                    {}
+                    _ => return ::core::result::Result::Err(s.err_enum(&__nserde_tag)),
+                }})
+            }}
+        }}",
+        generic_w_bounds, crate_name, enum_.name, generic_no_bounds, crate_name, crate_name, tag, crate_name, tag, r
+    )
+    .parse()
+    .unwrap()
+}
@@ -36,17 +36,111 @@ pub fn derive_de_ron_proxy(proxy_type: &str, type_: &str, crate_name: &str) -> T
     .unwrap()
 }
 
+pub fn derive_ser_ron_with(module: &str, type_: &str, crate_name: &str) -> TokenStream {
+    format!(
+        "impl {}::SerRon for {} {{
+            fn ser_ron(&self, d: usize, s: &mut {}::SerRonState) {{
+                {}::ser_ron(self, d, s)
+            }}
+        }}",
+        crate_name, type_, crate_name, module
+    )
+    .parse()
+    .unwrap()
+}
+
+pub fn derive_de_ron_with(module: &str, type_: &str, crate_name: &str) -> TokenStream {
+    format!(
+        "impl {}::DeRon for {} {{
+            fn de_ron(s: &mut {}::DeRonState, i: &mut core::str::Chars) -> ::core::result::Result<Self, {}::DeRonErr> {{
+                {}::de_ron(s, i)
+            }}
+        }}",
+        crate_name, type_, crate_name, crate_name, module
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_ser_ron_struct_transparent(struct_: &Struct, crate_name: &str) -> TokenStream {
+    let index = match shared::transparent_field(struct_) {
+        Ok(index) => index,
+        Err(err) => return err,
+    };
+    let (generic_w_bounds, generic_no_bounds) =
+        shared::struct_bounds_strings(struct_, "SerRon", crate_name);
+    format!(
+        "
+        impl{} {}::SerRon for {}{} {{
+            fn ser_ron(&self, d: usize, s: &mut {}::SerRonState) {{
+                self.{}.ser_ron(d, s);
+            }}
+        }}",
+        generic_w_bounds,
+        crate_name,
+        struct_
+            .name
+            .as_ref()
+            .expect("Cannot implement for anonymous struct"),
+        generic_no_bounds,
+        crate_name,
+        shared::struct_field_access(struct_, index)
+    )
+    .parse()
+    .unwrap()
+}
+
+fn derive_de_ron_struct_transparent(struct_: &Struct, crate_name: &str) -> TokenStream {
+    let index = match shared::transparent_field(struct_) {
+        Ok(index) => index,
+        Err(err) => return err,
+    };
+    let (generic_w_bounds, generic_no_bounds) =
+        shared::struct_bounds_strings(struct_, "DeRon", crate_name);
+    format!(
+        "
+        impl{} {}::DeRon for {}{} {{
+            fn de_ron(s: &mut {}::DeRonState, i: &mut core::str::Chars) -> ::core::result::Result<Self,{}::DeRonErr> {{
+                ::core::result::Result::Ok({})
+            }}
+        }}",
+        generic_w_bounds,
+        crate_name,
+        struct_
+            .name
+            .as_ref()
+            .expect("Cannot implement for anonymous struct"),
+        generic_no_bounds,
+        crate_name,
+        crate_name,
+        shared::struct_with_one_field(
+            struct_,
+            index,
+            &format!("{}::DeRon::de_ron(s, i)?", crate_name)
+        )
+    )
+    .parse()
+    .unwrap()
+}
+
 pub fn derive_ser_ron_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
+    if shared::attrs_transparent(&struct_.attributes) {
+        return derive_ser_ron_struct_transparent(struct_, crate_name);
+    }
+
     let mut s = String::new();
 
     for field in &struct_.fields {
         let struct_fieldname = field.field_name.clone().unwrap();
         let ron_fieldname =
             shared::attrs_rename(&field.attributes).unwrap_or_else(|| struct_fieldname.clone());
-        let skip = shared::attrs_skip(&field.attributes);
-        if skip {
+        if shared::attrs_skip_serializing(&field.attributes) {
             continue;
         }
+        let skip_if = shared::attrs_skip_serializing_if(&field.attributes);
+        if let Some(predicate) = &skip_if {
+            l!(s, "if !{}(&self.{}) {{", predicate, struct_fieldname);
+        }
         if field.ty.base() == "Option" {
             l!(
                 s,
@@ -68,6 +162,9 @@ pub fn derive_ser_ron_struct(struct_: &Struct, crate_name: &str) -> TokenStream
                 struct_fieldname
             );
         }
+        if skip_if.is_some() {
+            l!(s, "}");
+        }
     }
 
     format!(
@@ -93,14 +190,18 @@ pub fn derive_ser_ron_struct(struct_: &Struct, crate_name: &str) -> TokenStream
 }
 
 pub fn derive_ser_ron_struct_unnamed(struct_: &Struct, crate_name: &str) -> TokenStream {
+    if shared::attrs_transparent(&struct_.attributes) {
+        return derive_ser_ron_struct_transparent(struct_, crate_name);
+    }
+
     let mut body = String::new();
 
-    let last = struct_.fields.len() - 1;
+    // a unit struct is written as `()`
     for (n, _) in struct_.fields.iter().enumerate() {
-        l!(body, "self.{}.ser_ron(d, s);", n);
-        if n != last {
+        if n != 0 {
             l!(body, "s.out.push_str(\", \");");
         }
+        l!(body, "self.{}.ser_ron(d, s);", n);
     }
     format!(
         "
@@ -131,7 +232,7 @@ pub fn derive_de_ron_named(
 ) -> String {
     let mut local_vars = Vec::new();
     let mut struct_field_names = Vec::new();
-    let mut ron_field_names = Vec::new();
+    let mut ron_field_patterns = Vec::new();
 
     let container_attr_default = shared::attrs_default(attributes).is_some();
 
@@ -139,31 +240,13 @@ pub fn derive_de_ron_named(
     for field in fields {
         let struct_fieldname = field.field_name.as_ref().unwrap().to_string();
         let localvar = format!("_{}", struct_fieldname);
-        let field_attr_default = shared::attrs_default(&field.attributes);
-        let field_attr_default_with = shared::attrs_default_with(&field.attributes);
-        let default_val = if let Some(v) = field_attr_default {
-            if let Some(mut val) = v {
-                if field.ty.base() == "String" {
-                    val = format!("\"{}\".to_string()", val)
-                }
-                if field.ty.base() == "Option" {
-                    val = format!("Some({})", val);
-                }
-                Some(val)
-            } else if field.ty.base() != "Option" {
-                Some(String::from("Default::default()"))
-            } else {
-                Some(String::from("None"))
-            }
-        } else if let Some(mut v) = field_attr_default_with {
-            v.push_str("()");
-            Some(v)
-        } else {
-            None
-        };
+        let default_val = shared::default_val(&field.attributes, &field.ty);
         let ron_fieldname =
             shared::attrs_rename(&field.attributes).unwrap_or(struct_fieldname.clone());
-        let skip = crate::shared::attrs_skip(&field.attributes);
+        let skip = shared::attrs_skip_deserializing(&field.attributes);
+        // a conditionally skipped or never written field may be missing from the input
+        let skip_if = shared::attrs_skip_serializing_if(&field.attributes).is_some()
+            || shared::attrs_skip_serializing(&field.attributes);
 
         if !skip {
             if field.ty.base() == "Option" {
@@ -178,7 +261,7 @@ pub fn derive_de_ron_named(
                     localvar,
                     default_val.unwrap_or_else(|| String::from("None"))
                 ));
-            } else if container_attr_default || default_val.is_some() {
+            } else if container_attr_default || default_val.is_some() || skip_if {
                 unwraps.push(format!(
                     "{{
                         if let Some(t) = {} {{
@@ -209,8 +292,13 @@ pub fn derive_de_ron_named(
             ));
         }
 
+        let mut pattern = format!("\"{}\"", ron_fieldname);
+        for alias in shared::attrs_aliases(&field.attributes) {
+            pattern.push_str(&format!(" | \"{}\"", alias));
+        }
+
         struct_field_names.push(struct_fieldname);
-        ron_field_names.push(ron_fieldname);
+        ron_field_patterns.push(pattern);
         local_vars.push((localvar, field.ty.full()));
     }
 
@@ -225,16 +313,16 @@ pub fn derive_de_ron_named(
         )
     }
 
-    let match_names = if !ron_field_names.is_empty() {
+    let match_names = if !ron_field_patterns.is_empty() {
         let mut inner = String::new();
-        for (ron_field_name, (local_var, _)) in ron_field_names.iter().zip(local_vars.iter()) {
+        for (pattern, (local_var, _)) in ron_field_patterns.iter().zip(local_vars.iter()) {
             l!(
                 inner,
-                "\"{}\" => {{
+                "{} => {{
                     s.next_colon(i)?;
                     {} = Some({}::DeRon::de_ron(s, i)?)
                 }},",
-                ron_field_name,
+                pattern,
                 local_var,
                 crate_name
             );
@@ -242,7 +330,10 @@ pub fn derive_de_ron_named(
         format!(
             "match s.identbuf.as_ref() {{
                 {}
-                _ => return ::core::result::Result::Err(s.err_exp(&s.identbuf))
+                _ => {{
+                    s.next_colon(i)?;
+                    s.skip_value(i)?;
+                }}
             }}",
             inner
         )
@@ -260,7 +351,7 @@ pub fn derive_de_ron_named(
         "{{
             {}
             s.paren_open(i)?;
-            while let Some(_) = s.next_ident() {{
+            while let Some(_) = s.next_key() {{
                 {}
                 s.eat_comma_paren(i)?;
             }};
@@ -274,7 +365,11 @@ pub fn derive_de_ron_named(
 }
 
 pub fn derive_de_ron_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
-    let body = derive_de_ron_named(
+    if shared::attrs_transparent(&struct_.attributes) {
+        return derive_de_ron_struct_transparent(struct_, crate_name);
+    }
+
+    let mut body = derive_de_ron_named(
         struct_
             .name
             .as_ref()
@@ -283,9 +378,17 @@ pub fn derive_de_ron_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
         &struct_.attributes,
         crate_name,
     );
+    if shared::attrs_default(&struct_.attributes).is_some() {
+        body = format!(
+            "{{ {} {} }}",
+            shared::container_default_assertions(&struct_.fields),
+            body
+        );
+    }
 
     format!(
         "impl {}::DeRon for {} {{
+            #[allow(clippy::question_mark)]
             fn de_ron(s: &mut {}::DeRonState, i: &mut core::str::Chars) -> ::core::result::Result<Self,{}::DeRonErr> {{
                 ::core::result::Result::Ok({})
             }}
@@ -294,30 +397,82 @@ pub fn derive_de_ron_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
     .unwrap()
 }
 
+/// The value serialized for the position of a tuple variant bound to `name`,
+/// through its `proxy` if it has one.
+fn ser_ron_tuple_value(name: &str, attributes: &[Attribute], ty: &Type) -> String {
+    match shared::attrs_proxy(attributes) {
+        Some(proxy) if ty.base() == "Option" => {
+            format!("{{{name}.as_ref().map(|f| {{let proxy: {proxy} = Into::into(f);proxy}})}}")
+        }
+        Some(proxy) => format!("{{let proxy: {proxy} = Into::into({name});proxy}}"),
+        None => name.to_string(),
+    }
+}
+
+/// Deserializes the value of a tuple position, through its `proxy` if it has one.
+fn de_ron_tuple_value(attributes: &[Attribute], ty: &Type, crate_name: &str) -> String {
+    match shared::attrs_proxy(attributes) {
+        Some(proxy) if ty.base() == "Option" => format!(
+            "<Option<{proxy}> as {crate_name}::DeRon>::de_ron(s, i)?.as_ref().map(From::<&{proxy}>::from)"
+        ),
+        Some(proxy) => {
+            format!("From::<&{proxy}>::from(&<{proxy} as {crate_name}::DeRon>::de_ron(s, i)?)")
+        }
+        None => format!("{crate_name}::DeRon::de_ron(s, i)?"),
+    }
+}
+
+/// Deserializes one position of a tuple struct or variant followed by its comma.
+/// A position with a default may be missing from the end of the tuple.
+fn de_ron_tuple_position(
+    attributes: &[crate::parse::Attribute],
+    ty: &Type,
+    crate_name: &str,
+) -> String {
+    let de = format!(
+        "let r = {}; s.eat_comma_paren(i)?; r",
+        de_ron_tuple_value(attributes, ty, crate_name)
+    );
+    match shared::default_val(attributes, ty) {
+        Some(default) => format!(
+            "{{if s.tok == {}::DeRonTok::ParenClose {{ {} }} else {{ {} }} }},",
+            crate_name, default, de
+        ),
+        None => format!("{{ {} }},", de),
+    }
+}
+
 pub fn derive_de_ron_struct_unnamed(struct_: &Struct, crate_name: &str) -> TokenStream {
+    if shared::attrs_transparent(&struct_.attributes) {
+        return derive_de_ron_struct_transparent(struct_, crate_name);
+    }
+
     let mut body = String::new();
 
-    for _ in &struct_.fields {
-        l!(
-            body,
-            "{{
-                let r = {}::DeRon::de_ron(s, i)?;
-                s.eat_comma_paren(i)?;
-                r
-            }},",
-            crate_name
-        );
+    for field in &struct_.fields {
+        body.push_str(&de_ron_tuple_position(
+            &field.attributes,
+            &field.ty,
+            crate_name,
+        ));
     }
 
+    // a unit struct can't be constructed with `Self()`
+    let r = if struct_.fields.is_empty() {
+        "Self".to_string()
+    } else {
+        format!("Self({})", body)
+    };
+
     format! ("
         impl {}::DeRon for {} {{
             fn de_ron(s: &mut {}::DeRonState, i: &mut core::str::Chars) -> ::core::result::Result<Self,{}::DeRonErr> {{
                 s.paren_open(i)?;
-                let r = Self({});
+                let r = {};
                 s.paren_close(i)?;
                 ::core::result::Result::Ok(r)
             }}
-        }}", crate_name, struct_.name.as_ref().expect("Cannot implement for anonymous struct"), crate_name, crate_name, body
+        }}", crate_name, struct_.name.as_ref().expect("Cannot implement for anonymous struct"), crate_name, crate_name, r
     ).parse().unwrap()
 }
 
@@ -387,9 +542,15 @@ pub fn derive_ser_ron_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 let mut names = Vec::new();
                 let mut inner = String::new();
                 let last = contents.len() - 1;
-                for (index, _) in &mut contents.iter().enumerate() {
+                for (index, ty) in contents.iter().enumerate() {
                     let name = format!("f{}", index);
-                    l!(inner, "{}.ser_ron(d, s);", name);
+                    let attributes: &[Attribute] =
+                        variant.tuple_attributes.get(index).map_or(&[], |a| &a[..]);
+                    l!(
+                        inner,
+                        "{}.ser_ron(d, s);",
+                        ser_ron_tuple_value(&name, attributes, ty)
+                    );
                     if index != last {
                         l!(inner, "s.out.push_str(\", \");")
                     }
@@ -456,16 +617,12 @@ pub fn derive_de_ron_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
                 ..
             } => {
                 let mut inner = String::new();
-                for _ in contents.iter() {
-                    l!(
-                        inner,
-                        "{{
-                            let r = {}::DeRon::de_ron(s, i)?;
-                            s.eat_comma_paren(i)?;
-                            r
-                        }}, ",
-                        crate_name
-                    )
+                for (index, ty) in contents.iter().enumerate() {
+                    inner.push_str(&de_ron_tuple_position(
+                        variant.tuple_attributes.get(index).map_or(&[], |a| &a[..]),
+                        ty,
+                        crate_name,
+                    ));
                 }
 
                 l!(
@@ -489,6 +646,7 @@ pub fn derive_de_ron_enum(enum_: &Enum, crate_name: &str) -> TokenStream {
 
     format! ("
         impl {}::DeRon for {} {{
+            #[allow(clippy::question_mark)]
             fn de_ron(s: &mut {}::DeRonState, i: &mut core::str::Chars) -> ::core::result::Result<Self,{}::DeRonErr> {{
                 // we are expecting an identifier
                 s.ident(i)?;
@@ -0,0 +1,164 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::parse::Struct;
+use crate::shared::{self, struct_bounds_strings};
+
+use proc_macro::TokenStream;
+
+pub fn derive_ser_toml_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
+    let (generic_w_bounds, generic_no_bounds) =
+        struct_bounds_strings(struct_, "SerToml", crate_name);
+    let struct_name = struct_
+        .name
+        .as_ref()
+        .expect("Cannot implement for anonymous struct");
+
+    // TOML wants all the plain `key = value` lines of a table before any of
+    // its nested `[section]`s, so the fields are walked twice
+    let mut values = String::new();
+    let mut sections = String::new();
+    for field in &struct_.fields {
+        if shared::attrs_skip_serializing(&field.attributes) {
+            continue;
+        }
+        let struct_fieldname = field.field_name.clone().unwrap();
+        let toml_fieldname =
+            shared::attrs_rename(&field.attributes).unwrap_or_else(|| struct_fieldname.clone());
+
+        l!(
+            values,
+            "if !self.{}.is_toml_table() && !self.{}.is_toml_table_array() && !self.{}.is_toml_none() {{
+                s.key(\"{}\");
+                self.{}.ser_toml(s);
+                s.out.push('\\n');
+            }}",
+            struct_fieldname,
+            struct_fieldname,
+            struct_fieldname,
+            toml_fieldname,
+            struct_fieldname
+        );
+        l!(
+            sections,
+            "if self.{}.is_toml_table() {{
+                s.begin_section(\"{}\");
+                self.{}.ser_toml(s);
+                s.end_section();
+            }} else if self.{}.is_toml_table_array() {{
+                s.begin_table_array(\"{}\");
+                self.{}.ser_toml(s);
+                s.end_section();
+            }}",
+            struct_fieldname,
+            toml_fieldname,
+            struct_fieldname,
+            struct_fieldname,
+            toml_fieldname,
+            struct_fieldname
+        );
+    }
+
+    format!(
+        "
+        impl{} {}::SerToml for {}{} {{
+            fn ser_toml(&self, s: &mut {}::SerTomlState) {{
+                {}
+                {}
+            }}
+
+            fn is_toml_table(&self) -> bool {{
+                true
+            }}
+        }}
+    ",
+        generic_w_bounds, crate_name, struct_name, generic_no_bounds, crate_name, values, sections
+    )
+    .parse()
+    .unwrap()
+}
+
+pub fn derive_de_toml_struct(struct_: &Struct, crate_name: &str) -> TokenStream {
+    let (generic_w_bounds, generic_no_bounds) =
+        struct_bounds_strings(struct_, "DeToml", crate_name);
+    let struct_name = struct_
+        .name
+        .as_ref()
+        .expect("Cannot implement for anonymous struct");
+    let container_attr_default = shared::attrs_default(&struct_.attributes).is_some();
+
+    let assertions = if container_attr_default {
+        shared::container_default_assertions(&struct_.fields)
+    } else {
+        String::new()
+    };
+
+    let mut body = String::new();
+    for field in &struct_.fields {
+        let struct_fieldname = field.field_name.clone().unwrap();
+        if shared::attrs_skip_deserializing(&field.attributes) {
+            l!(body, "{}: Default::default(),", struct_fieldname);
+            continue;
+        }
+        let toml_fieldname =
+            shared::attrs_rename(&field.attributes).unwrap_or_else(|| struct_fieldname.clone());
+
+        let default_val = shared::default_val(&field.attributes, &field.ty).or_else(|| {
+            // a field that is never written may be missing from the input
+            (container_attr_default || shared::attrs_skip_serializing(&field.attributes))
+                .then(|| String::from("Default::default()"))
+        });
+
+        let de_field = format!(
+            "{}::DeToml::de_toml(toml, &{}::toml_key(key, \"{}\"))?",
+            crate_name, crate_name, toml_fieldname
+        );
+        match default_val {
+            Some(default_val) => l!(
+                body,
+                "{}: if <{} as {}::DeToml>::is_toml_present(toml, &{}::toml_key(key, \"{}\")) {{
+                    {}
+                }} else {{
+                    {}
+                }},",
+                struct_fieldname,
+                field.ty.full(),
+                crate_name,
+                crate_name,
+                toml_fieldname,
+                de_field,
+                default_val
+            ),
+            None => l!(body, "{}: {},", struct_fieldname, de_field),
+        }
+    }
+
+    format!(
+        "
+        impl{} {}::DeToml for {}{} {{
+            fn de_toml(toml: &{}::TomlTable, key: &str) -> ::core::result::Result<Self, {}::TomlErr> {{
+                {}
+                ::core::result::Result::Ok(Self {{
+                    {}
+                }})
+            }}
+
+            fn is_toml_present(toml: &{}::TomlTable, key: &str) -> bool {{
+                {}::toml_table_present(toml, key)
+            }}
+        }}
+    ",
+        generic_w_bounds,
+        crate_name,
+        struct_name,
+        generic_no_bounds,
+        crate_name,
+        crate_name,
+        assertions,
+        body,
+        crate_name,
+        crate_name
+    )
+    .parse()
+    .unwrap()
+}
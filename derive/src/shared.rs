@@ -1,12 +1,25 @@
-#![cfg(any(feature = "json", feature = "ron", feature = "binary"))]
+#![cfg(any(
+    feature = "json",
+    feature = "ron",
+    feature = "binary",
+    feature = "toml"
+))]
 
-#[cfg(any(feature = "json", feature = "binary"))]
-use alloc::{format, string::ToString, vec::Vec};
+use alloc::{format, string::ToString};
+
+use alloc::vec::Vec;
 
 use alloc::string::String;
 
 #[cfg(any(feature = "binary", feature = "json"))]
-use crate::parse::{Enum, Struct};
+use crate::parse::Enum;
+#[cfg(any(
+    feature = "binary",
+    feature = "json",
+    feature = "ron",
+    feature = "toml"
+))]
+use crate::parse::Struct;
 
 macro_rules! l {
     ($target:ident, $line:expr) => {
@@ -18,6 +31,7 @@ macro_rules! l {
     };
 }
 
+#[cfg(any(feature = "json", feature = "ron", feature = "binary"))]
 pub fn attrs_proxy(attributes: &[crate::parse::Attribute]) -> Option<String> {
     attributes.iter().find_map(|attr| {
         if attr.tokens.len() == 2 && attr.tokens[0] == "proxy" {
@@ -28,7 +42,75 @@ pub fn attrs_proxy(attributes: &[crate::parse::Attribute]) -> Option<String> {
     })
 }
 
-#[cfg(any(feature = "ron", feature = "json"))]
+#[cfg(any(feature = "json", feature = "ron", feature = "binary"))]
+pub fn attrs_with(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 2 && attr.tokens[0] == "with" {
+            Some(attr.tokens[1].clone())
+        } else {
+            None
+        }
+    })
+}
+
+/// Rejects `#[nserde(rename = "")]` on the container, its fields or its variants,
+/// returning the `compile_error!` to emit instead of the impl.
+pub fn check_renames(input: &crate::parse::Data) -> Option<proc_macro::TokenStream> {
+    use crate::parse::{Category, Data, Field};
+
+    fn fields_ok(fields: &[Field]) -> bool {
+        fields.iter().all(|field| {
+            attrs_ok(&field.attributes)
+                && match &field.ty.ident {
+                    Category::AnonymousStruct { contents } => fields_ok(&contents.fields),
+                    _ => true,
+                }
+        })
+    }
+
+    fn attrs_ok(attributes: &[crate::parse::Attribute]) -> bool {
+        !attributes.iter().any(|attr| {
+            attr.tokens.len() == 2 && attr.tokens[0] == "rename" && attr.tokens[1].is_empty()
+        })
+    }
+
+    let ok = attrs_ok(input.attributes())
+        && match input {
+            Data::Struct(struct_) => fields_ok(&struct_.fields),
+            Data::Enum(enum_) => fields_ok(&enum_.variants),
+            Data::Union(_) => true,
+        };
+    if ok {
+        None
+    } else {
+        Some(
+            format!(
+                "compile_error!(\"#[nserde(rename = \\\"\\\")] on {} can't be empty\");",
+                input.name()
+            )
+            .parse()
+            .unwrap(),
+        )
+    }
+}
+
+/// With a container `#[nserde(default)]` every field without its own default is
+/// filled with `Default::default()`. Returns statements asserting that the types of
+/// these fields implement `Default`, so a missing impl is reported for the field type.
+#[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
+pub fn container_default_assertions(fields: &[crate::parse::Field]) -> String {
+    let mut r = String::from("fn _assert_default<T: ::core::default::Default>() {}");
+    for field in fields.iter().filter(|field| {
+        attrs_default(&field.attributes).is_none()
+            && attrs_default_with(&field.attributes).is_none()
+            && attrs_default_const(&field.attributes).is_none()
+    }) {
+        r.push_str(&format!("_assert_default::<{}>();", field.ty.full()));
+    }
+    r
+}
+
+#[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
 pub fn attrs_rename(attributes: &[crate::parse::Attribute]) -> Option<String> {
     attributes.iter().find_map(|attr| {
         if attr.tokens.len() == 2 && attr.tokens[0] == "rename" {
@@ -40,6 +122,15 @@ pub fn attrs_rename(attributes: &[crate::parse::Attribute]) -> Option<String> {
 }
 
 #[cfg(any(feature = "ron", feature = "json"))]
+pub fn attrs_aliases(attributes: &[crate::parse::Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attr| attr.tokens.len() == 2 && attr.tokens[0] == "alias")
+        .map(|attr| attr.tokens[1].clone())
+        .collect()
+}
+
+#[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
 pub fn attrs_default(attributes: &[crate::parse::Attribute]) -> Option<Option<String>> {
     attributes.iter().find_map(|attr| {
         if attr.tokens.len() == 1 && attr.tokens[0] == "default" {
@@ -52,7 +143,52 @@ pub fn attrs_default(attributes: &[crate::parse::Attribute]) -> Option<Option<St
     })
 }
 
-#[cfg(any(feature = "ron", feature = "json"))]
+/// The value of a field or tuple position with `#[nserde(default)]`, `default = ""`,
+/// `default = const { .. }` or `default_with = ""`, if it has one of them.
+#[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
+pub fn default_val(
+    attributes: &[crate::parse::Attribute],
+    ty: &crate::parse::Type,
+) -> Option<String> {
+    if let Some(v) = attrs_default(attributes) {
+        if let Some(mut val) = v {
+            if ty.base() == "String"
+                || ty
+                    .wraps
+                    .as_ref()
+                    .is_some_and(|wrapped| wrapped.iter().any(|ty| ty.base() == "String"))
+            {
+                val = format!("\"{}\".to_string()", val)
+            }
+            if ty.base() == "Option" {
+                val = format!("Some({})", val);
+            }
+            Some(val)
+        } else if ty.base() != "Option" {
+            Some(String::from("Default::default()"))
+        } else {
+            Some(String::from("None"))
+        }
+    } else if let Some(expr) = attrs_default_const(attributes) {
+        Some(format!("({})", expr))
+    } else {
+        attrs_default_with(attributes).map(|f| format!("{}()", f))
+    }
+}
+
+/// The expression of `#[nserde(default = const { EXPR })]`, emitted as is.
+#[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
+pub fn attrs_default_const(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 3 && attr.tokens[0] == "default" && attr.tokens[1] == "const" {
+            Some(attr.tokens[2].clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(any(feature = "ron", feature = "json", feature = "toml"))]
 pub fn attrs_default_with(attributes: &[crate::parse::Attribute]) -> Option<String> {
     attributes.iter().find_map(|attr| {
         if attr.tokens.len() == 2 && attr.tokens[0] == "default_with" {
@@ -64,19 +200,144 @@ pub fn attrs_default_with(attributes: &[crate::parse::Attribute]) -> Option<Stri
 }
 
 #[cfg(feature = "json")]
+pub fn attrs_introspect(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "introspect")
+}
+
+#[cfg(any(feature = "json", feature = "ron"))]
 pub fn attrs_transparent(attributes: &[crate::parse::Attribute]) -> bool {
     attributes
         .iter()
         .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "transparent")
 }
 
+/// The index of the only non-skipped field of a `#[nserde(transparent)]` struct,
+/// or the `compile_error!` to emit when there isn't exactly one.
 #[cfg(any(feature = "json", feature = "ron"))]
+pub fn transparent_field(struct_: &Struct) -> Result<usize, proc_macro::TokenStream> {
+    let mut fields = struct_
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !attrs_skip(&field.attributes));
+    match (fields.next(), fields.next()) {
+        (Some((index, _)), None) => Ok(index),
+        _ => Err(format!(
+            "compile_error!(\"#[nserde(transparent)] requires {} to have exactly one non-skipped field\");",
+            struct_.name.as_deref().unwrap_or("the struct")
+        )
+        .parse()
+        .unwrap()),
+    }
+}
+
+/// How to access field `index` of `self` in a struct.
+#[cfg(any(feature = "json", feature = "ron"))]
+pub fn struct_field_access(struct_: &Struct, index: usize) -> String {
+    struct_.fields[index]
+        .field_name
+        .clone()
+        .unwrap_or_else(|| index.to_string())
+}
+
+/// Builds a struct whose field `index` is set to `value` and all other fields to their default.
+#[cfg(any(feature = "json", feature = "ron"))]
+pub fn struct_with_one_field(struct_: &Struct, index: usize, value: &str) -> String {
+    let values = struct_.fields.iter().enumerate().map(|(n, field)| {
+        let value = if n == index {
+            value
+        } else {
+            "::core::default::Default::default()"
+        };
+        match &field.field_name {
+            Some(name) => format!("{}: {}", name, value),
+            None => value.to_string(),
+        }
+    });
+    let values = values.collect::<Vec<_>>().join(", ");
+    if struct_.named {
+        format!("Self {{ {} }}", values)
+    } else {
+        format!("Self({})", values)
+    }
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "binary"))]
+pub fn attrs_skip_serializing_if(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 2 && attr.tokens[0] == "skip_serializing_if" {
+            Some(attr.tokens[1].clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_serialize_with(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 2 && attr.tokens[0] == "serialize_with" {
+            Some(attr.tokens[1].clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_deserialize_with(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 2 && attr.tokens[0] == "deserialize_with" {
+            Some(attr.tokens[1].clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(any(feature = "json", feature = "ron", feature = "binary"))]
 pub fn attrs_skip(attributes: &[crate::parse::Attribute]) -> bool {
     attributes
         .iter()
         .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "skip")
 }
 
+/// `#[nserde(skip)]` or `#[nserde(skip_serializing)]`, the field is never written.
+#[cfg(any(
+    feature = "json",
+    feature = "ron",
+    feature = "toml",
+    feature = "binary"
+))]
+pub fn attrs_skip_serializing(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes.iter().any(|attr| {
+        attr.tokens.len() == 1 && (attr.tokens[0] == "skip" || attr.tokens[0] == "skip_serializing")
+    })
+}
+
+/// `#[nserde(skip)]` or `#[nserde(skip_deserializing)]`, the field is never read.
+#[cfg(any(
+    feature = "json",
+    feature = "ron",
+    feature = "toml",
+    feature = "binary"
+))]
+pub fn attrs_skip_deserializing(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes.iter().any(|attr| {
+        attr.tokens.len() == 1
+            && (attr.tokens[0] == "skip" || attr.tokens[0] == "skip_deserializing")
+    })
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_sort_keys(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "sort_keys")
+}
+
 #[cfg(feature = "json")]
 pub fn attrs_serialize_none_as_null(attributes: &[crate::parse::Attribute]) -> bool {
     attributes
@@ -84,6 +345,148 @@ pub fn attrs_serialize_none_as_null(attributes: &[crate::parse::Attribute]) -> b
         .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "serialize_none_as_null")
 }
 
+#[cfg(feature = "json")]
+pub fn attrs_flatten(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "flatten")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_deny_unknown_fields(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "deny_unknown_fields")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_untagged(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "untagged")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_array_tagged(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "array_tagged")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_tuple_variant_as_array(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "tuple_variant_as_array")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_unit_as_object(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "unit_as_object")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_tuple_defaults(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "tuple_defaults")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_tagged_trait(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "tagged_trait")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_float_as_string(attributes: &[crate::parse::Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attr| attr.tokens.len() == 1 && attr.tokens[0] == "float_as_string")
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_duration(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 2 && attr.tokens[0] == "duration" {
+            Some(attr.tokens[1].clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_tag(attributes: &[crate::parse::Attribute]) -> Option<String> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens.len() == 2 && attr.tokens[0] == "tag" {
+            Some(attr.tokens[1].clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(feature = "json")]
+pub fn attrs_groups(attributes: &[crate::parse::Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attr| attr.tokens.len() == 2 && attr.tokens[0] == "group")
+        .map(|attr| attr.tokens[1].clone())
+        .collect()
+}
+
+/// The `u16` each variant of `enum_` is written as in binary, the `#[nserde(bin_tag = N)]`
+/// of the variant or else its index. A tag that isn't a `u16` or is used twice returns
+/// the `compile_error!` to emit instead of the impl.
+#[cfg(feature = "binary")]
+pub fn bin_tags(enum_: &Enum) -> Result<Vec<u16>, proc_macro::TokenStream> {
+    let error = |msg: String| -> proc_macro::TokenStream {
+        format!("compile_error!(\"{}\");", msg).parse().unwrap()
+    };
+    let mut tags: Vec<u16> = Vec::new();
+    for (index, variant) in enum_.variants.iter().enumerate() {
+        let name = variant.field_name.as_deref().unwrap_or_default();
+        let tag = match variant
+            .attributes
+            .iter()
+            .find(|attr| attr.tokens.len() == 2 && attr.tokens[0] == "bin_tag")
+        {
+            Some(attr) => attr.tokens[1].parse::<u16>().map_err(|_| {
+                error(format!(
+                    "#[nserde(bin_tag)] of {}::{} must be an integer from 0 to 65535",
+                    enum_.name, name
+                ))
+            })?,
+            None => index as u16,
+        };
+        if tags.contains(&tag) {
+            return Err(error(format!(
+                "{}::{} has the binary tag {} of another variant, pin it with a unique #[nserde(bin_tag)]",
+                enum_.name, name, tag
+            )));
+        }
+        tags.push(tag);
+    }
+    Ok(tags)
+}
+
+/// `Some(true)` for `#[nserde(unchecked_utf8 = "unsafe")]`, `Some(false)` for
+/// any other spelling of the attribute, which lacks the acknowledgement.
+#[cfg(feature = "binary")]
+pub fn attrs_unchecked_utf8(attributes: &[crate::parse::Attribute]) -> Option<bool> {
+    attributes.iter().find_map(|attr| {
+        if attr.tokens[0] == "unchecked_utf8" {
+            Some(attr.tokens.len() == 2 && attr.tokens[1] == "unsafe")
+        } else {
+            None
+        }
+    })
+}
+
 pub fn attrs_crate(attributes: &[crate::parse::Attribute]) -> Option<&str> {
     attributes.iter().find_map(|attr| {
         if attr.tokens.len() == 2 && attr.tokens[0] == "crate" {
@@ -94,7 +497,12 @@ pub fn attrs_crate(attributes: &[crate::parse::Attribute]) -> Option<&str> {
     })
 }
 
-#[cfg(any(feature = "binary", feature = "json"))]
+#[cfg(any(
+    feature = "binary",
+    feature = "json",
+    feature = "ron",
+    feature = "toml"
+))]
 pub(crate) fn struct_bounds_strings(
     struct_: &Struct,
     bound_name: &str,
@@ -10,18 +10,104 @@
 //! The main difference with "serde" and the reason why "nanoserde" is possible: there is no intermediate data model
 //! For each serialisation datatype there is a special macro.
 //!
-//! Derive macros available: `DeJson`, `SerJson`, `DeBin`, `SerBin`, `DeRon`, `SerRon`
+//! Derive macros available: `DeJson`, `SerJson`, `DeBin`, `SerBin`, `DeRon`, `SerRon`, `DeToml`, `SerToml`
 //!
 //! `nanoserde` supports some serialization customisation with `#[nserde()]` attributes.
 //! For `#[nserde(..)]` supported attributes for each format check [Features support matrix](https://github.com/not-fl3/nanoserde#features-support-matrix)
+//!
+//! Misconfigured attributes are rejected when deriving, for example an empty rename:
+//!
+//! ```rust,compile_fail
+//! use nanoserde::SerJson;
+//!
+//! #[derive(SerJson)]
+//! struct Property {
+//!     #[nserde(rename = "")]
+//!     name: String,
+//! }
+//! ```
+//!
+//! So is a container `#[nserde(default)]` with a field type that has no `Default`, unless
+//! that field is given its own `#[nserde(default = "")]` or `#[nserde(default_with = "")]`:
+//!
+//! ```rust,compile_fail
+//! use nanoserde::DeJson;
+//! use std::num::NonZeroU32;
+//!
+//! #[derive(DeJson)]
+//! #[nserde(default)]
+//! struct Entity {
+//!     name: String,
+//!     id: NonZeroU32,
+//! }
+//! ```
+//!
+//! Or two variants written with the same binary tag:
+//!
+//! ```rust,compile_fail
+//! use nanoserde::SerBin;
+//!
+//! #[derive(SerBin)]
+//! enum Message {
+//!     Ping,
+//!     #[nserde(bin_tag = 0)]
+//!     Pong,
+//! }
+//! ```
+//!
+//! Or `#[nserde(deny_unknown_fields)]` on a struct with a flattened field, which takes
+//! every key the other fields don't:
+//!
+//! ```rust,compile_fail
+//! use nanoserde::DeJson;
+//!
+//! #[derive(DeJson)]
+//! struct Point {
+//!     x: i32,
+//! }
+//!
+//! #[derive(DeJson)]
+//! #[nserde(deny_unknown_fields)]
+//! struct Entity {
+//!     id: u32,
+//!     #[nserde(flatten)]
+//!     position: Point,
+//! }
+//! ```
+//!
+//! Or a field attribute on a type it doesn't support:
+//!
+//! ```rust,compile_fail
+//! use nanoserde::SerJson;
+//!
+//! #[derive(SerJson)]
+//! struct Reading {
+//!     #[nserde(float_as_string)]
+//!     value: u32,
+//! }
+//! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 
-#[cfg(any(feature = "binary", feature = "json", feature = "ron"))]
+#[cfg(any(
+    feature = "binary",
+    feature = "json",
+    feature = "ron",
+    feature = "toml"
+))]
 pub use nanoserde_derive::*;
 
+mod time;
+pub use crate::time::*;
+
+/// Used by the code `json_tagged_trait!` expands to.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::boxed::Box;
+}
+
 #[cfg(feature = "binary")]
 mod serde_bin;
 #[cfg(feature = "binary")]
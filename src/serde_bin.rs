@@ -6,10 +6,14 @@ use core::error::Error;
 #[cfg(feature = "std")]
 use std::error::Error;
 
-use alloc::borrow::ToOwned;
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
-use alloc::collections::{BTreeMap, BTreeSet, LinkedList};
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::format;
+use alloc::rc::Rc;
 use alloc::string::String;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
 /// A trait for objects that can be serialized to binary.
@@ -18,11 +22,29 @@ pub trait SerBin {
     ///
     /// This is a convenient wrapper around `ser_bin`.
     fn serialize_bin(&self) -> Vec<u8> {
-        let mut s = Vec::new();
+        let mut s = Vec::with_capacity(self.ser_bin_len());
         self.ser_bin(&mut s);
         s
     }
 
+    /// Serialize Self to bytes, appending to `output`.
+    ///
+    /// Unlike `serialize_bin` no new buffer is allocated, so clearing and
+    /// reusing one buffer saves allocations across repeated serializations.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let mut out = Vec::new();
+    /// 1u16.serialize_bin_into(&mut out);
+    /// out.clear();
+    /// 2u16.serialize_bin_into(&mut out);
+    /// assert_eq!(out, [2, 0]);
+    /// ```
+    fn serialize_bin_into(&self, output: &mut Vec<u8>) {
+        output.reserve(self.ser_bin_len());
+        self.ser_bin(output);
+    }
+
     /// Serialize Self to bytes.
     ///
     /// ```rust
@@ -32,6 +54,34 @@ pub trait SerBin {
     /// assert_eq!(s, vec![42, 0, 0, 0])
     /// ```
     fn ser_bin(&self, output: &mut Vec<u8>);
+
+    /// Estimated number of bytes `ser_bin` writes, used by `serialize_bin` to
+    /// reserve the output up front. The default of 0 means no estimate.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let v = vec![1u32, 2, 3];
+    /// let mut s = Vec::with_capacity(v.ser_bin_len());
+    /// v.ser_bin(&mut s);
+    /// assert_eq!(s.len(), v.ser_bin_len());
+    /// ```
+    fn ser_bin_len(&self) -> usize {
+        0
+    }
+
+    /// Serialize a slice of Self back to back, without a length prefix.
+    ///
+    /// Used by the slice, array and `Vec` impls, so that `u8` can copy the
+    /// whole slice at once instead of pushing byte by byte.
+    #[doc(hidden)]
+    fn ser_bin_slice(items: &[Self], output: &mut Vec<u8>)
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.ser_bin(output);
+        }
+    }
 }
 
 /// A trait for objects that can be deserialized from binary.
@@ -43,6 +93,23 @@ pub trait DeBin: Sized {
         DeBin::de_bin(&mut 0, d)
     }
 
+    /// Parse Self from the start of the input bytes, also returning the number
+    /// of bytes used. Handy for decoding values packed back to back.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let bytes = [1, 0, 0, 0, 2, 0];
+    /// let (one, used) = u32::deserialize_bin_with_offset(&bytes).unwrap();
+    /// assert_eq!((one, used), (1, 4));
+    /// let (two, _) = u16::deserialize_bin_with_offset(&bytes[used..]).unwrap();
+    /// assert_eq!(two, 2);
+    /// ```
+    fn deserialize_bin_with_offset(d: &[u8]) -> Result<(Self, usize), DeBinErr> {
+        let mut offset = 0;
+        let value = DeBin::de_bin(&mut offset, d)?;
+        Ok((value, offset))
+    }
+
     /// Parse Self from the input bytes starting at index `offset`.
     ///
     /// After deserialization, `offset` is updated to point at the byte after
@@ -57,6 +124,49 @@ pub trait DeBin: Sized {
     /// assert_eq!(offset, 8);
     /// ```
     fn de_bin(offset: &mut usize, bytes: &[u8]) -> Result<Self, DeBinErr>;
+
+    /// Parse `len` values of Self stored back to back, used by the `Vec` impl
+    /// so that `u8` can copy all of them at once.
+    #[doc(hidden)]
+    fn de_bin_vec(len: usize, offset: &mut usize, bytes: &[u8]) -> Result<Vec<Self>, DeBinErr> {
+        let mut out = Vec::with_capacity(len);
+        for _ in 0..len {
+            out.push(DeBin::de_bin(offset, bytes)?)
+        }
+        Ok(out)
+    }
+
+    /// Parse `N` values of Self stored back to back, used by the array impl
+    /// so that `u8` can copy all of them at once.
+    #[doc(hidden)]
+    fn de_bin_array<const N: usize>(o: &mut usize, d: &[u8]) -> Result<[Self; N], DeBinErr> {
+        use core::mem::MaybeUninit;
+
+        // waiting for uninit_array(or for array::try_from_fn) stabilization
+        // https://github.com/rust-lang/rust/issues/96097
+        // https://github.com/rust-lang/rust/issues/89379
+        let mut to: [MaybeUninit<Self>; N] =
+            unsafe { MaybeUninit::<[MaybeUninit<Self>; N]>::uninit().assume_init() };
+
+        for index in 0..N {
+            to[index] = match DeBin::de_bin(o, d) {
+                Ok(v) => MaybeUninit::new(v),
+                Err(e) => {
+                    // drop all the MaybeUninit values which we've already
+                    // successfully deserialized so we don't leak memory.
+                    // See https://github.com/not-fl3/nanoserde/issues/79
+                    for (_, to_drop) in (0..index).zip(to) {
+                        unsafe { to_drop.assume_init() };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        // waiting for array_assume_init or core::array::map optimizations
+        // https://github.com/rust-lang/rust/issues/61956
+        Ok(unsafe { (*(&to as *const _ as *const MaybeUninit<_>)).assume_init_read() })
+    }
 }
 
 /// The error message when failing to deserialize from raw bytes.
@@ -66,21 +176,56 @@ pub struct DeBinErr {
     pub o: usize,
     pub l: usize,
     pub s: usize,
+    /// What went wrong at `o`, in a form that can be matched on.
+    pub reason: DeBinErrReason,
+}
+
+/// The reason raw bytes failed to deserialize.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DeBinErrReason {
+    /// Wanted `l` more bytes but the input is only `s` bytes long.
+    Length { l: usize, s: usize },
+    /// The bytes were read but don't hold a valid value.
+    Range(String),
 }
 
 impl DeBinErr {
+    /// An error about running out of input, wanting `l` bytes at offset `o`
+    /// of an input `s` bytes long.
     pub fn new(o: usize, l: usize, s: usize) -> Self {
-        Self { o, l, s }
+        Self {
+            o,
+            l,
+            s,
+            reason: DeBinErrReason::Length { l, s },
+        }
+    }
+
+    /// An error about an invalid value at offset `o` of an input `s` bytes
+    /// long. No bytes are missing, so `l` is 0.
+    pub fn range(o: usize, s: usize, what: impl Into<String>) -> Self {
+        Self {
+            o,
+            l: 0,
+            s,
+            reason: DeBinErrReason::Range(what.into()),
+        }
     }
 }
 
 impl core::fmt::Debug for DeBinErr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(
-            f,
-            "Bin deserialize error at:{} wanted:{} bytes but max size is {}",
-            self.o, self.l, self.s
-        )
+        match &self.reason {
+            DeBinErrReason::Length { l, s } => write!(
+                f,
+                "Bin deserialize error at:{} wanted:{} bytes but max size is {}",
+                self.o, l, s
+            ),
+            DeBinErrReason::Range(what) => {
+                write!(f, "Bin deserialize error at:{} {}", self.o, what)
+            }
+        }
     }
 }
 
@@ -99,17 +244,17 @@ macro_rules! impl_ser_de_bin_for {
                 let du8 = self.to_le_bytes();
                 s.extend_from_slice(&du8);
             }
+
+            fn ser_bin_len(&self) -> usize {
+                core::mem::size_of::<$ty>()
+            }
         }
 
         impl DeBin for $ty {
             fn de_bin(o: &mut usize, d: &[u8]) -> Result<$ty, DeBinErr> {
                 let l = core::mem::size_of::<$ty>();
                 if *o + l > d.len() {
-                    return Err(DeBinErr {
-                        o: *o,
-                        l,
-                        s: d.len(),
-                    });
+                    return Err(DeBinErr::new(*o, l, d.len()));
                 }
 
                 // We just checked that the correct amount of bytes are available,
@@ -135,12 +280,53 @@ impl_ser_de_bin_for!(u16);
 impl_ser_de_bin_for!(i16);
 impl_ser_de_bin_for!(i8);
 
+macro_rules! impl_ser_de_bin_nonzero {
+    ($($ty:ident($inner:ident)),* $(,)?) => {$(
+        impl SerBin for core::num::$ty {
+            fn ser_bin(&self, s: &mut Vec<u8>) {
+                self.get().ser_bin(s)
+            }
+
+            fn ser_bin_len(&self) -> usize {
+                self.get().ser_bin_len()
+            }
+        }
+
+        impl DeBin for core::num::$ty {
+            fn de_bin(o: &mut usize, d: &[u8]) -> Result<core::num::$ty, DeBinErr> {
+                let start = *o;
+                let val: $inner = DeBin::de_bin(o, d)?;
+                core::num::$ty::new(val)
+                    .ok_or_else(|| DeBinErr::range(start, d.len(), concat!(stringify!($ty), " is zero")))
+            }
+        }
+    )*};
+}
+
+impl_ser_de_bin_nonzero!(
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroUsize(usize),
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128),
+);
+
 impl SerBin for usize {
     fn ser_bin(&self, s: &mut Vec<u8>) {
         let u64usize = *self as u64;
         let du8 = u64usize.to_le_bytes();
         s.extend_from_slice(&du8);
     }
+
+    fn ser_bin_len(&self) -> usize {
+        8
+    }
 }
 
 impl DeBin for usize {
@@ -150,11 +336,7 @@ impl DeBin for usize {
         let m = match d.get(*o..*o + l) {
             Some(data) => u64::from_le_bytes(data.try_into().unwrap()),
             None => {
-                return Err(DeBinErr {
-                    o: *o,
-                    l,
-                    s: d.len(),
-                });
+                return Err(DeBinErr::new(*o, l, d.len()));
             }
         };
 
@@ -163,41 +345,150 @@ impl DeBin for usize {
     }
 }
 
+/// Serialize the length prefix of a collection.
+///
+/// Lengths are written as a `u64`, or with the `varint` feature as a variable
+/// length integer taking 1, 3, 5 or 9 bytes.
+pub fn ser_len(len: usize, s: &mut Vec<u8>) {
+    #[cfg(feature = "varint")]
+    {
+        let len = len as u64;
+        if len < 0xFD {
+            s.push(len as u8);
+        } else if len <= 0xFFFF {
+            s.push(0xFD);
+            (len as u16).ser_bin(s);
+        } else if len <= 0xFFFF_FFFF {
+            s.push(0xFE);
+            (len as u32).ser_bin(s);
+        } else {
+            s.push(0xFF);
+            len.ser_bin(s);
+        }
+    }
+    #[cfg(not(feature = "varint"))]
+    len.ser_bin(s);
+}
+
+/// Number of bytes [`ser_len`] writes for `len`.
+fn len_size(len: usize) -> usize {
+    #[cfg(feature = "varint")]
+    {
+        let len = len as u64;
+        if len < 0xFD {
+            1
+        } else if len <= 0xFFFF {
+            3
+        } else if len <= 0xFFFF_FFFF {
+            5
+        } else {
+            9
+        }
+    }
+    #[cfg(not(feature = "varint"))]
+    {
+        let _ = len;
+        8
+    }
+}
+
+/// Deserialize a collection length prefix written by [`ser_len`].
+pub fn de_len(o: &mut usize, d: &[u8]) -> Result<usize, DeBinErr> {
+    #[cfg(feature = "varint")]
+    {
+        let start = *o;
+        let len = match u8::de_bin(o, d)? {
+            0xFD => u16::de_bin(o, d)? as u64,
+            0xFE => u32::de_bin(o, d)? as u64,
+            0xFF => u64::de_bin(o, d)?,
+            len => len as u64,
+        };
+        len.try_into()
+            .map_err(|_| DeBinErr::range(start, d.len(), "length does not fit in usize"))
+    }
+    #[cfg(not(feature = "varint"))]
+    usize::de_bin(o, d)
+}
+
 impl DeBin for u8 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<u8, DeBinErr> {
         if *o + 1 > d.len() {
-            return Err(DeBinErr {
-                o: *o,
-                l: 1,
-                s: d.len(),
-            });
+            return Err(DeBinErr::new(*o, 1, d.len()));
         }
         let m = d[*o];
         *o += 1;
         Ok(m)
     }
+
+    fn de_bin_vec(len: usize, o: &mut usize, d: &[u8]) -> Result<Vec<u8>, DeBinErr> {
+        match d.get(*o..).and_then(|rest| rest.get(..len)) {
+            Some(bytes) => {
+                *o += len;
+                Ok(bytes.to_vec())
+            }
+            None => Err(DeBinErr::new(*o, len, d.len())),
+        }
+    }
+
+    fn de_bin_array<const N: usize>(o: &mut usize, d: &[u8]) -> Result<[u8; N], DeBinErr> {
+        match d.get(*o..).and_then(|rest| rest.get(..N)) {
+            Some(bytes) => {
+                *o += N;
+                Ok(bytes.try_into().unwrap())
+            }
+            None => Err(DeBinErr::new(*o, N, d.len())),
+        }
+    }
 }
 
 impl SerBin for u8 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
         s.push(*self);
     }
+
+    fn ser_bin_len(&self) -> usize {
+        1
+    }
+
+    fn ser_bin_slice(items: &[u8], output: &mut Vec<u8>) {
+        output.extend_from_slice(items);
+    }
+}
+
+impl SerBin for char {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        u32::from(*self).ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        4
+    }
+}
+
+impl DeBin for char {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<char, DeBinErr> {
+        let start = *o;
+        let scalar = u32::de_bin(o, d)?;
+        char::from_u32(scalar).ok_or_else(|| {
+            DeBinErr::range(start, d.len(), format!("{:#x} is not a valid char", scalar))
+        })
+    }
 }
 
 impl SerBin for bool {
     fn ser_bin(&self, s: &mut Vec<u8>) {
         s.push(if *self { 1 } else { 0 });
     }
+
+    fn ser_bin_len(&self) -> usize {
+        1
+    }
 }
 
 impl DeBin for bool {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<bool, DeBinErr> {
         if *o + 1 > d.len() {
-            return Err(DeBinErr {
-                o: *o,
-                l: 1,
-                s: d.len(),
-            });
+            return Err(DeBinErr::new(*o, 1, d.len()));
         }
         let m = d[*o];
         *o += 1;
@@ -211,47 +502,77 @@ impl DeBin for bool {
 
 impl SerBin for String {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
+        self.as_str().ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.as_str().ser_bin_len()
+    }
+}
+
+impl SerBin for str {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        ser_len(self.len(), s);
         s.extend_from_slice(self.as_bytes());
     }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.len()
+    }
 }
 
 impl DeBin for String {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<String, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
+        let len = de_len(o, d)?;
         if *o + len > d.len() {
-            return Err(DeBinErr {
-                o: *o,
-                l: 1,
-                s: d.len(),
-            });
+            return Err(DeBinErr::new(*o, 1, d.len()));
         }
         let r = match core::str::from_utf8(&d[*o..(*o + len)]) {
             Ok(r) => r.to_owned(),
-            Err(_) => {
-                return Err(DeBinErr {
-                    o: *o,
-                    l: len,
-                    s: d.len(),
-                })
-            }
+            Err(_) => return Err(DeBinErr::range(*o, d.len(), "invalid UTF-8 in string")),
         };
         *o += len;
         Ok(r)
     }
 }
 
+/// Deserialize a `String` without checking that its bytes are valid UTF-8.
+///
+/// This is what `#[nserde(unchecked_utf8 = "unsafe")]` fields use instead of
+/// the `DeBin` impl of `String`, skipping the validation pass for trusted data.
+/// A hand-written `DeBin` impl can call it directly as well.
+///
+/// The derived `DeBin` impl stays safe to call, so by writing the attribute
+/// the caller takes on the contract below for every input that impl is ever
+/// given.
+///
+/// # Safety
+///
+/// The string bytes in `d` must be valid UTF-8, e.g. because they were written
+/// by `SerBin` and can't have been tampered with. Deserializing untrusted input
+/// this way can produce a `String` containing invalid UTF-8, which is
+/// undefined behavior.
+pub unsafe fn de_bin_string_unchecked(o: &mut usize, d: &[u8]) -> Result<String, DeBinErr> {
+    let len = de_len(o, d)?;
+    if *o + len > d.len() {
+        return Err(DeBinErr::new(*o, 1, d.len()));
+    }
+    let r = String::from_utf8_unchecked(d[*o..(*o + len)].to_vec());
+    *o += len;
+    Ok(r)
+}
+
 impl<T> SerBin for Vec<T>
 where
     T: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
-        for item in self {
-            item.ser_bin(s);
-        }
+        ser_len(self.len(), s);
+        T::ser_bin_slice(self, s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.iter().map(SerBin::ser_bin_len).sum::<usize>()
     }
 }
 
@@ -260,12 +581,8 @@ where
     T: DeBin,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<Vec<T>, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
-        let mut out = Vec::with_capacity(len);
-        for _ in 0..len {
-            out.push(DeBin::de_bin(o, d)?)
-        }
-        Ok(out)
+        let len = de_len(o, d)?;
+        T::de_bin_vec(len, o, d)
     }
 }
 
@@ -274,12 +591,15 @@ where
     T: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
+        ser_len(self.len(), s);
         for item in self.iter() {
             item.ser_bin(s);
         }
     }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.iter().map(SerBin::ser_bin_len).sum::<usize>()
+    }
 }
 
 impl<T> DeBin for LinkedList<T>
@@ -287,7 +607,7 @@ where
     T: DeBin,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<LinkedList<T>, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
+        let len = de_len(o, d)?;
         let mut out = LinkedList::new();
         for _ in 0..len {
             out.push_back(DeBin::de_bin(o, d)?)
@@ -296,18 +616,72 @@ where
     }
 }
 
+impl<T> SerBin for VecDeque<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        ser_len(self.len(), s);
+        let (front, back) = self.as_slices();
+        T::ser_bin_slice(front, s);
+        T::ser_bin_slice(back, s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.iter().map(SerBin::ser_bin_len).sum::<usize>()
+    }
+}
+
+impl<T> DeBin for VecDeque<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<VecDeque<T>, DeBinErr> {
+        Vec::de_bin(o, d).map(VecDeque::from)
+    }
+}
+
+/// The items are written in the heap's internal order, not sorted.
+impl<T> SerBin for BinaryHeap<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        ser_len(self.len(), s);
+        for item in self.iter() {
+            item.ser_bin(s);
+        }
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.iter().map(SerBin::ser_bin_len).sum::<usize>()
+    }
+}
+
+impl<T> DeBin for BinaryHeap<T>
+where
+    T: DeBin + Ord,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<BinaryHeap<T>, DeBinErr> {
+        Vec::de_bin(o, d).map(BinaryHeap::from)
+    }
+}
+
 #[cfg(feature = "std")]
 impl<T> SerBin for std::collections::HashSet<T>
 where
     T: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
+        ser_len(self.len(), s);
         for item in self.iter() {
             item.ser_bin(s);
         }
     }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.iter().map(SerBin::ser_bin_len).sum::<usize>()
+    }
 }
 
 #[cfg(feature = "std")]
@@ -316,7 +690,7 @@ where
     T: DeBin + core::hash::Hash + Eq,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
+        let len = de_len(o, d)?;
         let mut out = std::collections::HashSet::with_capacity(len);
         for _ in 0..len {
             out.insert(DeBin::de_bin(o, d)?);
@@ -330,12 +704,15 @@ where
     T: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
+        ser_len(self.len(), s);
         for item in self.iter() {
             item.ser_bin(s);
         }
     }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + self.iter().map(SerBin::ser_bin_len).sum::<usize>()
+    }
 }
 
 impl<T> DeBin for BTreeSet<T>
@@ -343,7 +720,7 @@ where
     T: DeBin + Ord,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<BTreeSet<T>, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
+        let len = de_len(o, d)?;
         let mut out = BTreeSet::new();
         for _ in 0..len {
             out.insert(DeBin::de_bin(o, d)?);
@@ -364,6 +741,10 @@ where
             s.push(0);
         }
     }
+
+    fn ser_bin_len(&self) -> usize {
+        1 + self.as_ref().map_or(0, SerBin::ser_bin_len)
+    }
 }
 
 impl<T> DeBin for Option<T>
@@ -372,11 +753,7 @@ where
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<Option<T>, DeBinErr> {
         if *o + 1 > d.len() {
-            return Err(DeBinErr {
-                o: *o,
-                l: 1,
-                s: d.len(),
-            });
+            return Err(DeBinErr::new(*o, 1, d.len()));
         }
         let m = d[*o];
         *o += 1;
@@ -393,9 +770,11 @@ where
     T: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        for item in self {
-            item.ser_bin(s);
-        }
+        T::ser_bin_slice(self, s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.iter().map(SerBin::ser_bin_len).sum()
     }
 }
 
@@ -407,6 +786,10 @@ where
     fn ser_bin(&self, s: &mut Vec<u8>) {
         self.as_slice().ser_bin(s)
     }
+
+    fn ser_bin_len(&self) -> usize {
+        self.as_slice().ser_bin_len()
+    }
 }
 
 impl<T, const N: usize> DeBin for [T; N]
@@ -414,32 +797,51 @@ where
     T: DeBin,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
-        use core::mem::MaybeUninit;
+        T::de_bin_array(o, d)
+    }
+}
 
-        // waiting for uninit_array(or for array::try_from_fn) stabilization
-        // https://github.com/rust-lang/rust/issues/96097
-        // https://github.com/rust-lang/rust/issues/89379
-        let mut to: [MaybeUninit<T>; N] =
-            unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() };
+/// `Duration` is stored as its whole seconds followed by the nanoseconds.
+impl SerBin for core::time::Duration {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.as_secs().ser_bin(s);
+        self.subsec_nanos().ser_bin(s);
+    }
 
-        for index in 0..N {
-            to[index] = match DeBin::de_bin(o, d) {
-                Ok(v) => MaybeUninit::new(v),
-                Err(e) => {
-                    // drop all the MaybeUninit values which we've already
-                    // successfully deserialized so we don't leak memory.
-                    // See https://github.com/not-fl3/nanoserde/issues/79
-                    for (_, to_drop) in (0..index).zip(to) {
-                        unsafe { to_drop.assume_init() };
-                    }
-                    return Err(e);
-                }
-            }
+    fn ser_bin_len(&self) -> usize {
+        12
+    }
+}
+
+impl DeBin for core::time::Duration {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let secs = u64::de_bin(o, d)?;
+        let start = *o;
+        let nanos = u32::de_bin(o, d)?;
+        if nanos >= 1_000_000_000 {
+            return Err(DeBinErr::range(
+                start,
+                d.len(),
+                "Duration nanos above 999999999",
+            ));
         }
+        Ok(core::time::Duration::new(secs, nanos))
+    }
+}
 
-        // waiting for array_assume_init or core::array::map optimizations
-        // https://github.com/rust-lang/rust/issues/61956
-        Ok(unsafe { (*(&to as *const _ as *const MaybeUninit<_>)).assume_init_read() })
+impl SerBin for crate::RelativeInstant {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.0.ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.0.ser_bin_len()
+    }
+}
+
+impl DeBin for crate::RelativeInstant {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        core::time::Duration::de_bin(o, d).map(crate::RelativeInstant)
     }
 }
 
@@ -457,111 +859,89 @@ impl DeBin for () {
     }
 }
 
-impl<A, B> SerBin for (A, B)
-where
-    A: SerBin,
-    B: SerBin,
-{
-    fn ser_bin(&self, s: &mut Vec<u8>) {
-        self.0.ser_bin(s);
-        self.1.ser_bin(s);
+impl<T: ?Sized> SerBin for core::marker::PhantomData<T> {
+    #[inline(always)]
+    fn ser_bin(&self, _s: &mut Vec<u8>) {
+        // zero sized marker, nothing to write
     }
 }
 
-impl<A, B> DeBin for (A, B)
-where
-    A: DeBin,
-    B: DeBin,
-{
-    fn de_bin(o: &mut usize, d: &[u8]) -> Result<(A, B), DeBinErr> {
-        Ok((DeBin::de_bin(o, d)?, DeBin::de_bin(o, d)?))
+impl<T: ?Sized> DeBin for core::marker::PhantomData<T> {
+    #[inline(always)]
+    fn de_bin(_o: &mut usize, _d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(core::marker::PhantomData)
     }
 }
 
-impl<A, B, C> SerBin for (A, B, C)
+macro_rules! impl_bin_tuple {
+    ($($name: ident . $index: tt),+) => {
+        impl<$($name),+> SerBin for ($($name,)+)
+        where
+            $($name: SerBin),+
+        {
+            fn ser_bin(&self, s: &mut Vec<u8>) {
+                $(self.$index.ser_bin(s);)+
+            }
+
+            fn ser_bin_len(&self) -> usize {
+                0 $(+ self.$index.ser_bin_len())+
+            }
+        }
+
+        impl<$($name),+> DeBin for ($($name,)+)
+        where
+            $($name: DeBin),+
+        {
+            fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+                Ok(($(<$name as DeBin>::de_bin(o, d)?,)+))
+            }
+        }
+    };
+}
+
+impl_bin_tuple!(A.0, B.1);
+impl_bin_tuple!(A.0, B.1, C.2);
+impl_bin_tuple!(A.0, B.1, C.2, D.3);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+impl_bin_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
+
+#[cfg(feature = "std")]
+impl<K, V> SerBin for std::collections::HashMap<K, V>
 where
-    A: SerBin,
-    B: SerBin,
-    C: SerBin,
+    K: SerBin,
+    V: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        self.0.ser_bin(s);
-        self.1.ser_bin(s);
-        self.2.ser_bin(s);
+        ser_len(self.len(), s);
+        for (k, v) in self {
+            k.ser_bin(s);
+            v.ser_bin(s);
+        }
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len())
+            + self
+                .iter()
+                .map(|(k, v)| k.ser_bin_len() + v.ser_bin_len())
+                .sum::<usize>()
     }
 }
 
-impl<A, B, C> DeBin for (A, B, C)
-where
-    A: DeBin,
-    B: DeBin,
-    C: DeBin,
-{
-    fn de_bin(o: &mut usize, d: &[u8]) -> Result<(A, B, C), DeBinErr> {
-        Ok((
-            DeBin::de_bin(o, d)?,
-            DeBin::de_bin(o, d)?,
-            DeBin::de_bin(o, d)?,
-        ))
-    }
-}
-
-impl<A, B, C, D> SerBin for (A, B, C, D)
-where
-    A: SerBin,
-    B: SerBin,
-    C: SerBin,
-    D: SerBin,
-{
-    fn ser_bin(&self, s: &mut Vec<u8>) {
-        self.0.ser_bin(s);
-        self.1.ser_bin(s);
-        self.2.ser_bin(s);
-        self.3.ser_bin(s);
-    }
-}
-
-impl<A, B, C, D> DeBin for (A, B, C, D)
-where
-    A: DeBin,
-    B: DeBin,
-    C: DeBin,
-    D: DeBin,
-{
-    fn de_bin(o: &mut usize, d: &[u8]) -> Result<(A, B, C, D), DeBinErr> {
-        Ok((
-            DeBin::de_bin(o, d)?,
-            DeBin::de_bin(o, d)?,
-            DeBin::de_bin(o, d)?,
-            DeBin::de_bin(o, d)?,
-        ))
-    }
-}
-
-#[cfg(feature = "std")]
-impl<K, V> SerBin for std::collections::HashMap<K, V>
-where
-    K: SerBin,
-    V: SerBin,
-{
-    fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
-        for (k, v) in self {
-            k.ser_bin(s);
-            v.ser_bin(s);
-        }
-    }
-}
-
-#[cfg(feature = "std")]
-impl<K, V> DeBin for std::collections::HashMap<K, V>
+#[cfg(feature = "std")]
+impl<K, V> DeBin for std::collections::HashMap<K, V>
 where
     K: DeBin + core::cmp::Eq + core::hash::Hash,
     V: DeBin,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
+        let len = de_len(o, d)?;
         let mut h = std::collections::HashMap::with_capacity(len);
         for _ in 0..len {
             let k = DeBin::de_bin(o, d)?;
@@ -578,13 +958,20 @@ where
     V: SerBin,
 {
     fn ser_bin(&self, s: &mut Vec<u8>) {
-        let len = self.len();
-        len.ser_bin(s);
+        ser_len(self.len(), s);
         for (k, v) in self {
             k.ser_bin(s);
             v.ser_bin(s);
         }
     }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len())
+            + self
+                .iter()
+                .map(|(k, v)| k.ser_bin_len() + v.ser_bin_len())
+                .sum::<usize>()
+    }
 }
 
 impl<K, V> DeBin for BTreeMap<K, V>
@@ -593,7 +980,7 @@ where
     V: DeBin,
 {
     fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
-        let len: usize = DeBin::de_bin(o, d)?;
+        let len = de_len(o, d)?;
         let mut h = BTreeMap::new();
         for _ in 0..len {
             let k = DeBin::de_bin(o, d)?;
@@ -604,6 +991,19 @@ where
     }
 }
 
+impl<T> SerBin for &T
+where
+    T: SerBin + ?Sized,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        (**self).ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        (**self).ser_bin_len()
+    }
+}
+
 impl<T> SerBin for Box<T>
 where
     T: SerBin,
@@ -611,6 +1011,10 @@ where
     fn ser_bin(&self, s: &mut Vec<u8>) {
         (**self).ser_bin(s)
     }
+
+    fn ser_bin_len(&self) -> usize {
+        (**self).ser_bin_len()
+    }
 }
 
 impl<T> DeBin for Box<T>
@@ -621,3 +1025,545 @@ where
         Ok(Box::new(DeBin::de_bin(o, d)?))
     }
 }
+
+impl<T> SerBin for core::num::Wrapping<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.0.ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.0.ser_bin_len()
+    }
+}
+
+impl<T> DeBin for core::num::Wrapping<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<core::num::Wrapping<T>, DeBinErr> {
+        Ok(core::num::Wrapping(DeBin::de_bin(o, d)?))
+    }
+}
+
+impl<T> SerBin for Rc<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        (**self).ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        (**self).ser_bin_len()
+    }
+}
+
+impl<T> DeBin for Rc<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Rc<T>, DeBinErr> {
+        Ok(Rc::new(DeBin::de_bin(o, d)?))
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> SerBin for Arc<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        (**self).ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        (**self).ser_bin_len()
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> DeBin for Arc<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Arc<T>, DeBinErr> {
+        Ok(Arc::new(DeBin::de_bin(o, d)?))
+    }
+}
+
+impl SerBin for Cow<'_, str> {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        (**self).ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        (**self).ser_bin_len()
+    }
+}
+
+/// Written with a length prefix like the owned `Vec<T>`, unlike a bare `[T]`.
+impl<T> SerBin for Cow<'_, [T]>
+where
+    T: SerBin + Clone,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        ser_len(self.len(), s);
+        (**self).ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        len_size(self.len()) + (**self).ser_bin_len()
+    }
+}
+
+impl<'a, B> DeBin for Cow<'a, B>
+where
+    B: ?Sized + ToOwned,
+    B::Owned: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Cow<'a, B>, DeBinErr> {
+        Ok(Cow::Owned(DeBin::de_bin(o, d)?))
+    }
+}
+
+/// Ranges are stored as their start followed by their end.
+impl<T> SerBin for core::ops::Range<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.start.ser_bin(s);
+        self.end.ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.start.ser_bin_len() + self.end.ser_bin_len()
+    }
+}
+
+impl<T> DeBin for core::ops::Range<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let start = DeBin::de_bin(o, d)?;
+        let end = DeBin::de_bin(o, d)?;
+        Ok(start..end)
+    }
+}
+
+impl<T> SerBin for core::ops::RangeInclusive<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.start().ser_bin(s);
+        self.end().ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.start().ser_bin_len() + self.end().ser_bin_len()
+    }
+}
+
+impl<T> DeBin for core::ops::RangeInclusive<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let start = DeBin::de_bin(o, d)?;
+        let end = DeBin::de_bin(o, d)?;
+        Ok(core::ops::RangeInclusive::new(start, end))
+    }
+}
+
+/// `start..` is stored as its start.
+impl<T> SerBin for core::ops::RangeFrom<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.start.ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.start.ser_bin_len()
+    }
+}
+
+impl<T> DeBin for core::ops::RangeFrom<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(DeBin::de_bin(o, d)?..)
+    }
+}
+
+/// `..end` is stored as its end.
+impl<T> SerBin for core::ops::RangeTo<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.end.ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.end.ser_bin_len()
+    }
+}
+
+impl<T> DeBin for core::ops::RangeTo<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(..DeBin::de_bin(o, d)?)
+    }
+}
+
+/// `..` takes no bytes.
+impl SerBin for core::ops::RangeFull {
+    fn ser_bin(&self, _s: &mut Vec<u8>) {}
+
+    fn ser_bin_len(&self) -> usize {
+        0
+    }
+}
+
+impl DeBin for core::ops::RangeFull {
+    fn de_bin(_o: &mut usize, _d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(..)
+    }
+}
+
+impl SerBin for core::num::FpCategory {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        use core::num::FpCategory::*;
+        let id: u16 = match self {
+            Nan => 0,
+            Infinite => 1,
+            Zero => 2,
+            Subnormal => 3,
+            Normal => 4,
+        };
+        id.ser_bin(s);
+    }
+}
+
+impl DeBin for core::num::FpCategory {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        use core::num::FpCategory::*;
+        let id: u16 = DeBin::de_bin(o, d)?;
+        Ok(match id {
+            0 => Nan,
+            1 => Infinite,
+            2 => Zero,
+            3 => Subnormal,
+            4 => Normal,
+            _ => return Err(DeBinErr::new(*o, 0, d.len())),
+        })
+    }
+}
+
+impl SerBin for core::cmp::Ordering {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        use core::cmp::Ordering::*;
+        let id: u16 = match self {
+            Less => 0,
+            Equal => 1,
+            Greater => 2,
+        };
+        id.ser_bin(s);
+    }
+}
+
+impl DeBin for core::cmp::Ordering {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        use core::cmp::Ordering::*;
+        let id: u16 = DeBin::de_bin(o, d)?;
+        Ok(match id {
+            0 => Less,
+            1 => Equal,
+            2 => Greater,
+            _ => return Err(DeBinErr::new(*o, 0, d.len())),
+        })
+    }
+}
+
+impl<T> SerBin for core::ops::Bound<T>
+where
+    T: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        use core::ops::Bound::*;
+        match self {
+            Included(v) => {
+                0u16.ser_bin(s);
+                v.ser_bin(s);
+            }
+            Excluded(v) => {
+                1u16.ser_bin(s);
+                v.ser_bin(s);
+            }
+            Unbounded => 2u16.ser_bin(s),
+        }
+    }
+}
+
+impl<T> DeBin for core::ops::Bound<T>
+where
+    T: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        use core::ops::Bound::*;
+        let id: u16 = DeBin::de_bin(o, d)?;
+        Ok(match id {
+            0 => Included(DeBin::de_bin(o, d)?),
+            1 => Excluded(DeBin::de_bin(o, d)?),
+            2 => Unbounded,
+            _ => return Err(DeBinErr::new(*o, 0, d.len())),
+        })
+    }
+}
+
+/// `Result` is written like a derived enum, a `u16` tag of 0 for `Ok` or 1 for
+/// `Err` followed by the value.
+impl<T, E> SerBin for Result<T, E>
+where
+    T: SerBin,
+    E: SerBin,
+{
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        match self {
+            Ok(v) => {
+                0u16.ser_bin(s);
+                v.ser_bin(s);
+            }
+            Err(e) => {
+                1u16.ser_bin(s);
+                e.ser_bin(s);
+            }
+        }
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        2 + match self {
+            Ok(v) => v.ser_bin_len(),
+            Err(e) => e.ser_bin_len(),
+        }
+    }
+}
+
+impl<T, E> DeBin for Result<T, E>
+where
+    T: DeBin,
+    E: DeBin,
+{
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let start = *o;
+        let id: u16 = DeBin::de_bin(o, d)?;
+        Ok(match id {
+            0 => Ok(DeBin::de_bin(o, d)?),
+            1 => Err(DeBin::de_bin(o, d)?),
+            _ => {
+                return Err(DeBinErr::range(
+                    start,
+                    d.len(),
+                    format!("{} is not a valid Result tag", id),
+                ))
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl SerBin for std::net::Ipv4Addr {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        s.extend_from_slice(&self.octets());
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        4
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::net::Ipv4Addr {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let octets: [u8; 4] = DeBin::de_bin(o, d)?;
+        Ok(octets.into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl SerBin for std::net::Ipv6Addr {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        s.extend_from_slice(&self.octets());
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        16
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::net::Ipv6Addr {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let octets: [u8; 16] = DeBin::de_bin(o, d)?;
+        Ok(octets.into())
+    }
+}
+
+/// Like a derived enum, a `u16` variant index (0 for V4, 1 for V6) followed by the address.
+#[cfg(feature = "std")]
+impl SerBin for std::net::IpAddr {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        match self {
+            std::net::IpAddr::V4(addr) => {
+                0u16.ser_bin(s);
+                addr.ser_bin(s);
+            }
+            std::net::IpAddr::V6(addr) => {
+                1u16.ser_bin(s);
+                addr.ser_bin(s);
+            }
+        }
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        match self {
+            std::net::IpAddr::V4(addr) => 2 + addr.ser_bin_len(),
+            std::net::IpAddr::V6(addr) => 2 + addr.ser_bin_len(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::net::IpAddr {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let id: u16 = DeBin::de_bin(o, d)?;
+        Ok(match id {
+            0 => std::net::IpAddr::V4(DeBin::de_bin(o, d)?),
+            1 => std::net::IpAddr::V6(DeBin::de_bin(o, d)?),
+            _ => return Err(DeBinErr::new(*o, 0, d.len())),
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl SerBin for std::net::SocketAddrV4 {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.ip().ser_bin(s);
+        self.port().ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        6
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::net::SocketAddrV4 {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        Ok(std::net::SocketAddrV4::new(
+            DeBin::de_bin(o, d)?,
+            DeBin::de_bin(o, d)?,
+        ))
+    }
+}
+
+/// The scope id is kept along with the address and port. Like the text form used by
+/// JSON and RON, the flow info is not, and reads back as 0.
+#[cfg(feature = "std")]
+impl SerBin for std::net::SocketAddrV6 {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.ip().ser_bin(s);
+        self.port().ser_bin(s);
+        self.scope_id().ser_bin(s);
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        22
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::net::SocketAddrV6 {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let ip = DeBin::de_bin(o, d)?;
+        let port = DeBin::de_bin(o, d)?;
+        let scope_id = DeBin::de_bin(o, d)?;
+        Ok(std::net::SocketAddrV6::new(ip, port, 0, scope_id))
+    }
+}
+
+/// Like `IpAddr`, a `u16` variant index (0 for V4, 1 for V6) followed by the socket address.
+#[cfg(feature = "std")]
+impl SerBin for std::net::SocketAddr {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        match self {
+            std::net::SocketAddr::V4(addr) => {
+                0u16.ser_bin(s);
+                addr.ser_bin(s);
+            }
+            std::net::SocketAddr::V6(addr) => {
+                1u16.ser_bin(s);
+                addr.ser_bin(s);
+            }
+        }
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        match self {
+            std::net::SocketAddr::V4(addr) => 2 + addr.ser_bin_len(),
+            std::net::SocketAddr::V6(addr) => 2 + addr.ser_bin_len(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::net::SocketAddr {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        let id: u16 = DeBin::de_bin(o, d)?;
+        Ok(match id {
+            0 => std::net::SocketAddr::V4(DeBin::de_bin(o, d)?),
+            1 => std::net::SocketAddr::V6(DeBin::de_bin(o, d)?),
+            _ => return Err(DeBinErr::new(*o, 0, d.len())),
+        })
+    }
+}
+
+/// Paths are stored like a `String`, with invalid UTF-8 replaced by U+FFFD.
+#[cfg(feature = "std")]
+impl SerBin for std::path::Path {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.to_string_lossy().ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.to_string_lossy().ser_bin_len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl SerBin for std::path::PathBuf {
+    fn ser_bin(&self, s: &mut Vec<u8>) {
+        self.as_path().ser_bin(s)
+    }
+
+    fn ser_bin_len(&self) -> usize {
+        self.as_path().ser_bin_len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeBin for std::path::PathBuf {
+    fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+        String::de_bin(o, d).map(std::path::PathBuf::from)
+    }
+}
@@ -6,21 +6,59 @@ use core::error::Error;
 #[cfg(feature = "std")]
 use std::error::Error;
 
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
-use alloc::collections::{BTreeMap, BTreeSet, LinkedList};
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
 use alloc::format;
+use alloc::rc::Rc;
 use alloc::string::{String, ToString};
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
 /// The internal state of a JSON serialization.
 #[non_exhaustive]
-pub struct SerJsonState {
+pub struct SerJsonState<'a> {
     pub out: String,
+    /// Write the entries of `HashMap`s and `HashSet`s sorted by their serialized
+    /// key, see [`SerJson::serialize_json_canonical`].
+    pub canonical: bool,
+    /// The writer of [`SerJson::ser_json_to`], `out` is handed to it in chunks.
+    sink: Option<&'a mut dyn core::fmt::Write>,
+    sink_result: core::fmt::Result,
 }
 
-impl SerJsonState {
+/// How much `SerJsonState` buffers before handing it to the writer of `ser_json_to`.
+const SINK_CHUNK: usize = 1024;
+
+impl<'a> SerJsonState<'a> {
     pub fn new(out: String) -> Self {
-        Self { out }
+        Self {
+            out,
+            canonical: false,
+            sink: None,
+            sink_result: Ok(()),
+        }
+    }
+
+    fn with_sink(sink: &'a mut dyn core::fmt::Write) -> Self {
+        Self {
+            sink: Some(sink),
+            ..Self::new(String::with_capacity(SINK_CHUNK))
+        }
+    }
+
+    /// Hand what was written so far to the writer of `ser_json_to`, once it
+    /// has grown to a chunk. Only called between whole values.
+    fn flush(&mut self, force: bool) {
+        if let Some(sink) = &mut self.sink {
+            if force || self.out.len() >= SINK_CHUNK {
+                if self.sink_result.is_ok() {
+                    self.sink_result = sink.write_str(&self.out);
+                }
+                self.out.clear();
+            }
+        }
     }
 
     pub fn indent(&mut self, _d: usize) {
@@ -44,6 +82,7 @@ impl SerJsonState {
     }
 
     pub fn conl(&mut self) {
+        self.flush(false);
         self.out.push(',')
     }
 
@@ -55,6 +94,38 @@ impl SerJsonState {
         self.indent(d);
         self.out.push('}');
     }
+
+    /// Write the fields of `value`, which has to serialize to a JSON object,
+    /// into the object currently being written instead of nesting it.
+    ///
+    /// This is what `#[nserde(flatten)]` fields use, so their type has to
+    /// implement `SerJson` and `DeJson` itself, usually with the derives.
+    ///
+    /// Returns whether any field was written.
+    pub fn flatten<T: SerJson + ?Sized>(
+        &mut self,
+        d: usize,
+        value: &T,
+        first_field_was_serialized: bool,
+    ) -> bool {
+        let mut inner = SerJsonState::new(String::new());
+        inner.canonical = self.canonical;
+        value.ser_json(d, &mut inner);
+        match inner
+            .out
+            .strip_prefix('{')
+            .and_then(|fields| fields.strip_suffix('}'))
+        {
+            Some(fields) if !fields.is_empty() => {
+                if first_field_was_serialized {
+                    self.conl();
+                }
+                self.out.push_str(fields);
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 /// A trait for objects that can be serialized to JSON.
@@ -63,7 +134,57 @@ pub trait SerJson {
     ///
     /// This is a convenient wrapper around `ser_json`.
     fn serialize_json(&self) -> String {
-        let mut s = SerJsonState { out: String::new() };
+        let mut s = SerJsonState::new(String::new());
+        self.ser_json(0, &mut s);
+        s.out
+    }
+
+    /// Serialize Self to JSON, appending to `out`.
+    ///
+    /// Unlike `serialize_json` no new string is allocated, so clearing and
+    /// reusing one buffer saves allocations across repeated serializations.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let mut out = String::new();
+    /// 1u32.serialize_json_into(&mut out);
+    /// out.clear();
+    /// [2u32, 3].serialize_json_into(&mut out);
+    /// assert_eq!(out, "[2,3]");
+    /// ```
+    fn serialize_json_into(&self, out: &mut String) {
+        let mut s = SerJsonState::new(core::mem::take(out));
+        self.ser_json(0, &mut s);
+        *out = s.out;
+    }
+
+    /// Serialize Self to JSON, writing it to `w` in chunks as it goes instead
+    /// of building the whole string first, e.g. to stream a large value.
+    ///
+    /// Returns the first error of `w`, the rest of the output is dropped then.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let mut out = String::new();
+    /// vec![1u32, 2].ser_json_to(&mut out).unwrap();
+    /// assert_eq!(out, "[1,2]");
+    /// ```
+    fn ser_json_to<W: core::fmt::Write>(&self, w: &mut W) -> core::fmt::Result
+    where
+        Self: Sized,
+    {
+        let mut s = SerJsonState::with_sink(w);
+        self.ser_json(0, &mut s);
+        s.flush(true);
+        s.sink_result
+    }
+
+    /// Serialize Self to a JSON string in which the entries of every `HashMap` and
+    /// `HashSet` are sorted by their serialized key, so equal values always give
+    /// identical output, e.g. for hashing or signing.
+    fn serialize_json_canonical(&self) -> String {
+        let mut s = SerJsonState::new(String::new());
+        s.canonical = true;
         self.ser_json(0, &mut s);
         s.out
     }
@@ -86,7 +207,73 @@ pub trait DeJson: Sized {
     /// This is a convenient wrapper around `de_json`.
     fn deserialize_json(input: &str) -> Result<Self, DeJsonErr> {
         let mut state = DeJsonState::default();
-        let mut chars = input.chars();
+        let mut chars = input.strip_prefix('\u{feff}').unwrap_or(input).chars();
+        state.next(&mut chars);
+        state.next_tok(&mut chars)?;
+        DeJson::de_json(&mut state, &mut chars)
+    }
+
+    /// Parse Self from UTF-8 encoded bytes, e.g. a file read from disk.
+    ///
+    /// A leading byte order mark is skipped like in `deserialize_json`, input
+    /// starting with a UTF-16 byte order mark is rejected with a clear error.
+    /// Invalid UTF-8 is reported as `DeJsonErrReason::CannotParse` with the
+    /// position of the first bad byte.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let out: Vec<u32> = DeJson::deserialize_json_bytes(b"\xEF\xBB\xBF[1, 2]").unwrap();
+    /// assert_eq!(out, [1, 2]);
+    /// assert!(<Vec<u32>>::deserialize_json_bytes(b"\xFF\xFE[\x001\x00]\x00").is_err());
+    /// ```
+    fn deserialize_json_bytes(input: &[u8]) -> Result<Self, DeJsonErr> {
+        let encoding_err = |what: &str| {
+            let reason = DeJsonErrReason::CannotParse(what.to_string());
+            DeJsonErr {
+                msg: reason.to_string(),
+                reason,
+                line: 0,
+                col: 0,
+                offset: 0,
+            }
+        };
+        if input.starts_with(&[0xFF, 0xFE]) || input.starts_with(&[0xFE, 0xFF]) {
+            return Err(encoding_err(
+                "input, it appears to be UTF-16, expected UTF-8",
+            ));
+        }
+        match core::str::from_utf8(input) {
+            Ok(input) => DeJson::deserialize_json(input),
+            Err(err) => {
+                // walk the valid part so line, col and offset point at the bad byte
+                let valid = err.valid_up_to();
+                let mut state = DeJsonState::default();
+                let mut chars = core::str::from_utf8(&input[..valid]).unwrap().chars();
+                while !chars.as_str().is_empty() {
+                    state.next(&mut chars);
+                }
+                Err(state.err_parse(&format!("input, it is not valid UTF-8 at byte {}", valid)))
+            }
+        }
+    }
+
+    /// Parse Self from the input string, accepting a subset of JSON5.
+    ///
+    /// On top of the comments that `deserialize_json` already skips, object
+    /// keys may be unquoted identifiers, strings may be single-quoted and
+    /// numbers may have a leading `+`.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let out: Vec<String> = DeJson::deserialize_json5("['a', \"b\"]").unwrap();
+    /// assert_eq!(out, ["a", "b"]);
+    /// ```
+    fn deserialize_json5(input: &str) -> Result<Self, DeJsonErr> {
+        let mut state = DeJsonState {
+            json5: true,
+            ..Default::default()
+        };
+        let mut chars = input.strip_prefix('\u{feff}').unwrap_or(input).chars();
         state.next(&mut chars);
         state.next_tok(&mut chars)?;
         DeJson::de_json(&mut state, &mut chars)
@@ -107,13 +294,15 @@ pub trait DeJson: Sized {
 }
 
 /// A JSON parsed token.
-#[derive(PartialEq, Debug, Default)]
+#[derive(PartialEq, Debug, Default, Clone)]
 #[non_exhaustive]
 pub enum DeJsonTok {
     Str,
     Char(char),
     U64(u64),
     I64(i64),
+    U128(u128),
+    I128(i128),
     F64(f64),
     Bool(bool),
     BareIdent,
@@ -130,7 +319,6 @@ pub enum DeJsonTok {
 }
 
 /// The internal state of a JSON deserialization.
-#[derive(Default)]
 #[non_exhaustive]
 pub struct DeJsonState {
     pub cur: char,
@@ -140,6 +328,141 @@ pub struct DeJsonState {
     pub identbuf: String,
     pub line: usize,
     pub col: usize,
+    /// The number of chars consumed from the input so far.
+    pub offset: usize,
+    /// Accept unquoted object keys, single-quoted strings and numbers with a leading `+`.
+    pub json5: bool,
+    /// The number of arrays and objects currently open.
+    pub depth: usize,
+    /// The maximum nesting of arrays and objects before failing with
+    /// [`DeJsonErrReason::TooDeep`], 128 by default.
+    pub max_depth: usize,
+    /// The line, col and offset of the current token, used by `capture_value`.
+    tok_pos: (usize, usize, usize),
+}
+
+/// JSON text captured from the input by `capture_value` and `capture_field`,
+/// to be parsed again with [`DeJsonState::parse_captured`].
+///
+/// Objects and arrays are copied from the input as they are, and where each
+/// copied piece came from is kept so errors can point into the input.
+#[derive(Default)]
+pub struct DeJsonCapture {
+    pub text: String,
+    /// The byte index into `text` where a piece starts, along with the line,
+    /// col and offset in the input right before its first char.
+    spans: Vec<(usize, usize, usize, usize)>,
+}
+
+impl DeJsonCapture {
+    /// Moves the position of an error from `text` back into the input. Errors
+    /// before the first piece, in the parts `capture_field` adds around the
+    /// pieces, are given the position of `parent`.
+    fn locate(&self, mut err: DeJsonErr, parent: &DeJsonState) -> DeJsonErr {
+        let end = self
+            .text
+            .char_indices()
+            .nth(err.offset)
+            .map_or(self.text.len(), |(index, _)| index);
+        match self.spans.iter().rev().find(|span| span.0 <= end) {
+            Some(&(start, mut line, mut col, mut offset)) => {
+                for c in self.text[start..end].chars() {
+                    offset += 1;
+                    if c == '\n' {
+                        line += 1;
+                        col = 0;
+                    } else {
+                        col += 1;
+                    }
+                }
+                err.line = line;
+                err.col = col;
+                err.offset = offset;
+            }
+            None => {
+                err.line = parent.line;
+                err.col = parent.col;
+                err.offset = parent.offset;
+            }
+        }
+        err
+    }
+}
+
+impl Default for DeJsonState {
+    fn default() -> Self {
+        DeJsonState {
+            cur: '\0',
+            tok: DeJsonTok::default(),
+            strbuf: String::new(),
+            numbuf: String::new(),
+            identbuf: String::new(),
+            line: 0,
+            col: 0,
+            offset: 0,
+            json5: false,
+            depth: 0,
+            max_depth: 128,
+            tok_pos: (0, 0, 0),
+        }
+    }
+}
+
+/// The reason a JSON string failed to deserialize.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DeJsonErrReason {
+    /// A key that isn't part of the target type, along with the keys that are.
+    UnexpectedKey {
+        key: String,
+        expected: &'static [&'static str],
+    },
+    /// The token found and a description of what was expected instead.
+    UnexpectedToken(DeJsonTok, String),
+    /// A required key that wasn't present, along with all the keys of the target type.
+    MissingKey {
+        key: String,
+        expected: &'static [&'static str],
+    },
+    NoSuchEnum(String),
+    OutOfRange(String),
+    WrongType(String),
+    CannotParse(String),
+    /// Arrays and objects were nested deeper than `DeJsonState::max_depth`.
+    TooDeep,
+    /// A message from a `DeJson` impl or a `deserialize_with` function, see `DeJsonState::err_custom`.
+    Custom(String),
+}
+
+impl core::fmt::Display for DeJsonErrReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fn expected_keys(f: &mut core::fmt::Formatter<'_>, expected: &[&str]) -> core::fmt::Result {
+            if !expected.is_empty() {
+                write!(f, ", expected one of: {}", expected.join(", "))?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::UnexpectedKey { key, expected } => {
+                write!(f, "Unexpected key {}", key)?;
+                expected_keys(f, expected)
+            }
+            Self::UnexpectedToken(token, what) => {
+                write!(f, "Unexpected token {:?} expected {} ", token, what)
+            }
+            Self::MissingKey { key, expected } => {
+                write!(f, "Key not found {}", key)?;
+                expected_keys(f, expected)
+            }
+            Self::NoSuchEnum(name) => write!(f, "Enum not defined {}", name),
+            Self::OutOfRange(value) => write!(f, "Value out of range {} ", value),
+            Self::WrongType(what) => write!(f, "Token wrong type {} ", what),
+            Self::CannotParse(what) => write!(f, "Cannot parse {} ", what),
+            Self::TooDeep => write!(f, "Nesting too deep"),
+            Self::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
 }
 
 /// The error message when failing to deserialize a JSON string.
@@ -147,18 +470,23 @@ pub struct DeJsonState {
 #[non_exhaustive]
 pub struct DeJsonErr {
     pub msg: String,
+    /// The same error as `msg`, in a form that can be matched on.
+    pub reason: DeJsonErrReason,
     pub line: usize,
     pub col: usize,
+    /// The number of chars consumed from the input when the error occurred.
+    pub offset: usize,
 }
 
 impl core::fmt::Debug for DeJsonErr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
-            "Json Deserialize error: {}, line:{} col:{}",
+            "Json Deserialize error: {}, line:{} col:{} offset:{}",
             self.msg,
             self.line + 1,
-            self.col + 1
+            self.col + 1,
+            self.offset
         )
     }
 }
@@ -171,10 +499,33 @@ impl core::fmt::Display for DeJsonErr {
 
 impl Error for DeJsonErr {}
 
+impl DeJsonErr {
+    /// Returns the line of `input` the error occurred on, followed by a line with a caret
+    /// pointing at the char the parser stopped at.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let input = "[1,\n 2, x]";
+    /// let err = <Vec<u32>>::deserialize_json(input).unwrap_err();
+    /// assert_eq!(err.snippet(input), " 2, x]\n     ^");
+    /// ```
+    pub fn snippet(&self, input: &str) -> String {
+        let line = input.split('\n').nth(self.line).unwrap_or("");
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let mut out = String::with_capacity(line.len() * 2 + 2);
+        out.push_str(line);
+        out.push('\n');
+        out.extend(core::iter::repeat(' ').take(self.col.saturating_sub(1)));
+        out.push('^');
+        out
+    }
+}
+
 impl DeJsonState {
     pub fn next(&mut self, i: &mut Chars) {
         if let Some(c) = i.next() {
             self.cur = c;
+            self.offset += 1;
             if self.cur == '\n' {
                 self.line += 1;
                 self.col = 0;
@@ -186,60 +537,66 @@ impl DeJsonState {
         }
     }
 
-    pub fn err_exp(&self, name: &str) -> DeJsonErr {
+    fn err(&self, reason: DeJsonErrReason) -> DeJsonErr {
         DeJsonErr {
-            msg: format!("Unexpected key {}", name),
+            msg: reason.to_string(),
+            reason,
             line: self.line,
             col: self.col,
+            offset: self.offset,
         }
     }
 
+    pub fn err_exp(&self, name: &str) -> DeJsonErr {
+        self.err_exp_keys(name, &[])
+    }
+
+    /// Like `err_exp`, but also reports the keys that would have been accepted.
+    pub fn err_exp_keys(&self, name: &str, expected: &'static [&'static str]) -> DeJsonErr {
+        self.err(DeJsonErrReason::UnexpectedKey {
+            key: name.to_string(),
+            expected,
+        })
+    }
+
     pub fn err_nf(&self, name: &str) -> DeJsonErr {
-        DeJsonErr {
-            msg: format!("Key not found {}", name),
-            line: self.line,
-            col: self.col,
-        }
+        self.err_nf_keys(name, &[])
+    }
+
+    /// Like `err_nf`, but also reports all the keys of the type being deserialized.
+    pub fn err_nf_keys(&self, name: &str, expected: &'static [&'static str]) -> DeJsonErr {
+        self.err(DeJsonErrReason::MissingKey {
+            key: name.to_string(),
+            expected,
+        })
     }
 
     pub fn err_enum(&self, name: &str) -> DeJsonErr {
-        DeJsonErr {
-            msg: format!("Enum not defined {}", name),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeJsonErrReason::NoSuchEnum(name.to_string()))
     }
 
     pub fn err_token(&self, what: &str) -> DeJsonErr {
-        DeJsonErr {
-            msg: format!("Unexpected token {:?} expected {} ", self.tok, what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeJsonErrReason::UnexpectedToken(
+            self.tok.clone(),
+            what.to_string(),
+        ))
     }
 
     pub fn err_range(&self, what: &str) -> DeJsonErr {
-        DeJsonErr {
-            msg: format!("Value out of range {} ", what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeJsonErrReason::OutOfRange(what.to_string()))
     }
 
     pub fn err_type(&self, what: &str) -> DeJsonErr {
-        DeJsonErr {
-            msg: format!("Token wrong type {} ", what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeJsonErrReason::WrongType(what.to_string()))
     }
 
     pub fn err_parse(&self, what: &str) -> DeJsonErr {
-        DeJsonErr {
-            msg: format!("Cannot parse {} ", what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeJsonErrReason::CannotParse(what.to_string()))
+    }
+
+    /// An error with a domain-specific message, at the current position.
+    pub fn err_custom(&self, msg: &str) -> DeJsonErr {
+        self.err(DeJsonErrReason::Custom(msg.to_string()))
     }
 
     pub fn eat_comma_block(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
@@ -259,21 +616,27 @@ impl DeJsonState {
             | DeJsonTok::I64 { .. }
             | DeJsonTok::Str
             | DeJsonTok::U64 { .. }
+            | DeJsonTok::U128 { .. }
+            | DeJsonTok::I128 { .. }
             | DeJsonTok::Bool { .. }
             | DeJsonTok::Null => {
                 self.next_tok(i)?;
                 Ok(())
             }
+            // only read in JSON5 mode, skipped like the other scalars
+            DeJsonTok::BareIdent if self.json5 => {
+                self.next_tok(i)?;
+                Ok(())
+            }
             DeJsonTok::BlockOpen | DeJsonTok::CurlyOpen => {
                 let mut open_brackets = 0;
 
                 loop {
-                    if let DeJsonTok::BlockOpen | DeJsonTok::CurlyOpen = self.tok {
-                        open_brackets += 1;
-                    }
-
-                    if let DeJsonTok::BlockClose | DeJsonTok::CurlyClose = self.tok {
-                        open_brackets -= 1;
+                    match self.tok {
+                        DeJsonTok::BlockOpen | DeJsonTok::CurlyOpen => open_brackets += 1,
+                        DeJsonTok::BlockClose | DeJsonTok::CurlyClose => open_brackets -= 1,
+                        DeJsonTok::Eof => return Err(self.err_token("] or }")),
+                        _ => {}
                     }
 
                     self.next_tok(i)?;
@@ -284,8 +647,148 @@ impl DeJsonState {
                 }
                 Ok(())
             }
-            _ => unimplemented!("{:?}", self.tok),
+            _ => Err(self.err_token("value")),
+        }
+    }
+
+    /// Capture the value at the current token into `out`, advancing past it.
+    ///
+    /// Objects and arrays are copied from the input as they are, other values
+    /// are written back as JSON. Nesting counts towards `max_depth` like it
+    /// would when deserializing the value.
+    pub fn capture_value(
+        &mut self,
+        i: &mut Chars,
+        out: &mut DeJsonCapture,
+    ) -> Result<(), DeJsonErr> {
+        let (line, col, offset) = self.tok_pos;
+        // the token has been read, so its first char is one position back
+        out.spans.push((
+            out.text.len(),
+            line,
+            col.saturating_sub(1),
+            offset.saturating_sub(1),
+        ));
+        let open = match &self.tok {
+            DeJsonTok::CurlyOpen => '{',
+            DeJsonTok::BlockOpen => '[',
+            DeJsonTok::Str => {
+                push_json_str(&mut out.text, &self.strbuf);
+                return self.next_tok(i);
+            }
+            DeJsonTok::BareIdent => {
+                push_json_str(&mut out.text, &self.identbuf);
+                return self.next_tok(i);
+            }
+            DeJsonTok::U64(v) => {
+                out.text.push_str(&v.to_string());
+                return self.next_tok(i);
+            }
+            DeJsonTok::I64(v) => {
+                out.text.push_str(&v.to_string());
+                return self.next_tok(i);
+            }
+            DeJsonTok::U128(v) => {
+                out.text.push_str(&v.to_string());
+                return self.next_tok(i);
+            }
+            DeJsonTok::I128(v) => {
+                out.text.push_str(&v.to_string());
+                return self.next_tok(i);
+            }
+            DeJsonTok::F64(v) => {
+                out.text.push_str(&format!("{v:?}"));
+                return self.next_tok(i);
+            }
+            DeJsonTok::Bool(v) => {
+                out.text.push_str(if *v { "true" } else { "false" });
+                return self.next_tok(i);
+            }
+            DeJsonTok::Null => {
+                out.text.push_str("null");
+                return self.next_tok(i);
+            }
+            _ => return Err(self.err_token("value")),
+        };
+        out.text.push(open);
+        // everything after the opening token is copied from the input, which
+        // holds the char after it in `cur` and the rest in `i`
+        let first = self.cur;
+        let rest = i.as_str();
+        let mut open_brackets = 0;
+        loop {
+            match &self.tok {
+                DeJsonTok::CurlyOpen | DeJsonTok::BlockOpen => {
+                    if self.depth + open_brackets >= self.max_depth {
+                        return Err(self.err(DeJsonErrReason::TooDeep));
+                    }
+                    open_brackets += 1;
+                }
+                DeJsonTok::CurlyClose | DeJsonTok::BlockClose => open_brackets -= 1,
+                DeJsonTok::Eof => return Err(self.err_token("value")),
+                _ => {}
+            }
+            if open_brackets == 0 {
+                break;
+            }
+            self.next_tok(i)?;
+        }
+        let copied = &rest[..rest.len() - i.as_str().len()];
+        if first != '\0' {
+            out.text.push(first);
+            out.text.push_str(copied);
+            // `cur` holds the char after the closing token, which isn't part of the value
+            if self.cur != '\0' {
+                out.text.pop();
+            }
+        }
+        self.next_tok(i)
+    }
+
+    /// Re-emit the `"key":value` pair starting at the current key token into
+    /// the JSON object being built in `out`, advancing past it.
+    ///
+    /// Once all fields are captured, `finish_capture` closes the object.
+    pub fn capture_field(
+        &mut self,
+        i: &mut Chars,
+        out: &mut DeJsonCapture,
+    ) -> Result<(), DeJsonErr> {
+        out.text.push(if out.text.is_empty() { '{' } else { ',' });
+        push_json_str(&mut out.text, &self.strbuf);
+        out.text.push(':');
+        self.next_colon(i)?;
+        self.capture_value(i, out)
+    }
+
+    /// Close the JSON object built by `capture_field`.
+    pub fn finish_capture(out: &mut DeJsonCapture) {
+        if out.text.is_empty() {
+            out.text.push('{');
         }
+        out.text.push('}');
+    }
+
+    /// Parse a captured value again with `f`. The state it gets has the
+    /// JSON5 mode, nesting depth and `max_depth` of this one, and errors are
+    /// moved back to where the value was in the input.
+    pub fn parse_captured<T>(
+        &self,
+        capture: &DeJsonCapture,
+        f: impl FnOnce(&mut DeJsonState, &mut Chars) -> Result<T, DeJsonErr>,
+    ) -> Result<T, DeJsonErr> {
+        let mut state = DeJsonState {
+            json5: self.json5,
+            depth: self.depth,
+            max_depth: self.max_depth,
+            ..Default::default()
+        };
+        let mut chars = capture.text.chars();
+        state.next(&mut chars);
+        state
+            .next_tok(&mut chars)
+            .and_then(|()| f(&mut state, &mut chars))
+            .map_err(|err| capture.locate(err, self))
     }
 
     pub fn eat_comma_curly(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
@@ -335,8 +838,27 @@ impl DeJsonState {
         }
     }
 
+    /// Like `next_str`, but in JSON5 mode also accepts an unquoted identifier,
+    /// which is moved into `strbuf`.
+    pub fn next_key(&mut self) -> Option<()> {
+        if self.tok == DeJsonTok::BareIdent {
+            core::mem::swap(&mut self.strbuf, &mut self.identbuf);
+            self.tok = DeJsonTok::Str;
+        }
+        self.next_str()
+    }
+
+    fn enter(&mut self) -> Result<(), DeJsonErr> {
+        if self.depth >= self.max_depth {
+            return Err(self.err(DeJsonErrReason::TooDeep));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     pub fn block_open(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
         if self.tok == DeJsonTok::BlockOpen {
+            self.enter()?;
             self.next_tok(i)?;
             return Ok(());
         }
@@ -345,6 +867,7 @@ impl DeJsonState {
 
     pub fn block_close(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
         if self.tok == DeJsonTok::BlockClose {
+            self.depth = self.depth.saturating_sub(1);
             self.next_tok(i)?;
             return Ok(());
         }
@@ -353,6 +876,7 @@ impl DeJsonState {
 
     pub fn curly_open(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
         if self.tok == DeJsonTok::CurlyOpen {
+            self.enter()?;
             self.next_tok(i)?;
             return Ok(());
         }
@@ -361,6 +885,7 @@ impl DeJsonState {
 
     pub fn curly_close(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
         if self.tok == DeJsonTok::CurlyClose {
+            self.depth = self.depth.saturating_sub(1);
             self.next_tok(i)?;
             return Ok(());
         }
@@ -374,6 +899,10 @@ impl DeJsonState {
             }
             return Ok(value);
         }
+        // only a value above u64::MAX is read as a u128
+        if let DeJsonTok::U128(value) = self.tok {
+            return Err(self.err_range(&format!("{}>{}", value, max)));
+        }
         Err(self.err_token("unsigned integer"))
     }
 
@@ -385,14 +914,46 @@ impl DeJsonState {
             return Ok(value);
         }
         if let DeJsonTok::U64(value) = self.tok {
-            if value as i64 > max {
+            // compared as u64, a value above i64::MAX would wrap negative
+            if value > max as u64 {
                 return Err(self.err_range(&format!("{}>{}", value, max)));
             }
             return Ok(value as i64);
         }
-        Err(self.err_token("signed integer"))
+        // only values outside of the 64 bit integers are read as 128 bit ones
+        match self.tok {
+            DeJsonTok::I128(value) => Err(self.err_range(&format!("{}<{}", value, min))),
+            DeJsonTok::U128(value) => Err(self.err_range(&format!("{}>{}", value, max))),
+            _ => Err(self.err_token("signed integer")),
+        }
+    }
+
+    pub fn as_i128(&mut self) -> Result<i128, DeJsonErr> {
+        match self.tok {
+            DeJsonTok::I64(value) => Ok(value as i128),
+            DeJsonTok::U64(value) => Ok(value as i128),
+            DeJsonTok::I128(value) => Ok(value),
+            DeJsonTok::U128(value) => {
+                if value > i128::MAX as u128 {
+                    return Err(self.err_range(&format!("{}>{}", value, i128::MAX)));
+                }
+                Ok(value as i128)
+            }
+            _ => Err(self.err_token("signed integer")),
+        }
+    }
+
+    pub fn as_u128(&mut self) -> Result<u128, DeJsonErr> {
+        match self.tok {
+            DeJsonTok::U64(value) => Ok(value as u128),
+            DeJsonTok::U128(value) => Ok(value),
+            _ => Err(self.err_token("unsigned integer")),
+        }
     }
 
+    /// The current token as a float. Besides numbers this accepts `null`, which
+    /// is how non-finite floats are written, as NaN, and the strings `"NaN"`,
+    /// `"inf"` and `"-inf"` written by `#[nserde(float_as_string)]`.
     pub fn as_f64(&mut self) -> Result<f64, DeJsonErr> {
         if let DeJsonTok::I64(value) = self.tok {
             return Ok(value as f64);
@@ -400,9 +961,26 @@ impl DeJsonState {
         if let DeJsonTok::U64(value) = self.tok {
             return Ok(value as f64);
         }
+        if let DeJsonTok::I128(value) = self.tok {
+            return Ok(value as f64);
+        }
+        if let DeJsonTok::U128(value) = self.tok {
+            return Ok(value as f64);
+        }
         if let DeJsonTok::F64(value) = self.tok {
             return Ok(value);
         }
+        if self.tok == DeJsonTok::Null {
+            return Ok(f64::NAN);
+        }
+        if self.tok == DeJsonTok::Str {
+            match self.strbuf.as_str() {
+                "NaN" => return Ok(f64::NAN),
+                "inf" => return Ok(f64::INFINITY),
+                "-inf" => return Ok(f64::NEG_INFINITY),
+                _ => {}
+            }
+        }
         Err(self.err_token("floating point"))
     }
 
@@ -422,6 +1000,29 @@ impl DeJsonState {
         Err(self.err_token("string"))
     }
 
+    /// Reads a run of digits into `numbuf`. A `_` is allowed between two digits
+    /// as a separator and left out, anywhere else it fails.
+    fn digits(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
+        let mut after_digit = false;
+        loop {
+            match self.cur {
+                '0'..='9' => {
+                    self.numbuf.push(self.cur);
+                    after_digit = true;
+                }
+                '_' => {
+                    self.next(i);
+                    if !after_digit || !self.cur.is_ascii_digit() {
+                        return Err(self.err_parse("number"));
+                    }
+                    continue;
+                }
+                _ => return Ok(()),
+            }
+            self.next(i);
+        }
+    }
+
     pub fn next_tok(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
         while self.cur == '\n' || self.cur == '\r' || self.cur == '\t' || self.cur == ' ' {
             self.next(i);
@@ -459,6 +1060,7 @@ impl DeJsonState {
                 }
             }
         }
+        self.tok_pos = (self.line, self.col, self.offset);
         match self.cur {
             ':' => {
                 self.next(i);
@@ -492,6 +1094,10 @@ impl DeJsonState {
             }
             '-' | '+' | '0'..='9' => {
                 self.numbuf.truncate(0);
+                // JSON only allows a leading `-`, JSON5 also allows a `+`
+                if self.cur == '+' && !self.json5 {
+                    return Err(self.err_parse("number"));
+                }
                 let is_neg = if self.cur == '-' || self.cur == '+' {
                     let sign = self.cur;
                     self.numbuf.push(self.cur);
@@ -500,19 +1106,13 @@ impl DeJsonState {
                 } else {
                     false
                 };
-                while self.cur >= '0' && self.cur <= '9' {
-                    self.numbuf.push(self.cur);
-                    self.next(i);
-                }
+                self.digits(i)?;
                 let mut is_float = false;
                 if self.cur == '.' {
                     is_float = true;
                     self.numbuf.push(self.cur);
                     self.next(i);
-                    while self.cur >= '0' && self.cur <= '9' {
-                        self.numbuf.push(self.cur);
-                        self.next(i);
-                    }
+                    self.digits(i)?;
                 }
                 if self.cur == 'e' || self.cur == 'E' {
                     is_float = true;
@@ -539,23 +1139,31 @@ impl DeJsonState {
                         if let Ok(num) = self.numbuf.parse() {
                             self.tok = DeJsonTok::I64(num);
                             return Ok(());
-                        } else {
-                            return Err(self.err_parse("number"));
                         }
+                        // too large for i64, keep the full magnitude for i128 fields
+                        if let Ok(num) = self.numbuf.parse() {
+                            self.tok = DeJsonTok::I128(num);
+                            return Ok(());
+                        }
+                        return Err(self.err_parse("number"));
                     }
                     if let Ok(num) = self.numbuf.parse() {
                         self.tok = DeJsonTok::U64(num);
-                        Ok(())
-                    } else {
-                        Err(self.err_parse("number"))
+                        return Ok(());
+                    }
+                    if let Ok(num) = self.numbuf.parse() {
+                        self.tok = DeJsonTok::U128(num);
+                        return Ok(());
                     }
+                    Err(self.err_parse("number"))
                 }
             }
-            'a'..='z' | 'A'..='Z' | '_' => {
+            'a'..='z' | 'A'..='Z' | '_' | '$' if self.cur != '$' || self.json5 => {
                 self.identbuf.truncate(0);
                 while self.cur >= 'a' && self.cur <= 'z'
                     || self.cur >= 'A' && self.cur <= 'Z'
                     || self.cur == '_'
+                    || self.json5 && (self.cur >= '0' && self.cur <= '9' || self.cur == '$')
                 {
                     self.identbuf.push(self.cur);
                     self.next(i);
@@ -573,15 +1181,19 @@ impl DeJsonState {
                     return Ok(());
                 }
                 self.tok = DeJsonTok::BareIdent;
+                if self.json5 {
+                    return Ok(());
+                }
                 Err(self.err_token(&format!(
                     "Got ##{}## needed true, false, null",
                     self.identbuf
                 )))
             }
-            '"' => {
+            '"' | '\'' if self.cur == '"' || self.json5 => {
+                let quote = self.cur;
                 self.strbuf.truncate(0);
                 self.next(i);
-                while self.cur != '"' {
+                while self.cur != quote {
                     if self.cur == '\\' {
                         self.next(i);
                         match self.cur {
@@ -716,9 +1328,47 @@ macro_rules! impl_ser_de_json_signed {
 
 macro_rules! impl_ser_de_json_float {
     ( $ ty: ident) => {
+        /// JSON has no NaN or infinity, so non-finite values are written as
+        /// `null` and read back as NaN. Use `#[nserde(float_as_string)]` to keep them.
         impl SerJson for $ty {
             fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
-                s.out.push_str(&format!("{self:?}"));
+                if self.is_finite() {
+                    s.out.push_str(&format!("{self:?}"));
+                } else {
+                    s.out.push_str("null");
+                }
+            }
+        }
+
+        impl From<&$ty> for FloatAsString<$ty> {
+            fn from(value: &$ty) -> Self {
+                FloatAsString(*value)
+            }
+        }
+
+        impl From<&FloatAsString<$ty>> for $ty {
+            fn from(value: &FloatAsString<$ty>) -> Self {
+                value.0
+            }
+        }
+
+        impl SerJson for FloatAsString<$ty> {
+            fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+                if self.0.is_nan() {
+                    s.out.push_str("\"NaN\"");
+                } else if self.0 == <$ty>::INFINITY {
+                    s.out.push_str("\"inf\"");
+                } else if self.0 == <$ty>::NEG_INFINITY {
+                    s.out.push_str("\"-inf\"");
+                } else {
+                    self.0.ser_json(d, s);
+                }
+            }
+        }
+
+        impl DeJson for FloatAsString<$ty> {
+            fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+                <$ty>::de_json(s, i).map(FloatAsString)
             }
         }
 
@@ -742,31 +1392,96 @@ impl_ser_de_json_signed!(i64, i64::MIN, i64::MAX);
 impl_ser_de_json_signed!(i32, i32::MIN, i32::MAX);
 impl_ser_de_json_signed!(i16, i16::MIN, i16::MAX);
 impl_ser_de_json_signed!(i8, i8::MIN, i8::MAX);
+/// Proxy used by `#[nserde(float_as_string)]`, writes NaN and the infinities as
+/// the strings `"NaN"`, `"inf"` and `"-inf"` instead of `null` so they survive a
+/// round trip. Finite values are written as numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct FloatAsString<T>(pub T);
+
 impl_ser_de_json_float!(f64);
 impl_ser_de_json_float!(f32);
 
-impl<T> SerJson for Option<T>
-where
-    T: SerJson,
-{
-    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        if let Some(v) = self {
-            v.ser_json(d, s);
-        } else {
-            s.out.push_str("null");
-        }
+impl SerJson for i128 {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        s.out.push_str(&self.to_string());
     }
 }
 
-impl<T> DeJson for Option<T>
-where
-    T: DeJson,
-{
-    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
-        if let DeJsonTok::Null = s.tok {
-            s.next_tok(i)?;
-            return Ok(None);
-        }
+impl DeJson for i128 {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<i128, DeJsonErr> {
+        let val = s.as_i128()?;
+        s.next_tok(i)?;
+        Ok(val)
+    }
+}
+
+impl SerJson for u128 {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        s.out.push_str(&self.to_string());
+    }
+}
+
+impl DeJson for u128 {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<u128, DeJsonErr> {
+        let val = s.as_u128()?;
+        s.next_tok(i)?;
+        Ok(val)
+    }
+}
+
+macro_rules! impl_ser_de_json_nonzero {
+    ($($ty:ident($inner:ident)),* $(,)?) => {$(
+        impl SerJson for core::num::$ty {
+            fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+                self.get().ser_json(d, s)
+            }
+        }
+
+        impl DeJson for core::num::$ty {
+            fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<core::num::$ty, DeJsonErr> {
+                let val: $inner = DeJson::de_json(s, i)?;
+                core::num::$ty::new(val).ok_or_else(|| s.err_parse(concat!(stringify!($ty), ", it is zero")))
+            }
+        }
+    )*};
+}
+
+impl_ser_de_json_nonzero!(
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroUsize(usize),
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128),
+);
+
+impl<T> SerJson for Option<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        if let Some(v) = self {
+            v.ser_json(d, s);
+        } else {
+            s.out.push_str("null");
+        }
+    }
+}
+
+impl<T> DeJson for Option<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        if let DeJsonTok::Null = s.tok {
+            s.next_tok(i)?;
+            return Ok(None);
+        }
         Ok(Some(DeJson::de_json(s, i)?))
     }
 }
@@ -788,6 +1503,19 @@ impl DeJson for () {
     }
 }
 
+/// A zero sized marker, written as `null` like `()`.
+impl<T: ?Sized> SerJson for core::marker::PhantomData<T> {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ().ser_json(d, s)
+    }
+}
+
+impl<T: ?Sized> DeJson for core::marker::PhantomData<T> {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        <()>::de_json(s, i).map(|_| core::marker::PhantomData)
+    }
+}
+
 impl SerJson for bool {
     fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
         if *self {
@@ -806,28 +1534,32 @@ impl DeJson for bool {
     }
 }
 
+fn push_json_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\x08' => *out += "\\b",
+            '\x0C' => *out += "\\f",
+            '\n' => *out += "\\n",
+            '\r' => *out += "\\r",
+            '\t' => *out += "\\t",
+            _ if c.is_ascii_control() => {
+                use core::fmt::Write as _;
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            '\\' => *out += "\\\\",
+            '"' => *out += "\\\"",
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 macro_rules! impl_ser_json_string {
     ($ty: ident) => {
         impl SerJson for $ty {
             fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
-                s.out.push('"');
-                for c in self.chars() {
-                    match c {
-                        '\x08' => s.out += "\\b",
-                        '\x0C' => s.out += "\\f",
-                        '\n' => s.out += "\\n",
-                        '\r' => s.out += "\\r",
-                        '\t' => s.out += "\\t",
-                        _ if c.is_ascii_control() => {
-                            use core::fmt::Write as _;
-                            let _ = write!(s.out, "\\u{:04x}", c as u32);
-                        }
-                        '\\' => s.out += "\\\\",
-                        '"' => s.out += "\\\"",
-                        _ => s.out.push(c),
-                    }
-                }
-                s.out.push('"');
+                push_json_str(&mut s.out, self);
             }
         }
     };
@@ -836,6 +1568,26 @@ macro_rules! impl_ser_json_string {
 impl_ser_json_string!(String);
 impl_ser_json_string!(str);
 
+impl SerJson for char {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        push_json_str(&mut s.out, self.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl DeJson for char {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<char, DeJsonErr> {
+        let val = s.as_string()?;
+        let mut chars = val.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                s.next_tok(i)?;
+                Ok(c)
+            }
+            _ => Err(s.err_parse("char")),
+        }
+    }
+}
+
 impl DeJson for String {
     fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<String, DeJsonErr> {
         let val = s.as_string()?;
@@ -844,11 +1596,19 @@ impl DeJson for String {
     }
 }
 
+/// Rough number of bytes a JSON array element of type `T` serializes to,
+/// used to reserve the output up front. Primitives take about two characters
+/// per byte of their size, the cap keeps large types from over-reserving.
+fn json_size_hint<T>() -> usize {
+    core::mem::size_of::<T>().clamp(1, 8) * 2 + 1
+}
+
 impl<T> SerJson for Vec<T>
 where
     T: SerJson,
 {
     fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        s.out.reserve(self.len() * json_size_hint::<T>() + 2);
         s.out.push('[');
         if !self.is_empty() {
             let last = self.len() - 1;
@@ -856,7 +1616,7 @@ where
                 s.indent(d + 1);
                 item.ser_json(d + 1, s);
                 if index != last {
-                    s.out.push(',');
+                    s.conl();
                 }
             }
         }
@@ -887,19 +1647,33 @@ where
     T: SerJson,
 {
     fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        s.out.push('[');
-        if !self.is_empty() {
-            let last = self.len() - 1;
-            for (index, item) in self.iter().enumerate() {
-                s.indent(d + 1);
-                item.ser_json(d + 1, s);
-                if index != last {
-                    s.out.push(',');
-                }
-            }
+        if s.canonical {
+            let mut items: Vec<_> = self.iter().collect();
+            items.sort_by_cached_key(|item| item.serialize_json());
+            ser_json_items(items.into_iter(), d, s);
+        } else {
+            ser_json_items(self.iter(), d, s);
+        }
+    }
+}
+
+fn ser_json_items<'a, T>(
+    items: impl ExactSizeIterator<Item = &'a T>,
+    d: usize,
+    s: &mut SerJsonState,
+) where
+    T: SerJson + 'a,
+{
+    s.out.push('[');
+    let len = items.len();
+    for (index, item) in items.enumerate() {
+        s.indent(d + 1);
+        item.ser_json(d + 1, s);
+        if index + 1 != len {
+            s.conl();
         }
-        s.out.push(']');
     }
+    s.out.push(']');
 }
 
 #[cfg(feature = "std")]
@@ -932,7 +1706,7 @@ where
                 s.indent(d + 1);
                 item.ser_json(d + 1, s);
                 if index != last {
-                    s.out.push(',');
+                    s.conl();
                 }
             }
         }
@@ -957,6 +1731,43 @@ where
     }
 }
 
+impl<T> SerJson for VecDeque<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_items(self.iter(), d, s);
+    }
+}
+
+impl<T> DeJson for VecDeque<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<VecDeque<T>, DeJsonErr> {
+        Vec::de_json(s, i).map(VecDeque::from)
+    }
+}
+
+/// The items are written in the heap's internal order, not sorted.
+impl<T> SerJson for BinaryHeap<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_items(self.iter(), d, s);
+    }
+}
+
+impl<T> DeJson for BinaryHeap<T>
+where
+    T: DeJson + Ord,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<BinaryHeap<T>, DeJsonErr> {
+        Vec::de_json(s, i).map(BinaryHeap::from)
+    }
+}
+
 impl<T> SerJson for BTreeSet<T>
 where
     T: SerJson,
@@ -969,7 +1780,7 @@ where
                 s.indent(d + 1);
                 item.ser_json(d + 1, s);
                 if index != last {
-                    s.out.push(',');
+                    s.conl();
                 }
             }
         }
@@ -994,17 +1805,23 @@ where
     }
 }
 
+/// Slices serialize as JSON arrays, so `slice.serialize_json()` works at the top level
+/// without first collecting into a `Vec`.
 impl<T> SerJson for [T]
 where
     T: SerJson,
 {
     fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        s.out.reserve(self.len() * json_size_hint::<T>() + 2);
         s.out.push('[');
-        let last = self.len() - 1;
-        for (index, item) in self.iter().enumerate() {
-            item.ser_json(d + 1, s);
-            if index != last {
-                s.out.push(',');
+        if !self.is_empty() {
+            let last = self.len() - 1;
+            for (index, item) in self.iter().enumerate() {
+                s.indent(d + 1);
+                item.ser_json(d + 1, s);
+                if index != last {
+                    s.conl();
+                }
             }
         }
         s.out.push(']');
@@ -1028,6 +1845,10 @@ where
     fn de_json(o: &mut DeJsonState, d: &mut Chars) -> Result<Self, DeJsonErr> {
         use core::mem::MaybeUninit;
 
+        if core::mem::size_of::<[T; N]>() > MAX_STACK_ARRAY_SIZE {
+            return de_json_array_heap(o, d).map(|boxed| *boxed);
+        }
+
         // waiting for uninit_array(or for array::try_from_fn) stabilization
         // https://github.com/rust-lang/rust/issues/96097
         // https://github.com/rust-lang/rust/issues/89379
@@ -1064,6 +1885,31 @@ where
     }
 }
 
+/// Arrays larger than this many bytes are deserialized through a heap buffer
+/// rather than a `[MaybeUninit<T>; N]` on the stack.
+const MAX_STACK_ARRAY_SIZE: usize = 16 * 1024;
+
+fn de_json_array_heap<T, const N: usize>(
+    o: &mut DeJsonState,
+    d: &mut Chars,
+) -> Result<Box<[T; N]>, DeJsonErr>
+where
+    T: DeJson,
+{
+    // already deserialized elements are dropped by the Vec on error
+    let mut to = Vec::with_capacity(N);
+    o.block_open(d)?;
+    for _ in 0..N {
+        to.push(de_json_comma_block(o, d)?);
+    }
+    o.block_close(d)?;
+
+    match to.into_boxed_slice().try_into() {
+        Ok(boxed) => Ok(boxed),
+        Err(_) => unreachable!("exactly N elements were pushed"),
+    }
+}
+
 fn de_json_comma_block<T>(s: &mut DeJsonState, i: &mut Chars) -> Result<T, DeJsonErr>
 where
     T: DeJson,
@@ -1073,142 +1919,234 @@ where
     t
 }
 
-impl<A, B> SerJson for (A, B)
+macro_rules! impl_json_tuple {
+    ($first_name: ident . $first: tt $(, $name: ident . $index: tt)*) => {
+        impl<$first_name, $($name),*> SerJson for ($first_name, $($name),*)
+        where
+            $first_name: SerJson,
+            $($name: SerJson),*
+        {
+            fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+                s.out.push('[');
+                self.$first.ser_json(d, s);
+                $(
+                    s.conl();
+                    self.$index.ser_json(d, s);
+                )*
+                s.out.push(']');
+            }
+        }
+
+        impl<$first_name, $($name),*> DeJson for ($first_name, $($name),*)
+        where
+            $first_name: DeJson,
+            $($name: DeJson),*
+        {
+            fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+                s.block_open(i)?;
+                let r = (
+                    de_json_comma_block::<$first_name>(s, i)?,
+                    $(de_json_comma_block::<$name>(s, i)?,)*
+                );
+                s.block_close(i)?;
+                Ok(r)
+            }
+        }
+
+        impl<$first_name, $($name),*> DeJson for TupleDefaults<($first_name, $($name),*)>
+        where
+            $first_name: DeJson + Default,
+            $($name: DeJson + Default),*
+        {
+            fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+                s.block_open(i)?;
+                let r = (
+                    de_json_comma_block_or_default::<$first_name>(s, i)?,
+                    $(de_json_comma_block_or_default::<$name>(s, i)?,)*
+                );
+                s.block_close(i)?;
+                Ok(TupleDefaults(r))
+            }
+        }
+    };
+}
+
+/// Used by `#[nserde(tuple_defaults)]`, deserializes a tuple from an array that
+/// may be shorter than the tuple, the missing trailing elements are set to their
+/// default. A longer array is still an error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TupleDefaults<T>(pub T);
+
+fn de_json_comma_block_or_default<T>(s: &mut DeJsonState, i: &mut Chars) -> Result<T, DeJsonErr>
 where
-    A: SerJson,
-    B: SerJson,
+    T: DeJson + Default,
 {
-    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        s.out.push('[');
-        self.0.ser_json(d, s);
-        s.out.push(',');
-        self.1.ser_json(d, s);
-        s.out.push(']');
+    if s.tok == DeJsonTok::BlockClose {
+        return Ok(T::default());
     }
+    de_json_comma_block(s, i)
+}
+
+impl_json_tuple!(A.0, B.1);
+impl_json_tuple!(A.0, B.1, C.2);
+impl_json_tuple!(A.0, B.1, C.2, D.3);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+impl_json_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
+
+/// A map key type, written as the string of a JSON object key.
+///
+/// JSON object keys are always strings, so only types with a plain string form
+/// can be map keys: the integers, `char`, `bool` and the string types.
+pub trait ToJsonMapKey {
+    /// The key, before it is escaped and quoted.
+    fn to_json_map_key(&self) -> Cow<'_, str>;
+}
+
+/// A map key type, read back from the string of a JSON object key, see
+/// [`ToJsonMapKey`].
+pub trait FromJsonMapKey: Sized {
+    /// Parse the key from the unescaped contents of the key string, reporting
+    /// an invalid key through `s`.
+    fn from_json_map_key(s: &DeJsonState, key: String) -> Result<Self, DeJsonErr>;
 }
 
-impl<A, B> DeJson for (A, B)
+impl<T> ToJsonMapKey for &T
 where
-    A: DeJson,
-    B: DeJson,
+    T: ToJsonMapKey + ?Sized,
 {
-    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<(A, B), DeJsonErr> {
-        s.block_open(i)?;
-        let r = (de_json_comma_block(s, i)?, de_json_comma_block(s, i)?);
-        s.block_close(i)?;
-        Ok(r)
+    fn to_json_map_key(&self) -> Cow<'_, str> {
+        (**self).to_json_map_key()
     }
 }
 
-impl<A, B, C> SerJson for (A, B, C)
-where
-    A: SerJson,
-    B: SerJson,
-    C: SerJson,
-{
-    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        s.out.push('[');
-        self.0.ser_json(d, s);
-        s.out.push(',');
-        self.1.ser_json(d, s);
-        s.out.push(',');
-        self.2.ser_json(d, s);
-        s.out.push(']');
+impl ToJsonMapKey for str {
+    fn to_json_map_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
     }
 }
 
-impl<A, B, C> DeJson for (A, B, C)
-where
-    A: DeJson,
-    B: DeJson,
-    C: DeJson,
-{
-    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<(A, B, C), DeJsonErr> {
-        s.block_open(i)?;
-        let r = (
-            de_json_comma_block(s, i)?,
-            de_json_comma_block(s, i)?,
-            de_json_comma_block(s, i)?,
-        );
-        s.block_close(i)?;
-        Ok(r)
+impl ToJsonMapKey for String {
+    fn to_json_map_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
     }
 }
 
-impl<A, B, C, D> SerJson for (A, B, C, D)
-where
-    A: SerJson,
-    B: SerJson,
-    C: SerJson,
-    D: SerJson,
-{
-    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        s.out.push('[');
-        self.0.ser_json(d, s);
-        s.out.push(',');
-        self.1.ser_json(d, s);
-        s.out.push(',');
-        self.2.ser_json(d, s);
-        s.out.push(',');
-        self.3.ser_json(d, s);
-        s.out.push(']');
+impl FromJsonMapKey for String {
+    fn from_json_map_key(_s: &DeJsonState, key: String) -> Result<Self, DeJsonErr> {
+        Ok(key)
     }
 }
 
-impl<A, B, C, D> DeJson for (A, B, C, D)
-where
-    A: DeJson,
-    B: DeJson,
-    C: DeJson,
-    D: DeJson,
-{
-    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<(A, B, C, D), DeJsonErr> {
-        s.block_open(i)?;
-        let r = (
-            de_json_comma_block(s, i)?,
-            de_json_comma_block(s, i)?,
-            de_json_comma_block(s, i)?,
-            de_json_comma_block(s, i)?,
-        );
-        s.block_close(i)?;
-        Ok(r)
+impl ToJsonMapKey for Cow<'_, str> {
+    fn to_json_map_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl FromJsonMapKey for Cow<'_, str> {
+    fn from_json_map_key(_s: &DeJsonState, key: String) -> Result<Self, DeJsonErr> {
+        Ok(Cow::Owned(key))
+    }
+}
+
+impl ToJsonMapKey for char {
+    fn to_json_map_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+impl FromJsonMapKey for char {
+    fn from_json_map_key(s: &DeJsonState, key: String) -> Result<Self, DeJsonErr> {
+        let mut chars = key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(s.err_parse(&format!("map key {:?} as char", key))),
+        }
     }
 }
 
+macro_rules! impl_json_map_key {
+    ($($ty:ident),*) => {$(
+        impl ToJsonMapKey for $ty {
+            fn to_json_map_key(&self) -> Cow<'_, str> {
+                Cow::Owned(self.to_string())
+            }
+        }
+
+        impl FromJsonMapKey for $ty {
+            fn from_json_map_key(s: &DeJsonState, key: String) -> Result<Self, DeJsonErr> {
+                key.parse()
+                    .map_err(|_| s.err_parse(&format!("map key {:?} as {}", key, stringify!($ty))))
+            }
+        }
+    )*};
+}
+
+impl_json_map_key!(bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 #[cfg(feature = "std")]
 impl<K, V> SerJson for std::collections::HashMap<K, V>
 where
-    K: SerJson,
+    K: ToJsonMapKey,
     V: SerJson,
 {
     fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        s.out.push('{');
-        let len = self.len();
-        for (index, (k, v)) in self.iter().enumerate() {
-            s.indent(d + 1);
-            k.ser_json(d + 1, s);
-            s.out.push(':');
-            v.ser_json(d + 1, s);
-            if (index + 1) < len {
-                s.conl();
-            }
+        if s.canonical {
+            let mut entries: Vec<_> = self.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.to_json_map_key().cmp(&b.to_json_map_key()));
+            ser_json_map(entries, d, s);
+        } else {
+            ser_json_map(self.iter(), d, s);
         }
-        s.indent(d);
-        s.out.push('}');
     }
 }
 
+/// Serialize `(key, value)` pairs as a JSON object, without collecting them
+/// into a map first. Keys which aren't strings, like numbers, are written quoted.
+///
+/// ```rust
+/// # use nanoserde::*;
+/// let mut s = SerJsonState::new(String::new());
+/// ser_json_map([("a", 1), ("b", 2)].iter().map(|(k, v)| (k.to_string(), *v)), 0, &mut s);
+/// assert_eq!(s.out, r#"{"a":1,"b":2}"#);
+/// ```
+pub fn ser_json_map<I, K, V>(pairs: I, d: usize, s: &mut SerJsonState)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: ToJsonMapKey,
+    V: SerJson,
+{
+    s.out.push('{');
+    for (index, (k, v)) in pairs.into_iter().enumerate() {
+        if index > 0 {
+            s.conl();
+        }
+        s.indent(d + 1);
+        push_json_str(&mut s.out, &k.to_json_map_key());
+        s.out.push(':');
+        v.ser_json(d + 1, s);
+    }
+    s.indent(d);
+    s.out.push('}');
+}
+
 #[cfg(feature = "std")]
 impl<K, V> DeJson for std::collections::HashMap<K, V>
 where
-    K: DeJson + Eq + core::hash::Hash,
+    K: FromJsonMapKey + Eq + core::hash::Hash,
     V: DeJson,
 {
     fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
         let mut h = std::collections::HashMap::new();
         s.curly_open(i)?;
         while s.tok != DeJsonTok::CurlyClose {
-            let k = DeJson::de_json(s, i)?;
+            let k = de_json_map_key(s, i)?;
             s.colon(i)?;
             let v = DeJson::de_json(s, i)?;
             s.eat_comma_curly(i)?;
@@ -1221,36 +2159,44 @@ where
 
 impl<K, V> SerJson for BTreeMap<K, V>
 where
-    K: SerJson,
+    K: ToJsonMapKey,
     V: SerJson,
 {
     fn ser_json(&self, d: usize, s: &mut SerJsonState) {
-        s.out.push('{');
-        let len = self.len();
-        for (index, (k, v)) in self.iter().enumerate() {
-            s.indent(d + 1);
-            k.ser_json(d + 1, s);
-            s.out.push(':');
-            v.ser_json(d + 1, s);
-            if (index + 1) < len {
-                s.conl();
-            }
-        }
-        s.indent(d);
-        s.out.push('}');
+        ser_json_map(self.iter(), d, s);
     }
 }
 
+/// Reads the key of a map entry, which in JSON5 mode may be an unquoted identifier.
+fn de_json_map_key<K>(s: &mut DeJsonState, i: &mut Chars) -> Result<K, DeJsonErr>
+where
+    K: FromJsonMapKey,
+{
+    s.next_key();
+    let key = match s.tok {
+        DeJsonTok::Str => core::mem::take(&mut s.strbuf),
+        // bare numbers, as written by older versions
+        DeJsonTok::U64(n) => n.to_string(),
+        DeJsonTok::I64(n) => n.to_string(),
+        DeJsonTok::U128(n) => n.to_string(),
+        DeJsonTok::I128(n) => n.to_string(),
+        _ => return Err(s.err_token("map key")),
+    };
+    let k = K::from_json_map_key(s, key)?;
+    s.next_tok(i)?;
+    Ok(k)
+}
+
 impl<K, V> DeJson for BTreeMap<K, V>
 where
-    K: DeJson + Eq + Ord,
+    K: FromJsonMapKey + Eq + Ord,
     V: DeJson,
 {
     fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
         let mut h = BTreeMap::new();
         s.curly_open(i)?;
         while s.tok != DeJsonTok::CurlyClose {
-            let k = DeJson::de_json(s, i)?;
+            let k = de_json_map_key(s, i)?;
             s.colon(i)?;
             let v = DeJson::de_json(s, i)?;
             s.eat_comma_curly(i)?;
@@ -1261,6 +2207,15 @@ where
     }
 }
 
+impl<T> SerJson for &T
+where
+    T: SerJson + ?Sized,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        (**self).ser_json(d, s)
+    }
+}
+
 impl<T> SerJson for Box<T>
 where
     T: SerJson,
@@ -1278,3 +2233,787 @@ where
         Ok(Box::new(DeJson::de_json(s, i)?))
     }
 }
+
+impl<T> SerJson for core::num::Wrapping<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.0.ser_json(d, s)
+    }
+}
+
+impl<T> DeJson for core::num::Wrapping<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<core::num::Wrapping<T>, DeJsonErr> {
+        Ok(core::num::Wrapping(DeJson::de_json(s, i)?))
+    }
+}
+
+impl<T> SerJson for Rc<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        (**self).ser_json(d, s)
+    }
+}
+
+impl<T> DeJson for Rc<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Rc<T>, DeJsonErr> {
+        Ok(Rc::new(DeJson::de_json(s, i)?))
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> SerJson for Arc<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        (**self).ser_json(d, s)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> DeJson for Arc<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Arc<T>, DeJsonErr> {
+        Ok(Arc::new(DeJson::de_json(s, i)?))
+    }
+}
+
+impl<'a, B> SerJson for Cow<'a, B>
+where
+    B: ?Sized + ToOwned + SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        (**self).ser_json(d, s)
+    }
+}
+
+impl<'a, B> DeJson for Cow<'a, B>
+where
+    B: ?Sized + ToOwned,
+    B::Owned: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Cow<'a, B>, DeJsonErr> {
+        Ok(Cow::Owned(DeJson::de_json(s, i)?))
+    }
+}
+
+impl SerJson for core::num::FpCategory {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        use core::num::FpCategory::*;
+        s.label(match self {
+            Nan => "Nan",
+            Infinite => "Infinite",
+            Zero => "Zero",
+            Subnormal => "Subnormal",
+            Normal => "Normal",
+        });
+    }
+}
+
+impl DeJson for core::num::FpCategory {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        use core::num::FpCategory::*;
+        s.string(i)?;
+        Ok(match s.strbuf.as_ref() {
+            "Nan" => Nan,
+            "Infinite" => Infinite,
+            "Zero" => Zero,
+            "Subnormal" => Subnormal,
+            "Normal" => Normal,
+            _ => return Err(s.err_enum(&s.strbuf)),
+        })
+    }
+}
+
+impl SerJson for core::cmp::Ordering {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        use core::cmp::Ordering::*;
+        s.label(match self {
+            Less => "Less",
+            Equal => "Equal",
+            Greater => "Greater",
+        });
+    }
+}
+
+impl DeJson for core::cmp::Ordering {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        use core::cmp::Ordering::*;
+        s.string(i)?;
+        Ok(match s.strbuf.as_ref() {
+            "Less" => Less,
+            "Equal" => Equal,
+            "Greater" => Greater,
+            _ => return Err(s.err_enum(&s.strbuf)),
+        })
+    }
+}
+
+impl<T> SerJson for core::ops::Bound<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        use core::ops::Bound::*;
+        let (label, v) = match self {
+            Included(v) => ("Included", v),
+            Excluded(v) => ("Excluded", v),
+            Unbounded => {
+                s.label("Unbounded");
+                return;
+            }
+        };
+        s.out.push('{');
+        s.label(label);
+        s.out.push(':');
+        s.out.push('[');
+        v.ser_json(d, s);
+        s.out.push(']');
+        s.out.push('}');
+    }
+}
+
+impl<T> DeJson for core::ops::Bound<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        use core::ops::Bound::*;
+        match s.tok {
+            DeJsonTok::CurlyOpen => {
+                s.curly_open(i)?;
+                s.string(i)?;
+                s.colon(i)?;
+                let r = match s.strbuf.as_ref() {
+                    "Included" => Included(de_json_variant_payload(s, i)?),
+                    "Excluded" => Excluded(de_json_variant_payload(s, i)?),
+                    _ => return Err(s.err_enum(&s.strbuf)),
+                };
+                s.curly_close(i)?;
+                Ok(r)
+            }
+            DeJsonTok::Str => {
+                s.string(i)?;
+                match s.strbuf.as_ref() {
+                    "Unbounded" => Ok(Unbounded),
+                    _ => Err(s.err_enum(&s.strbuf)),
+                }
+            }
+            _ => Err(s.err_token("String or {")),
+        }
+    }
+}
+
+/// Reads the `[value]` payload of an externally tagged single field tuple variant.
+fn de_json_variant_payload<T>(s: &mut DeJsonState, i: &mut Chars) -> Result<T, DeJsonErr>
+where
+    T: DeJson,
+{
+    s.block_open(i)?;
+    let r = de_json_comma_block(s, i)?;
+    s.block_close(i)?;
+    Ok(r)
+}
+
+impl<T> SerJson for core::ops::Range<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_range(Some(&self.start), Some(&self.end), d, s)
+    }
+}
+
+impl<T> DeJson for core::ops::Range<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let (start, end) = de_json_range(s, i, &["start", "end"])?;
+        let start = start.ok_or_else(|| s.err_nf_keys("start", &["start", "end"]))?;
+        let end = end.ok_or_else(|| s.err_nf_keys("end", &["start", "end"]))?;
+        Ok(start..end)
+    }
+}
+
+impl<T> SerJson for core::ops::RangeInclusive<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_range(Some(self.start()), Some(self.end()), d, s)
+    }
+}
+
+impl<T> DeJson for core::ops::RangeInclusive<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let (start, end) = de_json_range(s, i, &["start", "end"])?;
+        let start = start.ok_or_else(|| s.err_nf_keys("start", &["start", "end"]))?;
+        let end = end.ok_or_else(|| s.err_nf_keys("end", &["start", "end"]))?;
+        Ok(core::ops::RangeInclusive::new(start, end))
+    }
+}
+
+impl<T> SerJson for core::ops::RangeFrom<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_range(Some(&self.start), None, d, s)
+    }
+}
+
+impl<T> DeJson for core::ops::RangeFrom<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let (start, _) = de_json_range::<T>(s, i, &["start"])?;
+        let start = start.ok_or_else(|| s.err_nf_keys("start", &["start"]))?;
+        Ok(start..)
+    }
+}
+
+impl<T> SerJson for core::ops::RangeTo<T>
+where
+    T: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_range(None, Some(&self.end), d, s)
+    }
+}
+
+impl<T> DeJson for core::ops::RangeTo<T>
+where
+    T: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let (_, end) = de_json_range::<T>(s, i, &["end"])?;
+        let end = end.ok_or_else(|| s.err_nf_keys("end", &["end"]))?;
+        Ok(..end)
+    }
+}
+
+/// `..` is written as `{}`, `null` is accepted as well.
+impl SerJson for core::ops::RangeFull {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        ser_json_range::<()>(None, None, d, s)
+    }
+}
+
+impl DeJson for core::ops::RangeFull {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        if s.tok == DeJsonTok::Null {
+            s.next_tok(i)?;
+        } else {
+            de_json_range::<()>(s, i, &[])?;
+        }
+        Ok(..)
+    }
+}
+
+/// Writes a range as `{"start":..,"end":..}`, leaving out the unbounded sides.
+fn ser_json_range<T>(start: Option<&T>, end: Option<&T>, d: usize, s: &mut SerJsonState)
+where
+    T: SerJson,
+{
+    s.st_pre();
+    if let Some(start) = start {
+        s.field(d + 1, "start");
+        start.ser_json(d + 1, s);
+    }
+    if let Some(end) = end {
+        if start.is_some() {
+            s.conl();
+        }
+        s.field(d + 1, "end");
+        end.ser_json(d + 1, s);
+    }
+    s.st_post(d);
+}
+
+/// Reads the `start` and `end` of a range, failing on any key not in `keys`.
+fn de_json_range<T>(
+    s: &mut DeJsonState,
+    i: &mut Chars,
+    keys: &'static [&'static str],
+) -> Result<(Option<T>, Option<T>), DeJsonErr>
+where
+    T: DeJson,
+{
+    let mut start = None;
+    let mut end = None;
+    s.curly_open(i)?;
+    while s.next_key().is_some() {
+        match s.strbuf.as_ref() {
+            "start" if keys.contains(&"start") => {
+                s.next_colon(i)?;
+                start = Some(T::de_json(s, i)?);
+            }
+            "end" if keys.contains(&"end") => {
+                s.next_colon(i)?;
+                end = Some(T::de_json(s, i)?);
+            }
+            _ => return Err(s.err_exp_keys(&s.strbuf, keys)),
+        }
+        s.eat_comma_curly(i)?;
+    }
+    s.curly_close(i)?;
+    Ok((start, end))
+}
+
+impl SerJson for core::time::Duration {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        s.st_pre();
+        s.field(d + 1, "secs");
+        self.as_secs().ser_json(d + 1, s);
+        s.conl();
+        s.field(d + 1, "nanos");
+        self.subsec_nanos().ser_json(d + 1, s);
+        s.st_post(d);
+    }
+}
+
+impl DeJson for core::time::Duration {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let mut secs = None;
+        let mut nanos = None;
+        s.curly_open(i)?;
+        while s.next_key().is_some() {
+            match s.strbuf.as_ref() {
+                "secs" => {
+                    s.next_colon(i)?;
+                    secs = Some(u64::de_json(s, i)?);
+                }
+                "nanos" => {
+                    s.next_colon(i)?;
+                    let value = s.u64_range(u64::MAX)?;
+                    if value > 999_999_999 {
+                        return Err(s.err_custom(&format!(
+                            "Duration nanos {} is not below 1000000000",
+                            value
+                        )));
+                    }
+                    s.next_tok(i)?;
+                    nanos = Some(value as u32);
+                }
+                _ => return Err(s.err_exp_keys(&s.strbuf, &["secs", "nanos"])),
+            }
+            s.eat_comma_curly(i)?;
+        }
+        s.curly_close(i)?;
+        match (secs, nanos) {
+            (Some(secs), Some(nanos)) => Ok(core::time::Duration::new(secs, nanos)),
+            (None, _) => Err(s.err_nf_keys("secs", &["secs", "nanos"])),
+            (_, None) => Err(s.err_nf_keys("nanos", &["secs", "nanos"])),
+        }
+    }
+}
+
+impl SerJson for crate::RelativeInstant {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.0.ser_json(d, s)
+    }
+}
+
+impl DeJson for crate::RelativeInstant {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        core::time::Duration::de_json(s, i).map(crate::RelativeInstant)
+    }
+}
+
+/// Proxy used by `#[nserde(duration = "iso8601")]`, stores a `Duration` as an
+/// ISO 8601 duration string like `"PT1.5S"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Iso8601Duration(pub core::time::Duration);
+
+impl From<&core::time::Duration> for Iso8601Duration {
+    fn from(value: &core::time::Duration) -> Self {
+        Iso8601Duration(*value)
+    }
+}
+
+impl From<&Iso8601Duration> for core::time::Duration {
+    fn from(value: &Iso8601Duration) -> Self {
+        value.0
+    }
+}
+
+impl SerJson for Iso8601Duration {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        use core::fmt::Write as _;
+        let _ = write!(s.out, "\"PT{}", self.0.as_secs());
+        let nanos = self.0.subsec_nanos();
+        if nanos != 0 {
+            let frac = format!("{:09}", nanos);
+            s.out.push('.');
+            s.out.push_str(frac.trim_end_matches('0'));
+        }
+        s.out.push_str("S\"");
+    }
+}
+
+impl DeJson for Iso8601Duration {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        let val = s.as_string()?;
+        let duration =
+            parse_iso8601_duration(&val).ok_or_else(|| s.err_parse("ISO 8601 duration"))?;
+        s.next_tok(i)?;
+        Ok(Iso8601Duration(duration))
+    }
+}
+
+/// Parses `PnWnDTnHnMnS` durations, every component is optional but at least one
+/// is required and only the seconds may have a fraction. Years and months are
+/// rejected as they don't have a fixed length.
+fn parse_iso8601_duration(input: &str) -> Option<core::time::Duration> {
+    let rest = input.strip_prefix('P')?;
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, time),
+        None if rest.is_empty() => return None,
+        None => (rest, ""),
+    };
+
+    let mut secs = 0u64;
+    let mut nanos = 0u32;
+    for (part, units) in [
+        (date, &[('W', 7 * 24 * 3600), ('D', 24 * 3600)][..]),
+        (time, &[('H', 3600), ('M', 60), ('S', 1)][..]),
+    ] {
+        let mut units = units.iter();
+        let mut rest = part;
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',')?;
+            let (number, tail) = rest.split_at(end);
+            let designator = tail.chars().next()?;
+            // designators have to be in order, so only look at the remaining ones
+            let &(_, scale) = units.find(|(unit, _)| *unit == designator)?;
+            rest = &tail[designator.len_utf8()..];
+
+            let (whole, frac) = match number.split_once(['.', ',']) {
+                Some((whole, frac)) => (whole, Some(frac)),
+                None => (number, None),
+            };
+            if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            secs = secs.checked_add(whole.parse::<u64>().ok()?.checked_mul(scale)?)?;
+
+            if let Some(frac) = frac {
+                if scale != 1 || frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                // digits past nanosecond precision are truncated
+                for (index, b) in frac.bytes().take(9).enumerate() {
+                    nanos += u32::from(b - b'0') * 10u32.pow(8 - index as u32);
+                }
+            }
+        }
+    }
+    Some(core::time::Duration::new(secs, nanos))
+}
+
+/// `SystemTime` is stored as the `Duration` since `UNIX_EPOCH`.
+///
+/// Times before the epoch can't be represented and are serialized as `null`,
+/// which is rejected on deserialization.
+#[cfg(feature = "std")]
+impl SerJson for std::time::SystemTime {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .ser_json(d, s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeJson for std::time::SystemTime {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        if s.tok == DeJsonTok::Null {
+            return Err(s.err_range("SystemTime before UNIX_EPOCH"));
+        }
+        let since_epoch = core::time::Duration::de_json(s, i)?;
+        std::time::UNIX_EPOCH
+            .checked_add(since_epoch)
+            .ok_or_else(|| s.err_range("SystemTime overflow"))
+    }
+}
+
+/// `Infallible` has no values, so it never gets serialized and deserializing
+/// one is always an error. This lets `Result<T, Infallible>` be used.
+impl SerJson for core::convert::Infallible {
+    fn ser_json(&self, _d: usize, _s: &mut SerJsonState) {
+        match *self {}
+    }
+}
+
+impl DeJson for core::convert::Infallible {
+    fn de_json(s: &mut DeJsonState, _i: &mut Chars) -> Result<Self, DeJsonErr> {
+        Err(s.err_enum("Infallible"))
+    }
+}
+
+/// `Result` is externally tagged like a derived enum, but without the
+/// surrounding array: `{"Ok":5}` or `{"Err":"reason"}`.
+impl<T, E> SerJson for Result<T, E>
+where
+    T: SerJson,
+    E: SerJson,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        s.out.push('{');
+        match self {
+            Ok(v) => {
+                s.label("Ok");
+                s.out.push(':');
+                v.ser_json(d, s);
+            }
+            Err(e) => {
+                s.label("Err");
+                s.out.push(':');
+                e.ser_json(d, s);
+            }
+        }
+        s.out.push('}');
+    }
+}
+
+impl<T, E> DeJson for Result<T, E>
+where
+    T: DeJson,
+    E: DeJson,
+{
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        s.curly_open(i)?;
+        s.string(i)?;
+        // the payload may be a string itself, so resolve the tag before
+        // moving past the colon
+        let is_ok = match s.strbuf.as_ref() {
+            "Ok" => true,
+            "Err" => false,
+            _ => return Err(s.err_enum(&s.strbuf)),
+        };
+        s.colon(i)?;
+        let r = if is_ok {
+            Ok(DeJson::de_json(s, i)?)
+        } else {
+            Err(DeJson::de_json(s, i)?)
+        };
+        s.curly_close(i)?;
+        Ok(r)
+    }
+}
+
+macro_rules! impl_ser_de_json_net {
+    ($($ty:ident),*) => {$(
+        #[cfg(feature = "std")]
+        impl SerJson for std::net::$ty {
+            fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+                push_json_str(&mut s.out, &self.to_string());
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl DeJson for std::net::$ty {
+            fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+                let val = s.as_string()?;
+                let addr = val.parse().map_err(|_| s.err_parse(stringify!($ty)))?;
+                s.next_tok(i)?;
+                Ok(addr)
+            }
+        }
+    )*};
+}
+
+// addresses are written in their canonical text form, like "127.0.0.1" or "[::1]:80",
+// which keeps the scope id of a SocketAddrV6 but not its flow info
+impl_ser_de_json_net!(
+    Ipv4Addr,
+    Ipv6Addr,
+    IpAddr,
+    SocketAddrV4,
+    SocketAddrV6,
+    SocketAddr
+);
+
+/// Paths are written as strings, with invalid UTF-8 replaced by U+FFFD.
+#[cfg(feature = "std")]
+impl SerJson for std::path::Path {
+    fn ser_json(&self, _d: usize, s: &mut SerJsonState) {
+        push_json_str(&mut s.out, &self.to_string_lossy());
+    }
+}
+
+#[cfg(feature = "std")]
+impl SerJson for std::path::PathBuf {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.as_path().ser_json(d, s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeJson for std::path::PathBuf {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        String::de_json(s, i).map(std::path::PathBuf::from)
+    }
+}
+
+/// The tag a type registered with [`json_tagged_trait!`] is written with.
+///
+/// A trait used for `#[nserde(tagged_trait)]` fields has to have it as a
+/// supertrait, so the tag of the value behind a `Box<dyn Trait>` is known.
+pub trait JsonTagged: SerJson {
+    fn json_tag(&self) -> &'static str;
+}
+
+/// Implemented for `dyn Trait` by [`json_tagged_trait!`], writes the value behind
+/// a `#[nserde(tagged_trait)]` `Box<dyn Trait>` field as an object with a tag field
+/// next to its own fields, and reads it back into the type registered for the tag.
+pub trait JsonTaggedTrait {
+    fn ser_json_tagged(&self, d: usize, s: &mut SerJsonState);
+    fn de_json_tagged(s: &mut DeJsonState, i: &mut Chars) -> Result<Box<Self>, DeJsonErr>;
+}
+
+/// Registers the types that a `#[nserde(tagged_trait)]` `Box<dyn Trait>` field can
+/// hold, each with the tag naming it in the `tag_key` field of the JSON object.
+///
+/// Every registered type has to derive `SerJson` and `DeJson` and serialize to an
+/// object, and the trait has to have [`JsonTagged`] as a supertrait.
+///
+/// ```rust
+/// use nanoserde::{DeJson, JsonTagged, SerJson};
+///
+/// trait Shape: JsonTagged {
+///     fn area(&self) -> f32;
+/// }
+///
+/// #[derive(DeJson, SerJson)]
+/// struct Square {
+///     side: f32,
+/// }
+///
+/// impl Shape for Square {
+///     fn area(&self) -> f32 {
+///         self.side * self.side
+///     }
+/// }
+///
+/// nanoserde::json_tagged_trait!(Shape, "type", { "square" => Square });
+///
+/// #[derive(DeJson, SerJson)]
+/// struct Drawing {
+///     #[nserde(tagged_trait)]
+///     shape: Box<dyn Shape>,
+/// }
+///
+/// let drawing = Drawing::deserialize_json(r#"{"shape":{"type":"square","side":2.0}}"#).unwrap();
+/// assert_eq!(drawing.shape.area(), 4.0);
+/// assert_eq!(drawing.serialize_json(), r#"{"shape":{"type":"square","side":2.0}}"#);
+/// ```
+#[macro_export]
+macro_rules! json_tagged_trait {
+    ($trait:path, $tag_key:literal, { $($tag:literal => $ty:ty),* $(,)? }) => {
+        $(
+            impl $crate::JsonTagged for $ty {
+                fn json_tag(&self) -> &'static str {
+                    $tag
+                }
+            }
+        )*
+
+        impl $crate::JsonTaggedTrait for dyn $trait {
+            fn ser_json_tagged(&self, d: usize, s: &mut $crate::SerJsonState) {
+                $crate::ser_json_tagged_object(self, $tag_key, $crate::JsonTagged::json_tag(self), d, s)
+            }
+
+            fn de_json_tagged(
+                s: &mut $crate::DeJsonState,
+                i: &mut ::core::str::Chars,
+            ) -> ::core::result::Result<$crate::__private::Box<Self>, $crate::DeJsonErr> {
+                let (tag, fields) = $crate::de_json_tagged_object(s, i, $tag_key)?;
+                match tag.as_str() {
+                    $($tag => ::core::result::Result::Ok($crate::__private::Box::new(
+                        s.parse_captured(&fields, <$ty as $crate::DeJson>::de_json)?,
+                    )),)*
+                    _ => ::core::result::Result::Err(s.err_enum(&tag)),
+                }
+            }
+        }
+    };
+}
+
+/// Serializes a `#[nserde(tagged_trait)]` field through [`JsonTaggedTrait`].
+#[doc(hidden)]
+pub struct TaggedTraitRef<'a, T: ?Sized>(pub &'a T);
+
+impl<T> SerJson for TaggedTraitRef<'_, T>
+where
+    T: JsonTaggedTrait + ?Sized,
+{
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        self.0.ser_json_tagged(d, s)
+    }
+}
+
+/// Writes `value` as an object with `tag_key` set to `tag` followed by its own fields.
+#[doc(hidden)]
+pub fn ser_json_tagged_object<T>(
+    value: &T,
+    tag_key: &str,
+    tag: &str,
+    d: usize,
+    s: &mut SerJsonState,
+) where
+    T: SerJson + ?Sized,
+{
+    s.st_pre();
+    s.field(d + 1, tag_key);
+    push_json_str(&mut s.out, tag);
+    s.flatten(d, value, true);
+    s.st_post(d);
+}
+
+/// Reads an object into the value of its `tag_key` field and the other fields,
+/// captured as a JSON object for [`DeJsonState::parse_captured`].
+#[doc(hidden)]
+pub fn de_json_tagged_object(
+    s: &mut DeJsonState,
+    i: &mut Chars,
+    tag_key: &str,
+) -> Result<(String, DeJsonCapture), DeJsonErr> {
+    let mut tag = None;
+    let mut fields = DeJsonCapture::default();
+    s.curly_open(i)?;
+    while s.next_key().is_some() {
+        if s.strbuf == tag_key {
+            s.next_colon(i)?;
+            tag = Some(s.as_string()?);
+            s.next_tok(i)?;
+        } else {
+            s.capture_field(i, &mut fields)?;
+        }
+        s.eat_comma_curly(i)?;
+    }
+    s.curly_close(i)?;
+    DeJsonState::finish_capture(&mut fields);
+    match tag {
+        Some(tag) => Ok((tag, fields)),
+        None => Err(s.err_nf(tag_key)),
+    }
+}
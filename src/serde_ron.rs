@@ -6,10 +6,14 @@ use core::error::Error;
 #[cfg(feature = "std")]
 use std::error::Error;
 
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
-use alloc::collections::{BTreeMap, BTreeSet, LinkedList};
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
 use alloc::format;
+use alloc::rc::Rc;
 use alloc::string::{String, ToString};
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
 /// The internal state of a RON serialization.
@@ -26,7 +30,19 @@ impl SerRonState {
 
     pub fn field(&mut self, d: usize, field: &str) {
         self.indent(d);
-        self.out.push_str(field);
+        let mut chars = field.chars();
+        let is_ident = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && field != "true"
+            && field != "false";
+        if is_ident {
+            self.out.push_str(field);
+        } else {
+            // e.g. renamed fields with a `-`, which are read back by `DeRonState::next_key`
+            push_ron_str(&mut self.out, field);
+        }
         self.out.push(':');
     }
 
@@ -35,12 +51,23 @@ impl SerRonState {
     }
 
     pub fn st_pre(&mut self) {
-        self.out.push_str("(\n");
+        self.block_pre('(');
     }
 
     pub fn st_post(&mut self, d: usize) {
+        self.block_post(d, ')');
+    }
+
+    /// Open a struct, list or map whose items are each followed by `conl`.
+    pub fn block_pre(&mut self, open: char) {
+        self.out.push(open);
+        self.out.push('\n');
+    }
+
+    /// Close what `block_pre` opened.
+    pub fn block_post(&mut self, d: usize, close: char) {
         self.indent(d);
-        self.out.push(')');
+        self.out.push(close);
     }
 }
 
@@ -57,6 +84,48 @@ pub trait SerRon {
         s.out
     }
 
+    /// Serialize Self to RON, appending to `out`.
+    ///
+    /// Unlike `serialize_ron` no new string is allocated, so clearing and
+    /// reusing one buffer saves allocations across repeated serializations.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let mut out = String::new();
+    /// 1u32.serialize_ron_into(&mut out);
+    /// out.clear();
+    /// 2u32.serialize_ron_into(&mut out);
+    /// assert_eq!(out, "2");
+    /// ```
+    fn serialize_ron_into(&self, out: &mut String) {
+        let mut s = SerRonState {
+            out: core::mem::take(out),
+        };
+        self.ser_ron(0, &mut s);
+        *out = s.out;
+    }
+
+    /// Serialize Self to a RON string in which the entries of every `HashMap` and
+    /// `HashSet` are sorted by their serialized key, so equal values always give
+    /// identical output, e.g. for hashing or signing.
+    ///
+    /// RON always writes hash collections sorted, so this is the same as
+    /// `serialize_ron`.
+    fn serialize_ron_canonical(&self) -> String {
+        self.serialize_ron()
+    }
+
+    /// Serialize Self to a RON string on a single line, without indentation
+    /// or trailing commas, e.g. for log lines or line based records.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// assert_eq!(vec![1u32, 2].serialize_ron_compact(), "[1,2]");
+    /// ```
+    fn serialize_ron_compact(&self) -> String {
+        compact_ron(&self.serialize_ron())
+    }
+
     /// Serialize Self to a RON string.
     ///
     /// ```rust
@@ -68,6 +137,41 @@ pub trait SerRon {
     fn ser_ron(&self, indent_level: usize, state: &mut SerRonState);
 }
 
+/// Strips the layout of pretty RON, the newlines, the indentation and the
+/// trailing commas before closing brackets. Strings and chars are copied as is.
+fn compact_ron(pretty: &str) -> String {
+    let mut out = String::with_capacity(pretty.len());
+    let mut chars = pretty.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                out.push(c);
+                while let Some(inner) = chars.next() {
+                    out.push(inner);
+                    if inner == '\\' {
+                        out.extend(chars.next());
+                    } else if inner == c {
+                        break;
+                    }
+                }
+            }
+            '\n' => {
+                while chars.as_str().starts_with(' ') {
+                    chars.next();
+                }
+            }
+            ')' | ']' | '}' => {
+                if out.ends_with(',') {
+                    out.pop();
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// A trait for objects that can be deserialized from the RON file format.
 ///
 /// [Specification](https://github.com/ron-rs/ron).
@@ -98,12 +202,14 @@ pub trait DeRon: Sized {
 }
 
 /// A RON parsed token.
-#[derive(PartialEq, Debug, Default)]
+#[derive(PartialEq, Debug, Default, Clone)]
 pub enum DeRonTok {
     Ident,
     Str,
     U64(u64),
     I64(i64),
+    U128(u128),
+    I128(i128),
     F64(f64),
     Bool(bool),
     Char(char),
@@ -121,7 +227,6 @@ pub enum DeRonTok {
 }
 
 /// The internal state of a RON deserialization.
-#[derive(Default)]
 #[non_exhaustive]
 pub struct DeRonState {
     pub cur: char,
@@ -131,6 +236,62 @@ pub struct DeRonState {
     pub identbuf: String,
     pub line: usize,
     pub col: usize,
+    /// The number of tuples, lists and maps currently open.
+    pub depth: usize,
+    /// The maximum nesting of tuples, lists and maps before failing, 128 by default.
+    pub max_depth: usize,
+}
+
+impl Default for DeRonState {
+    fn default() -> Self {
+        DeRonState {
+            cur: '\0',
+            tok: DeRonTok::default(),
+            strbuf: String::new(),
+            numbuf: String::new(),
+            identbuf: String::new(),
+            line: 0,
+            col: 0,
+            depth: 0,
+            max_depth: 128,
+        }
+    }
+}
+
+/// The reason a RON string failed to deserialize.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DeRonErrReason {
+    UnexpectedKey(String),
+    /// The token found and a description of what was expected instead.
+    UnexpectedToken(DeRonTok, String),
+    MissingKey(String),
+    NoSuchEnum(String),
+    OutOfRange(String),
+    WrongType(String),
+    CannotParse(String),
+    /// Tuples, lists and maps were nested deeper than `DeRonState::max_depth`.
+    TooDeep,
+    /// A message from a `DeRon` impl, see `DeRonState::err_custom`.
+    Custom(String),
+}
+
+impl core::fmt::Display for DeRonErrReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnexpectedKey(name) => write!(f, "Unexpected key {}", name),
+            Self::UnexpectedToken(token, what) => {
+                write!(f, "Unexpected token {:?} expected {} ", token, what)
+            }
+            Self::MissingKey(name) => write!(f, "Key not found {}", name),
+            Self::NoSuchEnum(name) => write!(f, "Enum not defined {}", name),
+            Self::OutOfRange(value) => write!(f, "Value out of range {} ", value),
+            Self::WrongType(what) => write!(f, "Token wrong type {} ", what),
+            Self::CannotParse(what) => write!(f, "Cannot parse {} ", what),
+            Self::TooDeep => write!(f, "Nesting too deep"),
+            Self::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
 }
 
 /// The error message when failing to deserialize a RON string.
@@ -138,6 +299,8 @@ pub struct DeRonState {
 #[non_exhaustive]
 pub struct DeRonErr {
     pub msg: String,
+    /// The same error as `msg`, in a form that can be matched on.
+    pub reason: DeRonErrReason,
     pub line: usize,
     pub col: usize,
 }
@@ -170,67 +333,60 @@ impl DeRonState {
                 self.line += 1;
                 self.col = 0;
             } else {
-                self.col = 0;
+                self.col += 1;
             }
         } else {
             self.cur = '\0';
         }
     }
 
-    pub fn err_exp(&self, name: &str) -> DeRonErr {
+    fn err(&self, reason: DeRonErrReason) -> DeRonErr {
         DeRonErr {
-            msg: format!("Unexpected key {}", name),
+            msg: reason.to_string(),
+            reason,
             line: self.line,
             col: self.col,
         }
     }
 
+    pub fn err_exp(&self, name: &str) -> DeRonErr {
+        self.err(DeRonErrReason::UnexpectedKey(name.to_string()))
+    }
+
     pub fn err_nf(&self, name: &str) -> DeRonErr {
-        DeRonErr {
-            msg: format!("Key not found {}", name),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeRonErrReason::MissingKey(name.to_string()))
     }
 
     pub fn err_enum(&self, name: &str) -> DeRonErr {
-        DeRonErr {
-            msg: format!("Enum not defined {}", name),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeRonErrReason::NoSuchEnum(name.to_string()))
     }
 
     pub fn err_token(&self, what: &str) -> DeRonErr {
-        DeRonErr {
-            msg: format!("Unexpected token {:?} expected {} ", self.tok, what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeRonErrReason::UnexpectedToken(
+            self.tok.clone(),
+            what.to_string(),
+        ))
     }
 
     pub fn err_range(&self, what: &str) -> DeRonErr {
-        DeRonErr {
-            msg: format!("Value out of range {} ", what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeRonErrReason::OutOfRange(what.to_string()))
     }
 
     pub fn err_type(&self, what: &str) -> DeRonErr {
-        DeRonErr {
-            msg: format!("Token wrong type {} ", what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeRonErrReason::WrongType(what.to_string()))
+    }
+
+    pub fn err_too_deep(&self) -> DeRonErr {
+        self.err(DeRonErrReason::TooDeep)
     }
 
     pub fn err_parse(&self, what: &str) -> DeRonErr {
-        DeRonErr {
-            msg: format!("Cannot parse {} ", what),
-            line: self.line,
-            col: self.col,
-        }
+        self.err(DeRonErrReason::CannotParse(what.to_string()))
+    }
+
+    /// An error with a domain-specific message, at the current position.
+    pub fn err_custom(&self, msg: &str) -> DeRonErr {
+        self.err(DeRonErrReason::Custom(msg.to_string()))
     }
 
     pub fn eat_comma_paren(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
@@ -266,6 +422,55 @@ impl DeRonState {
         }
     }
 
+    /// Skip over a whole value: a scalar, an identifier with its optional
+    /// `(...)` payload, or a tuple, list or map including everything nested in it.
+    pub fn skip_value(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
+        match self.tok {
+            DeRonTok::Ident => {
+                self.next_tok(i)?;
+                if self.tok == DeRonTok::ParenOpen {
+                    self.skip_value(i)?;
+                }
+                Ok(())
+            }
+            DeRonTok::Str
+            | DeRonTok::U64(_)
+            | DeRonTok::I64(_)
+            | DeRonTok::U128(_)
+            | DeRonTok::I128(_)
+            | DeRonTok::F64(_)
+            | DeRonTok::Bool(_)
+            | DeRonTok::Char(_) => {
+                self.next_tok(i)?;
+                Ok(())
+            }
+            DeRonTok::ParenOpen | DeRonTok::BlockOpen | DeRonTok::CurlyOpen => {
+                let mut open_brackets = 0;
+
+                loop {
+                    match self.tok {
+                        DeRonTok::ParenOpen | DeRonTok::BlockOpen | DeRonTok::CurlyOpen => {
+                            open_brackets += 1;
+                        }
+                        DeRonTok::ParenClose | DeRonTok::BlockClose | DeRonTok::CurlyClose => {
+                            open_brackets -= 1;
+                        }
+                        DeRonTok::Eof => return Err(self.err_token(") or ] or }")),
+                        _ => {}
+                    }
+
+                    self.next_tok(i)?;
+
+                    if open_brackets == 0 {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(self.err_token("value")),
+        }
+    }
+
     pub fn colon(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         match self.tok {
             DeRonTok::Colon => {
@@ -300,8 +505,30 @@ impl DeRonState {
         }
     }
 
+    /// Like `next_ident`, but also accepts a quoted key, which is moved into
+    /// `identbuf` so both kinds can be matched the same way.
+    pub fn next_key(&mut self) -> Option<()> {
+        match self.tok {
+            DeRonTok::Ident => Some(()),
+            DeRonTok::Str => {
+                core::mem::swap(&mut self.identbuf, &mut self.strbuf);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), DeRonErr> {
+        if self.depth >= self.max_depth {
+            return Err(self.err_too_deep());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     pub fn paren_open(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         if self.tok == DeRonTok::ParenOpen {
+            self.enter()?;
             self.next_tok(i)?;
             return Ok(());
         }
@@ -310,6 +537,7 @@ impl DeRonState {
 
     pub fn paren_close(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         if self.tok == DeRonTok::ParenClose {
+            self.depth = self.depth.saturating_sub(1);
             self.next_tok(i)?;
             return Ok(());
         }
@@ -318,6 +546,7 @@ impl DeRonState {
 
     pub fn block_open(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         if self.tok == DeRonTok::BlockOpen {
+            self.enter()?;
             self.next_tok(i)?;
             return Ok(());
         }
@@ -326,6 +555,7 @@ impl DeRonState {
 
     pub fn block_close(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         if self.tok == DeRonTok::BlockClose {
+            self.depth = self.depth.saturating_sub(1);
             self.next_tok(i)?;
             return Ok(());
         }
@@ -334,6 +564,7 @@ impl DeRonState {
 
     pub fn curly_open(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         if self.tok == DeRonTok::CurlyOpen {
+            self.enter()?;
             self.next_tok(i)?;
             return Ok(());
         }
@@ -342,6 +573,7 @@ impl DeRonState {
 
     pub fn curly_close(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         if self.tok == DeRonTok::CurlyClose {
+            self.depth = self.depth.saturating_sub(1);
             self.next_tok(i)?;
             return Ok(());
         }
@@ -355,6 +587,10 @@ impl DeRonState {
             }
             return Ok(value);
         }
+        // only a value above u64::MAX is read as a u128
+        if let DeRonTok::U128(value) = self.tok {
+            return Err(self.err_range(&format!("{}>{}", value, max)));
+        }
         Err(self.err_token("unsigned integer"))
     }
 
@@ -366,12 +602,41 @@ impl DeRonState {
             return Ok(value);
         }
         if let DeRonTok::U64(value) = self.tok {
-            if value as i64 > max {
+            // compared as u64, a value above i64::MAX would wrap negative
+            if value > max as u64 {
                 return Err(self.err_range(&format!("{}>{}", value, max)));
             }
             return Ok(value as i64);
         }
-        Err(self.err_token("signed integer"))
+        // only values outside of the 64 bit integers are read as 128 bit ones
+        match self.tok {
+            DeRonTok::I128(value) => Err(self.err_range(&format!("{}<{}", value, min))),
+            DeRonTok::U128(value) => Err(self.err_range(&format!("{}>{}", value, max))),
+            _ => Err(self.err_token("signed integer")),
+        }
+    }
+
+    pub fn as_u128(&mut self) -> Result<u128, DeRonErr> {
+        match self.tok {
+            DeRonTok::U64(value) => Ok(value as u128),
+            DeRonTok::U128(value) => Ok(value),
+            _ => Err(self.err_token("unsigned integer")),
+        }
+    }
+
+    pub fn as_i128(&mut self) -> Result<i128, DeRonErr> {
+        match self.tok {
+            DeRonTok::I64(value) => Ok(value as i128),
+            DeRonTok::U64(value) => Ok(value as i128),
+            DeRonTok::I128(value) => Ok(value),
+            DeRonTok::U128(value) => {
+                if value > i128::MAX as u128 {
+                    return Err(self.err_range(&format!("{}>{}", value, i128::MAX)));
+                }
+                Ok(value as i128)
+            }
+            _ => Err(self.err_token("signed integer")),
+        }
     }
 
     pub fn as_f64(&mut self) -> Result<f64, DeRonErr> {
@@ -381,6 +646,12 @@ impl DeRonState {
         if let DeRonTok::U64(value) = self.tok {
             return Ok(value as f64);
         }
+        if let DeRonTok::I128(value) = self.tok {
+            return Ok(value as f64);
+        }
+        if let DeRonTok::U128(value) = self.tok {
+            return Ok(value as f64);
+        }
         if let DeRonTok::F64(value) = self.tok {
             return Ok(value);
         }
@@ -406,6 +677,29 @@ impl DeRonState {
         Err(self.err_token("string"))
     }
 
+    /// Reads a run of digits into `numbuf`. A `_` is allowed between two digits
+    /// as a separator and left out, anywhere else it fails.
+    fn digits(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
+        let mut after_digit = false;
+        loop {
+            match self.cur {
+                '0'..='9' => {
+                    self.numbuf.push(self.cur);
+                    after_digit = true;
+                }
+                '_' => {
+                    self.next(i);
+                    if !after_digit || !self.cur.is_ascii_digit() {
+                        return Err(self.err_parse("number"));
+                    }
+                    continue;
+                }
+                _ => return Ok(()),
+            }
+            self.next(i);
+        }
+    }
+
     pub fn next_tok(&mut self, i: &mut Chars) -> Result<(), DeRonErr> {
         loop {
             while self.cur == '\n' || self.cur == '\r' || self.cur == '\t' || self.cur == ' ' {
@@ -468,9 +762,13 @@ impl DeRonState {
                             self.next(i);
                         }
                     } else if self.cur == '*' {
-                        // multline comment
+                        // multline comment, the opening star can't also close it
+                        self.next(i);
                         let mut last_star = false;
-                        while self.cur != '\0' {
+                        loop {
+                            if self.cur == '\0' {
+                                return Err(self.err_parse("comment"));
+                            }
                             if self.cur == '/' && last_star {
                                 self.next(i);
                                 break;
@@ -492,25 +790,19 @@ impl DeRonState {
                     } else {
                         false
                     };
-                    while self.cur >= '0' && self.cur <= '9' {
-                        self.numbuf.push(self.cur);
-                        self.next(i);
-                    }
+                    self.digits(i)?;
                     let mut is_float = false;
                     if self.cur == '.' {
                         is_float = true;
                         self.numbuf.push(self.cur);
                         self.next(i);
-                        while self.cur >= '0' && self.cur <= '9' {
-                            self.numbuf.push(self.cur);
-                            self.next(i);
-                        }
+                        self.digits(i)?;
                     }
                     if self.cur == 'e' || self.cur == 'E' {
                         is_float = true;
                         self.numbuf.push(self.cur);
                         self.next(i);
-                        if self.cur == '-' {
+                        if self.cur == '-' || self.cur == '+' {
                             self.numbuf.push(self.cur);
                             self.next(i);
                         }
@@ -531,16 +823,23 @@ impl DeRonState {
                             if let Ok(num) = self.numbuf.parse() {
                                 self.tok = DeRonTok::I64(num);
                                 return Ok(());
-                            } else {
-                                return Err(self.err_parse("number"));
                             }
+                            // too large for i64, keep the full magnitude for i128 fields
+                            if let Ok(num) = self.numbuf.parse() {
+                                self.tok = DeRonTok::I128(num);
+                                return Ok(());
+                            }
+                            return Err(self.err_parse("number"));
                         }
                         if let Ok(num) = self.numbuf.parse() {
                             self.tok = DeRonTok::U64(num);
                             return Ok(());
-                        } else {
-                            return Err(self.err_parse("number"));
                         }
+                        if let Ok(num) = self.numbuf.parse() {
+                            self.tok = DeRonTok::U128(num);
+                            return Ok(());
+                        }
+                        return Err(self.err_parse("number"));
                     }
                 }
                 'a'..='z' | 'A'..='Z' | '_' => {
@@ -566,16 +865,45 @@ impl DeRonState {
                 }
                 '\'' => {
                     self.next(i);
-                    if self.cur == '\\' {
-                        self.next(i);
-                    }
-                    let chr = self.cur;
-                    self.next(i);
+                    let chr = match self.cur {
+                        '\\' => {
+                            self.next(i);
+                            if self.cur == 'u' {
+                                match self.hex_unescape_char(i) {
+                                    Some(c) => c,
+                                    None => return Err(self.err_parse("char")),
+                                }
+                            } else {
+                                let chr = match self.cur {
+                                    'n' => '\n',
+                                    'r' => '\r',
+                                    't' => '\t',
+                                    'b' => '\x08',
+                                    'f' => '\x0c',
+                                    '0' => '\0',
+                                    '\0' => {
+                                        return Err(self.err_parse("char"));
+                                    }
+                                    c => c,
+                                };
+                                self.next(i);
+                                chr
+                            }
+                        }
+                        '\0' => {
+                            return Err(self.err_parse("char"));
+                        }
+                        c => {
+                            self.next(i);
+                            c
+                        }
+                    };
                     if self.cur != '\'' {
                         return Err(self.err_token("char"));
                     }
                     self.next(i);
                     self.tok = DeRonTok::Char(chr);
+                    return Ok(());
                 }
                 '"' => {
                     self.strbuf.truncate(0);
@@ -748,6 +1076,65 @@ impl_ser_de_ron_signed!(i8, i8::MIN, i8::MAX);
 impl_ser_de_ron_float!(f64);
 impl_ser_de_ron_float!(f32);
 
+impl SerRon for u128 {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        s.out.push_str(&self.to_string());
+    }
+}
+
+impl DeRon for u128 {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<u128, DeRonErr> {
+        let val = s.as_u128()?;
+        s.next_tok(i)?;
+        Ok(val)
+    }
+}
+
+impl SerRon for i128 {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        s.out.push_str(&self.to_string());
+    }
+}
+
+impl DeRon for i128 {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<i128, DeRonErr> {
+        let val = s.as_i128()?;
+        s.next_tok(i)?;
+        Ok(val)
+    }
+}
+
+macro_rules! impl_ser_de_ron_nonzero {
+    ($($ty:ident($inner:ident)),* $(,)?) => {$(
+        impl SerRon for core::num::$ty {
+            fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+                self.get().ser_ron(d, s)
+            }
+        }
+
+        impl DeRon for core::num::$ty {
+            fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<core::num::$ty, DeRonErr> {
+                let val: $inner = DeRon::de_ron(s, i)?;
+                core::num::$ty::new(val).ok_or_else(|| s.err_parse(concat!(stringify!($ty), ", it is zero")))
+            }
+        }
+    )*};
+}
+
+impl_ser_de_ron_nonzero!(
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroUsize(usize),
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128),
+);
+
 impl<T> SerRon for Option<T>
 where
     T: SerRon,
@@ -794,39 +1181,76 @@ impl DeRon for bool {
     }
 }
 
-impl SerRon for String {
+impl SerRon for char {
     fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
-        s.out.push('"');
-        for c in self.chars() {
-            match c {
-                '\n' => {
-                    s.out.push('\\');
-                    s.out.push('n');
-                }
-                '\r' => {
-                    s.out.push('\\');
-                    s.out.push('r');
-                }
-                '\t' => {
-                    s.out.push('\\');
-                    s.out.push('t');
-                }
-                '\0' => {
-                    s.out.push('\\');
-                    s.out.push('0');
-                }
-                '\\' => {
-                    s.out.push('\\');
-                    s.out.push('\\');
-                }
-                '"' => {
-                    s.out.push('\\');
-                    s.out.push('"');
-                }
-                _ => s.out.push(c),
+        s.out.push('\'');
+        match self {
+            '\n' => s.out.push_str("\\n"),
+            '\r' => s.out.push_str("\\r"),
+            '\t' => s.out.push_str("\\t"),
+            '\0' => s.out.push_str("\\0"),
+            '\\' => s.out.push_str("\\\\"),
+            '\'' => s.out.push_str("\\'"),
+            c => s.out.push(*c),
+        }
+        s.out.push('\'');
+    }
+}
+
+impl DeRon for char {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<char, DeRonErr> {
+        let val = match s.tok {
+            DeRonTok::Char(c) => c,
+            _ => return Err(s.err_token("char")),
+        };
+        s.next_tok(i)?;
+        Ok(val)
+    }
+}
+
+fn push_ron_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\n' => {
+                out.push('\\');
+                out.push('n');
+            }
+            '\r' => {
+                out.push('\\');
+                out.push('r');
+            }
+            '\t' => {
+                out.push('\\');
+                out.push('t');
+            }
+            '\0' => {
+                out.push('\\');
+                out.push('0');
+            }
+            '\\' => {
+                out.push('\\');
+                out.push('\\');
+            }
+            '"' => {
+                out.push('\\');
+                out.push('"');
             }
+            _ => out.push(c),
         }
-        s.out.push('"');
+    }
+    out.push('"');
+}
+
+impl SerRon for String {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        push_ron_str(&mut s.out, self);
+    }
+}
+
+impl SerRon for str {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        push_ron_str(&mut s.out, self);
     }
 }
 
@@ -843,14 +1267,7 @@ where
     T: SerRon,
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push_str("[\n");
-        for item in self {
-            s.indent(d + 1);
-            item.ser_ron(d + 1, s);
-            s.conl();
-        }
-        s.indent(d);
-        s.out.push(']');
+        ser_ron_items(self, d, s);
     }
 }
 
@@ -877,21 +1294,28 @@ where
     T: SerRon,
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push('[');
-        if !self.is_empty() {
-            let last = self.len() - 1;
-            for (index, item) in self.iter().enumerate() {
-                s.indent(d + 1);
-                item.ser_ron(d + 1, s);
-                if index != last {
-                    s.out.push(',');
-                }
-            }
-        }
-        s.out.push(']');
+        // sorted by the serialized item, so equal sets always give identical output
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_by_cached_key(|item| item.serialize_ron());
+        ser_ron_items(items, d, s);
     }
 }
 
+/// Writes a sequence like `Vec` does, one item per line, so all sequence types
+/// format identically.
+fn ser_ron_items<'a, T>(items: impl IntoIterator<Item = &'a T>, d: usize, s: &mut SerRonState)
+where
+    T: SerRon + 'a,
+{
+    s.block_pre('[');
+    for item in items {
+        s.indent(d + 1);
+        item.ser_ron(d + 1, s);
+        s.conl();
+    }
+    s.block_post(d, ']');
+}
+
 #[cfg(feature = "std")]
 impl<T> DeRon for std::collections::HashSet<T>
 where
@@ -915,18 +1339,7 @@ where
     T: SerRon,
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push('[');
-        if !self.is_empty() {
-            let last = self.len() - 1;
-            for (index, item) in self.iter().enumerate() {
-                s.indent(d + 1);
-                item.ser_ron(d + 1, s);
-                if index != last {
-                    s.out.push(',');
-                }
-            }
-        }
-        s.out.push(']');
+        ser_ron_items(self, d, s);
     }
 }
 
@@ -947,31 +1360,57 @@ where
     }
 }
 
-impl<T> SerRon for BTreeSet<T>
+impl<T> SerRon for VecDeque<T>
 where
     T: SerRon,
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push('[');
-        if !self.is_empty() {
-            let last = self.len() - 1;
-            for (index, item) in self.iter().enumerate() {
-                s.indent(d + 1);
-                item.ser_ron(d + 1, s);
-                if index != last {
-                    s.out.push(',');
-                }
-            }
-        }
-        s.out.push(']');
+        ser_ron_items(self.iter(), d, s);
     }
 }
 
-impl<T> DeRon for BTreeSet<T>
+impl<T> DeRon for VecDeque<T>
 where
-    T: DeRon + Ord,
+    T: DeRon,
 {
-    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<BTreeSet<T>, DeRonErr> {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<VecDeque<T>, DeRonErr> {
+        Vec::de_ron(s, i).map(VecDeque::from)
+    }
+}
+
+/// The items are written in the heap's internal order, not sorted.
+impl<T> SerRon for BinaryHeap<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_items(self.iter(), d, s);
+    }
+}
+
+impl<T> DeRon for BinaryHeap<T>
+where
+    T: DeRon + Ord,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<BinaryHeap<T>, DeRonErr> {
+        Vec::de_ron(s, i).map(BinaryHeap::from)
+    }
+}
+
+impl<T> SerRon for BTreeSet<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_items(self, d, s);
+    }
+}
+
+impl<T> DeRon for BTreeSet<T>
+where
+    T: DeRon + Ord,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<BTreeSet<T>, DeRonErr> {
         let mut out = BTreeSet::new();
         s.block_open(i)?;
 
@@ -990,12 +1429,11 @@ where
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
         s.out.push('(');
-        let last = self.len() - 1;
         for (index, item) in self.iter().enumerate() {
-            item.ser_ron(d + 1, s);
-            if index != last {
+            if index != 0 {
                 s.out.push_str(", ");
             }
+            item.ser_ron(d + 1, s);
         }
         s.out.push(')');
     }
@@ -1077,107 +1515,66 @@ impl DeRon for () {
     }
 }
 
-impl<A, B> SerRon for (A, B)
-where
-    A: SerRon,
-    B: SerRon,
-{
+/// A zero sized marker, written as `()` like the unit type.
+impl<T: ?Sized> SerRon for core::marker::PhantomData<T> {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push('(');
-        self.0.ser_ron(d, s);
-        s.out.push_str(", ");
-        self.1.ser_ron(d, s);
-        s.out.push(')');
-    }
-}
-
-impl<A, B> DeRon for (A, B)
-where
-    A: DeRon,
-    B: DeRon,
-{
-    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<(A, B), DeRonErr> {
-        s.paren_open(i)?;
-        let r = (de_ron_comma_paren(s, i)?, de_ron_comma_paren(s, i)?);
-        s.paren_close(i)?;
-        Ok(r)
+        ().ser_ron(d, s)
     }
 }
 
-impl<A, B, C> SerRon for (A, B, C)
-where
-    A: SerRon,
-    B: SerRon,
-    C: SerRon,
-{
-    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push('(');
-        self.0.ser_ron(d, s);
-        s.out.push_str(", ");
-        self.1.ser_ron(d, s);
-        s.out.push_str(", ");
-        self.2.ser_ron(d, s);
-        s.out.push(')');
+impl<T: ?Sized> DeRon for core::marker::PhantomData<T> {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        <()>::de_ron(s, i).map(|_| core::marker::PhantomData)
     }
 }
 
-impl<A, B, C> DeRon for (A, B, C)
-where
-    A: DeRon,
-    B: DeRon,
-    C: DeRon,
-{
-    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<(A, B, C), DeRonErr> {
-        s.paren_open(i)?;
-        let r = (
-            de_ron_comma_paren(s, i)?,
-            de_ron_comma_paren(s, i)?,
-            de_ron_comma_paren(s, i)?,
-        );
-        s.paren_close(i)?;
-        Ok(r)
-    }
-}
+macro_rules! impl_ron_tuple {
+    ($first_name: ident . $first: tt $(, $name: ident . $index: tt)*) => {
+        impl<$first_name, $($name),*> SerRon for ($first_name, $($name),*)
+        where
+            $first_name: SerRon,
+            $($name: SerRon),*
+        {
+            fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+                s.out.push('(');
+                self.$first.ser_ron(d, s);
+                $(
+                    s.out.push_str(", ");
+                    self.$index.ser_ron(d, s);
+                )*
+                s.out.push(')');
+            }
+        }
 
-impl<A, B, C, D> SerRon for (A, B, C, D)
-where
-    A: SerRon,
-    B: SerRon,
-    C: SerRon,
-    D: SerRon,
-{
-    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push('(');
-        self.0.ser_ron(d, s);
-        s.out.push_str(", ");
-        self.1.ser_ron(d, s);
-        s.out.push_str(", ");
-        self.2.ser_ron(d, s);
-        s.out.push_str(", ");
-        self.3.ser_ron(d, s);
-        s.out.push(')');
-    }
+        impl<$first_name, $($name),*> DeRon for ($first_name, $($name),*)
+        where
+            $first_name: DeRon,
+            $($name: DeRon),*
+        {
+            fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+                s.paren_open(i)?;
+                let r = (
+                    de_ron_comma_paren::<$first_name>(s, i)?,
+                    $(de_ron_comma_paren::<$name>(s, i)?,)*
+                );
+                s.paren_close(i)?;
+                Ok(r)
+            }
+        }
+    };
 }
 
-impl<A, B, C, D> DeRon for (A, B, C, D)
-where
-    A: DeRon,
-    B: DeRon,
-    C: DeRon,
-    D: DeRon,
-{
-    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<(A, B, C, D), DeRonErr> {
-        s.paren_open(i)?;
-        let r = (
-            de_ron_comma_paren(s, i)?,
-            de_ron_comma_paren(s, i)?,
-            de_ron_comma_paren(s, i)?,
-            de_ron_comma_paren(s, i)?,
-        );
-        s.paren_close(i)?;
-        Ok(r)
-    }
-}
+impl_ron_tuple!(A.0, B.1);
+impl_ron_tuple!(A.0, B.1, C.2);
+impl_ron_tuple!(A.0, B.1, C.2, D.3);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+impl_ron_tuple!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
 
 #[cfg(feature = "std")]
 impl<K, V> SerRon for std::collections::HashMap<K, V>
@@ -1186,19 +1583,33 @@ where
     V: SerRon,
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push_str("{\n");
-        for (k, v) in self {
-            s.indent(d + 1);
-            k.ser_ron(d + 1, s);
-            s.out.push(':');
-            v.ser_ron(d + 1, s);
-            s.conl();
-        }
-        s.indent(d);
-        s.out.push('}');
+        // sorted by the serialized key, so equal maps always give identical output
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by_cached_key(|(k, _)| k.serialize_ron());
+        ser_ron_entries(entries.into_iter(), d, s);
     }
 }
 
+#[cfg(feature = "std")]
+fn ser_ron_entries<'a, K, V>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    d: usize,
+    s: &mut SerRonState,
+) where
+    K: SerRon + 'a,
+    V: SerRon + 'a,
+{
+    s.block_pre('{');
+    for (k, v) in entries {
+        s.indent(d + 1);
+        k.ser_ron(d + 1, s);
+        s.out.push(':');
+        v.ser_ron(d + 1, s);
+        s.conl();
+    }
+    s.block_post(d, '}');
+}
+
 #[cfg(feature = "std")]
 impl<K, V> DeRon for std::collections::HashMap<K, V>
 where
@@ -1226,7 +1637,7 @@ where
     V: SerRon,
 {
     fn ser_ron(&self, d: usize, s: &mut SerRonState) {
-        s.out.push_str("{\n");
+        s.block_pre('{');
         for (k, v) in self {
             s.indent(d + 1);
             k.ser_ron(d + 1, s);
@@ -1234,8 +1645,7 @@ where
             v.ser_ron(d + 1, s);
             s.conl();
         }
-        s.indent(d);
-        s.out.push('}');
+        s.block_post(d, '}');
     }
 }
 
@@ -1259,6 +1669,15 @@ where
     }
 }
 
+impl<T> SerRon for &T
+where
+    T: SerRon + ?Sized,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        (**self).ser_ron(d, s)
+    }
+}
+
 impl<T> SerRon for Box<T>
 where
     T: SerRon,
@@ -1276,3 +1695,519 @@ where
         Ok(Box::new(DeRon::de_ron(s, i)?))
     }
 }
+
+impl<T> SerRon for core::num::Wrapping<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        self.0.ser_ron(d, s)
+    }
+}
+
+impl<T> DeRon for core::num::Wrapping<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<core::num::Wrapping<T>, DeRonErr> {
+        Ok(core::num::Wrapping(DeRon::de_ron(s, i)?))
+    }
+}
+
+impl<T> SerRon for Rc<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        (**self).ser_ron(d, s)
+    }
+}
+
+impl<T> DeRon for Rc<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Rc<T>, DeRonErr> {
+        Ok(Rc::new(DeRon::de_ron(s, i)?))
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> SerRon for Arc<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        (**self).ser_ron(d, s)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> DeRon for Arc<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Arc<T>, DeRonErr> {
+        Ok(Arc::new(DeRon::de_ron(s, i)?))
+    }
+}
+
+impl SerRon for Cow<'_, str> {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        (**self).ser_ron(d, s)
+    }
+}
+
+/// Written as a list like the owned `Vec<T>`, unlike a bare `[T]` which is a tuple.
+impl<T> SerRon for Cow<'_, [T]>
+where
+    T: SerRon + Clone,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_items(self.iter(), d, s);
+    }
+}
+
+impl<'a, B> DeRon for Cow<'a, B>
+where
+    B: ?Sized + ToOwned,
+    B::Owned: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Cow<'a, B>, DeRonErr> {
+        Ok(Cow::Owned(DeRon::de_ron(s, i)?))
+    }
+}
+
+impl SerRon for core::num::FpCategory {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        use core::num::FpCategory::*;
+        s.out.push_str(match self {
+            Nan => "Nan",
+            Infinite => "Infinite",
+            Zero => "Zero",
+            Subnormal => "Subnormal",
+            Normal => "Normal",
+        });
+    }
+}
+
+impl DeRon for core::num::FpCategory {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        use core::num::FpCategory::*;
+        s.ident(i)?;
+        Ok(match s.identbuf.as_ref() {
+            "Nan" => Nan,
+            "Infinite" => Infinite,
+            "Zero" => Zero,
+            "Subnormal" => Subnormal,
+            "Normal" => Normal,
+            _ => return Err(s.err_enum(&s.identbuf)),
+        })
+    }
+}
+
+impl SerRon for core::cmp::Ordering {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        use core::cmp::Ordering::*;
+        s.out.push_str(match self {
+            Less => "Less",
+            Equal => "Equal",
+            Greater => "Greater",
+        });
+    }
+}
+
+impl DeRon for core::cmp::Ordering {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        use core::cmp::Ordering::*;
+        s.ident(i)?;
+        Ok(match s.identbuf.as_ref() {
+            "Less" => Less,
+            "Equal" => Equal,
+            "Greater" => Greater,
+            _ => return Err(s.err_enum(&s.identbuf)),
+        })
+    }
+}
+
+impl<T> SerRon for core::ops::Bound<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        use core::ops::Bound::*;
+        let (label, v) = match self {
+            Included(v) => ("Included", v),
+            Excluded(v) => ("Excluded", v),
+            Unbounded => {
+                s.out.push_str("Unbounded");
+                return;
+            }
+        };
+        s.out.push_str(label);
+        s.out.push('(');
+        v.ser_ron(d, s);
+        s.out.push(')');
+    }
+}
+
+impl<T> DeRon for core::ops::Bound<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        use core::ops::Bound::*;
+        s.ident(i)?;
+        Ok(match s.identbuf.as_ref() {
+            "Included" => Included(de_ron_variant_payload(s, i)?),
+            "Excluded" => Excluded(de_ron_variant_payload(s, i)?),
+            "Unbounded" => Unbounded,
+            _ => return Err(s.err_enum(&s.identbuf)),
+        })
+    }
+}
+
+/// `Result` is written like a derived enum, `Ok(5)` or `Err("reason")`.
+impl<T, E> SerRon for Result<T, E>
+where
+    T: SerRon,
+    E: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        match self {
+            Ok(v) => {
+                s.out.push_str("Ok(");
+                v.ser_ron(d, s);
+            }
+            Err(e) => {
+                s.out.push_str("Err(");
+                e.ser_ron(d, s);
+            }
+        }
+        s.out.push(')');
+    }
+}
+
+impl<T, E> DeRon for Result<T, E>
+where
+    T: DeRon,
+    E: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        s.ident(i)?;
+        Ok(match s.identbuf.as_ref() {
+            "Ok" => Ok(de_ron_variant_payload(s, i)?),
+            "Err" => Err(de_ron_variant_payload(s, i)?),
+            _ => return Err(s.err_enum(&s.identbuf)),
+        })
+    }
+}
+
+/// Reads the `(value)` payload of a single field tuple variant.
+fn de_ron_variant_payload<T>(s: &mut DeRonState, i: &mut Chars) -> Result<T, DeRonErr>
+where
+    T: DeRon,
+{
+    s.paren_open(i)?;
+    let r = de_ron_comma_paren(s, i)?;
+    s.paren_close(i)?;
+    Ok(r)
+}
+
+impl<T> SerRon for core::ops::Range<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_range(Some(&self.start), Some(&self.end), d, s)
+    }
+}
+
+impl<T> DeRon for core::ops::Range<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        let (start, end) = de_ron_range(s, i, &["start", "end"])?;
+        let start = start.ok_or_else(|| s.err_nf("start"))?;
+        let end = end.ok_or_else(|| s.err_nf("end"))?;
+        Ok(start..end)
+    }
+}
+
+impl<T> SerRon for core::ops::RangeInclusive<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_range(Some(self.start()), Some(self.end()), d, s)
+    }
+}
+
+impl<T> DeRon for core::ops::RangeInclusive<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        let (start, end) = de_ron_range(s, i, &["start", "end"])?;
+        let start = start.ok_or_else(|| s.err_nf("start"))?;
+        let end = end.ok_or_else(|| s.err_nf("end"))?;
+        Ok(core::ops::RangeInclusive::new(start, end))
+    }
+}
+
+impl<T> SerRon for core::ops::RangeFrom<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_range(Some(&self.start), None, d, s)
+    }
+}
+
+impl<T> DeRon for core::ops::RangeFrom<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        let (start, _) = de_ron_range::<T>(s, i, &["start"])?;
+        let start = start.ok_or_else(|| s.err_nf("start"))?;
+        Ok(start..)
+    }
+}
+
+impl<T> SerRon for core::ops::RangeTo<T>
+where
+    T: SerRon,
+{
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        ser_ron_range(None, Some(&self.end), d, s)
+    }
+}
+
+impl<T> DeRon for core::ops::RangeTo<T>
+where
+    T: DeRon,
+{
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        let (_, end) = de_ron_range::<T>(s, i, &["end"])?;
+        let end = end.ok_or_else(|| s.err_nf("end"))?;
+        Ok(..end)
+    }
+}
+
+/// `..` is written as `()`.
+impl SerRon for core::ops::RangeFull {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        s.out.push_str("()");
+    }
+}
+
+impl DeRon for core::ops::RangeFull {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        de_ron_range::<()>(s, i, &[])?;
+        Ok(..)
+    }
+}
+
+/// Writes a range as `(start: .., end: ..)`, leaving out the unbounded sides.
+fn ser_ron_range<T>(start: Option<&T>, end: Option<&T>, d: usize, s: &mut SerRonState)
+where
+    T: SerRon,
+{
+    s.st_pre();
+    if let Some(start) = start {
+        s.field(d + 1, "start");
+        start.ser_ron(d + 1, s);
+        s.conl();
+    }
+    if let Some(end) = end {
+        s.field(d + 1, "end");
+        end.ser_ron(d + 1, s);
+        s.conl();
+    }
+    s.st_post(d);
+}
+
+/// Reads the `start` and `end` of a range, failing on any field not in `keys`.
+fn de_ron_range<T>(
+    s: &mut DeRonState,
+    i: &mut Chars,
+    keys: &[&str],
+) -> Result<(Option<T>, Option<T>), DeRonErr>
+where
+    T: DeRon,
+{
+    let mut start = None;
+    let mut end = None;
+    s.paren_open(i)?;
+    while s.next_ident().is_some() {
+        match s.identbuf.as_ref() {
+            "start" if keys.contains(&"start") => {
+                s.next_colon(i)?;
+                start = Some(T::de_ron(s, i)?);
+            }
+            "end" if keys.contains(&"end") => {
+                s.next_colon(i)?;
+                end = Some(T::de_ron(s, i)?);
+            }
+            _ => return Err(s.err_exp(&s.identbuf)),
+        }
+        s.eat_comma_paren(i)?;
+    }
+    s.paren_close(i)?;
+    Ok((start, end))
+}
+
+impl SerRon for core::time::Duration {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        s.st_pre();
+        s.field(d + 1, "secs");
+        self.as_secs().ser_ron(d + 1, s);
+        s.conl();
+        s.field(d + 1, "nanos");
+        self.subsec_nanos().ser_ron(d + 1, s);
+        s.conl();
+        s.st_post(d);
+    }
+}
+
+/// Accepts both the named `(secs: 1, nanos: 0)` and the positional `(1, 0)` form.
+impl DeRon for core::time::Duration {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        fn nanos(s: &mut DeRonState, i: &mut Chars) -> Result<u32, DeRonErr> {
+            let value = s.u64_range(u64::MAX)?;
+            if value > 999_999_999 {
+                return Err(
+                    s.err_custom(&format!("Duration nanos {} is not below 1000000000", value))
+                );
+            }
+            s.next_tok(i)?;
+            Ok(value as u32)
+        }
+
+        s.paren_open(i)?;
+        if s.tok != DeRonTok::Ident {
+            let secs = de_ron_comma_paren(s, i)?;
+            let nanos = nanos(s, i)?;
+            s.eat_comma_paren(i)?;
+            s.paren_close(i)?;
+            return Ok(core::time::Duration::new(secs, nanos));
+        }
+
+        let mut secs_field = None;
+        let mut nanos_field = None;
+        while s.next_ident().is_some() {
+            match s.identbuf.as_ref() {
+                "secs" => {
+                    s.next_colon(i)?;
+                    secs_field = Some(u64::de_ron(s, i)?);
+                }
+                "nanos" => {
+                    s.next_colon(i)?;
+                    nanos_field = Some(nanos(s, i)?);
+                }
+                _ => return Err(s.err_exp(&s.identbuf)),
+            }
+            s.eat_comma_paren(i)?;
+        }
+        s.paren_close(i)?;
+        match (secs_field, nanos_field) {
+            (Some(secs), Some(nanos)) => Ok(core::time::Duration::new(secs, nanos)),
+            (None, _) => Err(s.err_nf("secs")),
+            (_, None) => Err(s.err_nf("nanos")),
+        }
+    }
+}
+
+impl SerRon for crate::RelativeInstant {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        self.0.ser_ron(d, s)
+    }
+}
+
+impl DeRon for crate::RelativeInstant {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        core::time::Duration::de_ron(s, i).map(crate::RelativeInstant)
+    }
+}
+
+/// `SystemTime` is stored as the `Option<Duration>` since `UNIX_EPOCH`.
+///
+/// Times before the epoch can't be represented and are serialized as `None`,
+/// which is rejected on deserialization.
+#[cfg(feature = "std")]
+impl SerRon for std::time::SystemTime {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        self.duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .ser_ron(d, s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeRon for std::time::SystemTime {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        let since_epoch: Option<core::time::Duration> = DeRon::de_ron(s, i)?;
+        let Some(since_epoch) = since_epoch else {
+            return Err(s.err_range("SystemTime before UNIX_EPOCH"));
+        };
+        std::time::UNIX_EPOCH
+            .checked_add(since_epoch)
+            .ok_or_else(|| s.err_range("SystemTime overflow"))
+    }
+}
+
+macro_rules! impl_ser_de_ron_net {
+    ($($ty:ident),*) => {$(
+        #[cfg(feature = "std")]
+        impl SerRon for std::net::$ty {
+            fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+                push_ron_str(&mut s.out, &self.to_string());
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl DeRon for std::net::$ty {
+            fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+                let val = s.as_string()?;
+                let addr = val.parse().map_err(|_| s.err_parse(stringify!($ty)))?;
+                s.next_tok(i)?;
+                Ok(addr)
+            }
+        }
+    )*};
+}
+
+// addresses are written in their canonical text form, like "127.0.0.1" or "[::1]:80",
+// which keeps the scope id of a SocketAddrV6 but not its flow info
+impl_ser_de_ron_net!(
+    Ipv4Addr,
+    Ipv6Addr,
+    IpAddr,
+    SocketAddrV4,
+    SocketAddrV6,
+    SocketAddr
+);
+
+/// Paths are written as strings, with invalid UTF-8 replaced by U+FFFD.
+#[cfg(feature = "std")]
+impl SerRon for std::path::Path {
+    fn ser_ron(&self, _d: usize, s: &mut SerRonState) {
+        push_ron_str(&mut s.out, &self.to_string_lossy());
+    }
+}
+
+#[cfg(feature = "std")]
+impl SerRon for std::path::PathBuf {
+    fn ser_ron(&self, d: usize, s: &mut SerRonState) {
+        self.as_path().ser_ron(d, s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl DeRon for std::path::PathBuf {
+    fn de_ron(s: &mut DeRonState, i: &mut Chars) -> Result<Self, DeRonErr> {
+        String::de_ron(s, i).map(std::path::PathBuf::from)
+    }
+}
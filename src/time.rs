@@ -0,0 +1,45 @@
+use core::time::Duration;
+
+/// Time elapsed since some reference point, like an `Instant` taken at startup.
+///
+/// `Instant` can't be serialized as it has no fixed epoch, so store how long after
+/// a known `Instant` something happened instead. This is not a wall-clock time and
+/// only means something together with its reference point, use `SystemTime` for
+/// points in time that have to survive a restart.
+///
+/// It is serialized like the `Duration` it wraps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelativeInstant(pub Duration);
+
+impl From<Duration> for RelativeInstant {
+    fn from(value: Duration) -> Self {
+        RelativeInstant(value)
+    }
+}
+
+impl From<RelativeInstant> for Duration {
+    fn from(value: RelativeInstant) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl RelativeInstant {
+    /// The time from `reference` to `instant`, zero if `instant` is earlier.
+    ///
+    /// ```rust
+    /// # use nanoserde::RelativeInstant;
+    /// # use std::time::{Duration, Instant};
+    /// let start = Instant::now();
+    /// let elapsed = RelativeInstant::since(start, start + Duration::from_secs(2));
+    /// assert_eq!(elapsed.to_instant(start), Some(start + Duration::from_secs(2)));
+    /// ```
+    pub fn since(reference: std::time::Instant, instant: std::time::Instant) -> Self {
+        RelativeInstant(instant.saturating_duration_since(reference))
+    }
+
+    /// The `Instant` this is relative to `reference`, `None` on overflow.
+    pub fn to_instant(self, reference: std::time::Instant) -> Option<std::time::Instant> {
+        reference.checked_add(self.0)
+    }
+}
@@ -6,6 +6,7 @@ use core::error::Error;
 #[cfg(feature = "std")]
 use std::error::Error;
 
+use alloc::boxed::Box;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::{collections::BTreeMap, vec, vec::Vec};
@@ -42,10 +43,532 @@ macro_rules! ident_chars {
 /// Pattern matching a character that can terminate a valid ident.
 macro_rules! ident_term_chars {
     () => {
-        ' ' | '\t' | '\n' | '\0' | '=' | ']'
+        ' ' | '\t' | '\n' | '\0' | '=' | ']' | '}' | ',' | '.'
     };
 }
 
+/// The internal state of a TOML serialization.
+#[non_exhaustive]
+pub struct SerTomlState {
+    pub out: String,
+    /// Path of the `[section]` currently being written.
+    pub section: Vec<String>,
+}
+
+impl SerTomlState {
+    pub fn new(out: String) -> Self {
+        Self {
+            out,
+            section: Vec::new(),
+        }
+    }
+
+    /// Write `key = `, the value is expected to follow.
+    pub fn key(&mut self, key: &str) {
+        self.push_key(key);
+        self.out.push_str(" = ");
+    }
+
+    /// Write the `[section]` header of a nested table.
+    pub fn begin_section(&mut self, key: &str) {
+        self.section.push(key.to_string());
+        self.header("[", "]\n");
+    }
+
+    /// Start an array of tables, each element is preceded by `table_array_element`.
+    pub fn begin_table_array(&mut self, key: &str) {
+        self.section.push(key.to_string());
+    }
+
+    /// Write the `[[section]]` header of the next element of an array of tables.
+    pub fn table_array_element(&mut self) {
+        self.header("[[", "]]\n");
+    }
+
+    fn header(&mut self, open: &str, close: &str) {
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out.push_str(open);
+        for (index, key) in self.section.clone().iter().enumerate() {
+            if index != 0 {
+                self.out.push('.');
+            }
+            self.push_key(key);
+        }
+        self.out.push_str(close);
+    }
+
+    pub fn end_section(&mut self) {
+        self.section.pop();
+    }
+
+    pub fn str(&mut self, value: &str) {
+        self.out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                c if c.is_control() => self.out.push_str(&format!("\\u{:04X}", c as u32)),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn push_key(&mut self, key: &str) {
+        let bare = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if bare {
+            self.out.push_str(key);
+        } else {
+            self.str(key);
+        }
+    }
+}
+
+/// A trait for objects that can be serialized to TOML.
+pub trait SerToml {
+    /// Serialize Self to a TOML string.
+    ///
+    /// This is a convenient wrapper around `ser_toml`.
+    fn serialize_toml(&self) -> String {
+        let mut s = SerTomlState::new(String::new());
+        self.ser_toml(&mut s);
+        s.out
+    }
+
+    /// Serialize Self to a TOML string.
+    ///
+    /// Tables write their `key = value` lines followed by their nested
+    /// `[section]`s, every other type writes a single inline value.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let mut s = SerTomlState::new(String::new());
+    /// 42i32.ser_toml(&mut s);
+    /// assert_eq!(s.out, "42");
+    /// ```
+    fn ser_toml(&self, s: &mut SerTomlState);
+
+    /// Whether Self is written as a `[section]` rather than an inline value.
+    fn is_toml_table(&self) -> bool {
+        false
+    }
+
+    /// Whether Self is written as `[[section]]`s, like a `Vec` of tables.
+    fn is_toml_table_array(&self) -> bool {
+        false
+    }
+
+    /// Whether Self has no TOML representation and its key should be left
+    /// out, like `None`.
+    fn is_toml_none(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_ser_toml_display {
+    ($($ty:ident)*) => {
+        $(
+            impl SerToml for $ty {
+                fn ser_toml(&self, s: &mut SerTomlState) {
+                    s.out.push_str(&self.to_string());
+                }
+            }
+        )*
+    };
+}
+
+impl_ser_toml_display!(u8 u16 u32 u64 usize i8 i16 i32 i64 isize bool);
+
+macro_rules! impl_ser_toml_float {
+    ($($ty:ident)*) => {
+        $(
+            impl SerToml for $ty {
+                fn ser_toml(&self, s: &mut SerTomlState) {
+                    if self.is_nan() {
+                        s.out.push_str("nan");
+                    } else if self.is_infinite() {
+                        s.out.push_str(if *self < 0. { "-inf" } else { "inf" });
+                    } else {
+                        s.out.push_str(&format!("{:?}", self));
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_ser_toml_float!(f32 f64);
+
+impl SerToml for str {
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        s.str(self);
+    }
+}
+
+impl SerToml for String {
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        s.str(self);
+    }
+}
+
+impl<T> SerToml for Option<T>
+where
+    T: SerToml,
+{
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        if let Some(v) = self {
+            v.ser_toml(s);
+        }
+    }
+
+    fn is_toml_table(&self) -> bool {
+        self.as_ref().is_some_and(|v| v.is_toml_table())
+    }
+
+    fn is_toml_table_array(&self) -> bool {
+        self.as_ref().is_some_and(|v| v.is_toml_table_array())
+    }
+
+    fn is_toml_none(&self) -> bool {
+        match self {
+            Some(v) => v.is_toml_none(),
+            None => true,
+        }
+    }
+}
+
+impl<T> SerToml for [T]
+where
+    T: SerToml,
+{
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        // elements without a TOML representation, like `None`, are left out
+        let items = self.iter().filter(|item| !item.is_toml_none());
+        if self.is_toml_table_array() {
+            for item in items {
+                s.table_array_element();
+                item.ser_toml(s);
+            }
+            return;
+        }
+        s.out.push('[');
+        for (index, item) in items.enumerate() {
+            if index != 0 {
+                s.out.push_str(", ");
+            }
+            item.ser_toml(s);
+        }
+        s.out.push(']');
+    }
+
+    fn is_toml_table_array(&self) -> bool {
+        self.iter().any(|item| item.is_toml_table())
+    }
+}
+
+impl<T, const N: usize> SerToml for [T; N]
+where
+    T: SerToml,
+{
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        self.as_slice().ser_toml(s)
+    }
+
+    fn is_toml_table_array(&self) -> bool {
+        self.as_slice().is_toml_table_array()
+    }
+}
+
+impl<T> SerToml for Vec<T>
+where
+    T: SerToml,
+{
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        self.as_slice().ser_toml(s)
+    }
+
+    fn is_toml_table_array(&self) -> bool {
+        self.as_slice().is_toml_table_array()
+    }
+}
+
+impl<T> SerToml for Box<T>
+where
+    T: SerToml + ?Sized,
+{
+    fn ser_toml(&self, s: &mut SerTomlState) {
+        (**self).ser_toml(s)
+    }
+
+    fn is_toml_table(&self) -> bool {
+        (**self).is_toml_table()
+    }
+
+    fn is_toml_table_array(&self) -> bool {
+        (**self).is_toml_table_array()
+    }
+
+    fn is_toml_none(&self) -> bool {
+        (**self).is_toml_none()
+    }
+}
+
+/// A parsed TOML document, as returned by [`TomlParser::parse`].
+///
+/// Keys of nested tables are joined with `.`, like `"Section.value"`.
+pub type TomlTable = BTreeMap<String, Toml>;
+
+/// A trait for objects that can be deserialized from TOML.
+pub trait DeToml: Sized {
+    /// Parse Self from the input string.
+    ///
+    /// This is a convenient wrapper around `de_toml`. Errors about a value
+    /// point at the value, errors about missing keys at the end of the input.
+    fn deserialize_toml(input: &str) -> Result<Self, TomlErr> {
+        let (toml, positions) = TomlParser::parse_with_positions(input)?;
+        DeToml::de_toml(&toml, "").map_err(|mut err| {
+            match err.key.as_ref().map(|key| positions.get(key)) {
+                Some(Some(&(line, col))) => {
+                    err.line = line;
+                    err.col = col;
+                }
+                Some(None) => {
+                    err.line = input.matches('\n').count();
+                    err.col = input[input.rfind('\n').map_or(0, |newline| newline + 1)..]
+                        .chars()
+                        .count();
+                }
+                None => {}
+            }
+            err
+        })
+    }
+
+    /// Parse Self from the entry `key` of a parsed TOML document.
+    ///
+    /// Tables read their fields from the keys prefixed with `key.`, every
+    /// other type converts the single value stored under `key`.
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let toml = TomlParser::parse("[Section]\nvalue = 1").unwrap();
+    /// let value = u32::de_toml(&toml, "Section.value").unwrap();
+    /// assert_eq!(value, 1);
+    /// ```
+    fn de_toml(toml: &TomlTable, key: &str) -> Result<Self, TomlErr> {
+        match toml.get(key) {
+            Some(value) => DeToml::de_toml_value(value).map_err(|err| TomlErr {
+                msg: format!("{} for key {}", err.msg, key),
+                key: err.key.or_else(|| Some(key.to_string())),
+                ..err
+            }),
+            None => Err(TomlErr::key_not_found(key)),
+        }
+    }
+
+    /// Convert a single TOML value to Self.
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        Err(TomlErr::wrong_type("value", value))
+    }
+
+    /// Whether `key` is present in the document, so that `Option`s and
+    /// defaulted fields know when to fall back.
+    fn is_toml_present(toml: &TomlTable, key: &str) -> bool {
+        toml.contains_key(key)
+    }
+}
+
+impl TomlErr {
+    pub fn key_not_found(key: &str) -> TomlErr {
+        TomlErr {
+            msg: format!("Key not found {}", key),
+            line: 0,
+            col: 0,
+            key: Some(key.to_string()),
+        }
+    }
+
+    fn out_of_range(what: &str, num: f64) -> TomlErr {
+        TomlErr {
+            msg: format!("Value out of range {} for {}", num, what),
+            line: 0,
+            col: 0,
+            key: None,
+        }
+    }
+
+    fn wrong_type(what: &str, value: &Toml) -> TomlErr {
+        TomlErr {
+            msg: format!("Expected {} but got {:?}", what, value),
+            line: 0,
+            col: 0,
+            key: None,
+        }
+    }
+}
+
+macro_rules! impl_de_toml_int {
+    ($($ty:ident)*) => {
+        $(
+            impl DeToml for $ty {
+                fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+                    match value {
+                        Toml::Num(num) if *num % 1. == 0. => {
+                            if *num >= $ty::MIN as f64 && *num < $ty::MAX as f64 + 1. {
+                                Ok(*num as $ty)
+                            } else {
+                                Err(TomlErr::out_of_range(stringify!($ty), *num))
+                            }
+                        }
+                        _ => Err(TomlErr::wrong_type(stringify!($ty), value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_de_toml_int!(u8 u16 u32 u64 usize i8 i16 i32 i64 isize);
+
+impl DeToml for f64 {
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        match value {
+            Toml::Num(num) => Ok(*num),
+            _ => Err(TomlErr::wrong_type("f64", value)),
+        }
+    }
+}
+
+impl DeToml for f32 {
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        match value {
+            Toml::Num(num) if num.is_finite() && (*num as f32).is_infinite() => {
+                Err(TomlErr::out_of_range("f32", *num))
+            }
+            Toml::Num(num) => Ok(*num as f32),
+            _ => Err(TomlErr::wrong_type("f32", value)),
+        }
+    }
+}
+
+impl DeToml for bool {
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        match value {
+            Toml::Bool(boolean) => Ok(*boolean),
+            _ => Err(TomlErr::wrong_type("bool", value)),
+        }
+    }
+}
+
+impl DeToml for String {
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        match value {
+            Toml::Str(string) => Ok(string.clone()),
+            _ => Err(TomlErr::wrong_type("string", value)),
+        }
+    }
+}
+
+impl<T> DeToml for Vec<T>
+where
+    T: DeToml,
+{
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        match value {
+            Toml::SimpleArray(array) => array.iter().map(DeToml::de_toml_value).collect(),
+            Toml::Array(tables) => tables.iter().map(|table| T::de_toml(table, "")).collect(),
+            _ => Err(TomlErr::wrong_type("array", value)),
+        }
+    }
+}
+
+impl<T, const N: usize> DeToml for [T; N]
+where
+    T: DeToml,
+{
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        let vec = Vec::<T>::de_toml_value(value)?;
+        let len = vec.len();
+        vec.try_into().map_err(|_| TomlErr {
+            msg: format!("Expected array of length {} but got length {}", N, len),
+            line: 0,
+            col: 0,
+            key: None,
+        })
+    }
+}
+
+impl<T> DeToml for Option<T>
+where
+    T: DeToml,
+{
+    fn de_toml(toml: &TomlTable, key: &str) -> Result<Self, TomlErr> {
+        if T::is_toml_present(toml, key) {
+            Ok(Some(T::de_toml(toml, key)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        Ok(Some(T::de_toml_value(value)?))
+    }
+
+    fn is_toml_present(toml: &TomlTable, key: &str) -> bool {
+        T::is_toml_present(toml, key)
+    }
+}
+
+impl<T> DeToml for Box<T>
+where
+    T: DeToml,
+{
+    fn de_toml(toml: &TomlTable, key: &str) -> Result<Self, TomlErr> {
+        Ok(Box::new(T::de_toml(toml, key)?))
+    }
+
+    fn de_toml_value(value: &Toml) -> Result<Self, TomlErr> {
+        Ok(Box::new(T::de_toml_value(value)?))
+    }
+
+    fn is_toml_present(toml: &TomlTable, key: &str) -> bool {
+        T::is_toml_present(toml, key)
+    }
+}
+
+/// Join the key of a table and the key of one of its fields, used by the
+/// `DeToml` derive.
+pub fn toml_key(table: &str, key: &str) -> String {
+    if table.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", table, key)
+    }
+}
+
+/// Whether any key of the table `key` is present in the document, used by
+/// the `DeToml` derive.
+pub fn toml_table_present(toml: &TomlTable, key: &str) -> bool {
+    if key.is_empty() {
+        return !toml.is_empty();
+    }
+    let prefix = format!("{}.", key);
+    toml.range::<str, _>((
+        core::ops::Bound::Included(prefix.as_str()),
+        core::ops::Bound::Unbounded,
+    ))
+    .next()
+    .is_some_and(|(first, _)| first.starts_with(&prefix))
+}
+
 /// A parser for TOML string values.
 ///
 /// ```rust
@@ -79,6 +602,10 @@ pub enum TomlTok {
     Equals,
     BlockOpen,
     BlockClose,
+    CurlyOpen,
+    CurlyClose,
+    /// A `.` that isn't joined into a bare key, as in `a."b"` or `a . b`.
+    Dot,
     Comma,
     Eof,
 }
@@ -110,6 +637,9 @@ impl From<TomlTok> for String {
             TomlTok::Equals => '='.to_string(),
             TomlTok::BlockOpen => '['.to_string(),
             TomlTok::BlockClose => ']'.to_string(),
+            TomlTok::CurlyOpen => '{'.to_string(),
+            TomlTok::CurlyClose => '}'.to_string(),
+            TomlTok::Dot => '.'.to_string(),
             TomlTok::Comma => ','.to_string(),
             TomlTok::Eof => '\0'.to_string(),
         }
@@ -117,7 +647,7 @@ impl From<TomlTok> for String {
 }
 
 /// A TOML value.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Toml {
     Str(String),
     Bool(bool),
@@ -125,6 +655,8 @@ pub enum Toml {
     Date(String),
     Array(Vec<BTreeMap<String, Toml>>),
     SimpleArray(Vec<Toml>),
+    /// An inline table, `{ x = 1, y = 2 }`.
+    Table(BTreeMap<String, Toml>),
 }
 
 impl core::ops::Index<usize> for Toml {
@@ -195,6 +727,109 @@ impl Toml {
             _ => panic!(),
         }
     }
+    /// Get the TOML value as an inline table
+    ///
+    /// Panics if the TOML value isn't actually an inline table
+    pub fn table(&self) -> &BTreeMap<String, Toml> {
+        match self {
+            Toml::Table(table) => table,
+            _ => panic!(),
+        }
+    }
+    /// Convert the TOML array into a `Vec` of typed elements
+    ///
+    /// Fails if the TOML value isn't an array or an element has the wrong type
+    ///
+    /// ```rust
+    /// # use nanoserde::*;
+    /// let toml = TomlParser::parse("values = [1, 2, 3]").unwrap();
+    /// let values: Vec<f64> = toml["values"].try_vec().unwrap();
+    /// assert_eq!(values, [1., 2., 3.]);
+    /// ```
+    pub fn try_vec<T: DeToml>(&self) -> Result<Vec<T>, TomlErr> {
+        DeToml::de_toml_value(self)
+    }
+    /// Convert the TOML array into a fixed size array of typed elements
+    ///
+    /// Fails if the TOML value isn't an array of exactly `N` elements or an
+    /// element has the wrong type
+    pub fn try_array<T: DeToml, const N: usize>(&self) -> Result<[T; N], TomlErr> {
+        DeToml::de_toml_value(self)
+    }
+}
+
+/// Lookups in a parsed TOML document or an inline table that return `None`
+/// instead of panicking when a key is absent or has another type.
+///
+/// ```rust
+/// # use nanoserde::*;
+/// let toml = TomlParser::parse("[server]\nport = 8080\npoint = { x = 1 }").unwrap();
+/// assert_eq!(toml.get_num("server.port"), Some(8080.));
+/// assert_eq!(toml.get_num("server.point.x"), Some(1.));
+/// assert_eq!(toml.get_str("server.port"), None);
+/// assert_eq!(toml.get_bool("server.missing"), None);
+/// ```
+pub trait TomlGet {
+    /// The value stored under `key`, without looking into nested tables.
+    fn get(&self, key: &str) -> Option<&Toml>;
+
+    /// The value at the dotted `path`. Parts of the path may be joined keys,
+    /// like those of `[section]`s, or keys of inline tables.
+    fn get_path(&self, path: &str) -> Option<&Toml> {
+        if let Some(value) = self.get(path) {
+            return Some(value);
+        }
+        let mut end = path.len();
+        while let Some(dot) = path[..end].rfind('.') {
+            if let Some(value @ Toml::Table(_)) = self.get(&path[..dot]) {
+                if let Some(value) = value.get_path(&path[dot + 1..]) {
+                    return Some(value);
+                }
+            }
+            end = dot;
+        }
+        None
+    }
+
+    /// The number at the dotted `path`.
+    fn get_num(&self, path: &str) -> Option<f64> {
+        match self.get_path(path) {
+            Some(Toml::Num(num)) => Some(*num),
+            _ => None,
+        }
+    }
+
+    /// The string at the dotted `path`.
+    fn get_str(&self, path: &str) -> Option<&str> {
+        match self.get_path(path) {
+            Some(Toml::Str(string)) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// The boolean at the dotted `path`.
+    fn get_bool(&self, path: &str) -> Option<bool> {
+        match self.get_path(path) {
+            Some(Toml::Bool(boolean)) => Some(*boolean),
+            _ => None,
+        }
+    }
+}
+
+impl TomlGet for TomlTable {
+    fn get(&self, key: &str) -> Option<&Toml> {
+        BTreeMap::get(self, key)
+    }
+}
+
+impl TomlGet for Toml {
+    /// Only inline tables have keys, every other value returns `None`.
+    fn get(&self, key: &str) -> Option<&Toml> {
+        match self {
+            Toml::Table(table) => table.get(key),
+            _ => None,
+        }
+    }
 }
 
 /// The error message when failing to parse a TOML string.
@@ -204,6 +839,8 @@ pub struct TomlErr {
     pub msg: String,
     pub line: usize,
     pub col: usize,
+    /// The key the error is about, for `deserialize_toml` to find its position.
+    key: Option<String>,
 }
 
 impl core::fmt::Debug for TomlErr {
@@ -224,9 +861,13 @@ impl core::fmt::Display for TomlErr {
     }
 }
 
+/// The line and col of the values outside of arrays of tables, by key.
+type TomlPositions = BTreeMap<String, (usize, usize)>;
+
 struct Out {
     out: BTreeMap<String, Toml>,
     active_array_element: Option<(String, usize)>,
+    positions: TomlPositions,
 }
 impl Out {
     fn start_array(&mut self, key: &str) {
@@ -263,17 +904,23 @@ impl Error for TomlErr {}
 impl TomlParser {
     /// Parse a TOML string.
     pub fn parse(data: &str) -> Result<BTreeMap<String, Toml>, TomlErr> {
+        Self::parse_with_positions(data).map(|(toml, _)| toml)
+    }
+
+    /// Parse a TOML string, along with the line and col of each value.
+    fn parse_with_positions(data: &str) -> Result<(TomlTable, TomlPositions), TomlErr> {
         let i = &mut data.chars();
         let mut t = TomlParser::default();
         t.next(i);
         let mut out = Out {
             out: BTreeMap::new(),
             active_array_element: None,
+            positions: BTreeMap::new(),
         };
         let mut local_scope = String::new();
         while t.parse_line(i, &mut local_scope, &mut out)? {}
 
-        Ok(out.out)
+        Ok((out.out, out.positions))
     }
 
     fn parse_line(
@@ -294,8 +941,10 @@ impl TomlParser {
                 let tok = self.next_tok(i)?;
                 match tok {
                     TomlTok::Str(key) | TomlTok::Ident(key) => {
+                        let (key, tok) = self.dotted_key(key, i)?;
                         *local_scope = key;
-                        let tok = self.next_tok(i)?;
+                        // a plain table ends the element of an array of tables
+                        out.active_array_element = None;
                         if tok != TomlTok::BlockClose {
                             return Err(self.err_token(tok));
                         }
@@ -313,7 +962,7 @@ impl TomlParser {
                             | TomlTok::Date(_) => tok.into(),
                             _ => return Err(self.err_token(tok)),
                         };
-                        let tok = self.next_tok(i)?;
+                        let (key, tok) = self.dotted_key(key, i)?;
                         if tok != TomlTok::BlockClose {
                             return Err(self.err_token(tok));
                         }
@@ -321,6 +970,8 @@ impl TomlParser {
                         if tok != TomlTok::BlockClose {
                             return Err(self.err_token(tok));
                         }
+                        // the keys of the element are relative to it
+                        local_scope.clear();
                         out.start_array(&key);
                     }
                     _ => return Err(self.err_token(tok)),
@@ -334,7 +985,7 @@ impl TomlParser {
             | TomlTok::Bool(_)
             | TomlTok::Nan(_)
             | TomlTok::Inf(_)
-            | TomlTok::Date(_) => self.parse_key_value(local_scope, tok.into(), i, out.out())?,
+            | TomlTok::Date(_) => self.parse_key_value(local_scope, tok.into(), i, out)?,
             _ => return Err(self.err_token(tok)),
         }
         Ok(true)
@@ -356,6 +1007,34 @@ impl TomlParser {
                 }
                 Ok(Toml::SimpleArray(vals))
             }
+            TomlTok::CurlyOpen => {
+                let mut table = BTreeMap::new();
+                loop {
+                    let tok = self.next_tok(i)?;
+                    let key = match tok {
+                        TomlTok::CurlyClose => break,
+                        TomlTok::Comma => continue,
+                        TomlTok::Str(_)
+                        | TomlTok::Ident(_)
+                        | TomlTok::U64(_)
+                        | TomlTok::I64(_)
+                        | TomlTok::F64(_)
+                        | TomlTok::Bool(_)
+                        | TomlTok::Nan(_)
+                        | TomlTok::Inf(_)
+                        | TomlTok::Date(_) => tok.into(),
+                        _ => return Err(self.err_token(tok)),
+                    };
+                    let (key, tok) = self.dotted_key(key, i)?;
+                    if tok != TomlTok::Equals {
+                        return Err(self.err_token(tok));
+                    }
+                    let tok = self.next_tok(i)?;
+                    let val = self.to_val(tok, i)?;
+                    table.insert(key, val);
+                }
+                Ok(Toml::Table(table))
+            }
             TomlTok::Str(v) => Ok(Toml::Str(v)),
             TomlTok::U64(v) => Ok(Toml::Num(v as f64)),
             TomlTok::I64(v) => Ok(Toml::Num(v as f64)),
@@ -373,12 +1052,16 @@ impl TomlParser {
         local_scope: &String,
         key: String,
         i: &mut Chars,
-        out: &mut BTreeMap<String, Toml>,
+        out: &mut Out,
     ) -> Result<(), TomlErr> {
-        let tok = self.next_tok(i)?;
+        let (key, tok) = self.dotted_key(key, i)?;
         if tok != TomlTok::Equals {
             return Err(self.err_token(tok));
         }
+        while self.cur == ' ' || self.cur == '\t' {
+            self.next(i);
+        }
+        let position = (self.line, self.col.saturating_sub(1));
         let tok = self.next_tok(i)?;
         let val = self.to_val(tok, i)?;
         let key = if !local_scope.is_empty() {
@@ -386,10 +1069,63 @@ impl TomlParser {
         } else {
             key
         };
-        out.insert(key, val);
+        if let Toml::Table(table) = &val {
+            Self::flatten_inline_table(&key, table, position, out);
+        }
+        if out.active_array_element.is_none() {
+            out.positions.insert(key.clone(), position);
+        }
+        out.out().insert(key, val);
         Ok(())
     }
 
+    /// Also insert the values of an inline table under `key.name`, recursively,
+    /// so they can be read like the keys of a `[key]` section. Their position
+    /// is the one of the whole table.
+    fn flatten_inline_table(key: &str, table: &TomlTable, position: (usize, usize), out: &mut Out) {
+        for (name, val) in table {
+            let key = format!("{}.{}", key, name);
+            if let Toml::Table(table) = val {
+                Self::flatten_inline_table(&key, table, position, out);
+            }
+            if out.active_array_element.is_none() {
+                out.positions.insert(key.clone(), position);
+            }
+            out.out().insert(key, val.clone());
+        }
+    }
+
+    /// Append the remaining segments of a dotted key to its first one, joined
+    /// with `.` like the keys of `[section]`s. Bare segments are already joined
+    /// by the tokenizer, this handles quoted ones and whitespace around dots.
+    /// Returns the key and the token following it.
+    fn dotted_key(&mut self, mut key: String, i: &mut Chars) -> Result<(String, TomlTok), TomlErr> {
+        loop {
+            let tok = self.next_tok(i)?;
+            if tok != TomlTok::Dot {
+                return Ok((key, tok));
+            }
+            let tok = self.next_tok(i)?;
+            match tok {
+                TomlTok::Str(_)
+                | TomlTok::Ident(_)
+                | TomlTok::U64(_)
+                | TomlTok::I64(_)
+                | TomlTok::F64(_)
+                | TomlTok::Bool(_)
+                | TomlTok::Nan(_)
+                | TomlTok::Inf(_)
+                | TomlTok::Date(_) => {
+                    key.push('.');
+                    key.push_str(&String::from(tok));
+                }
+                _ => return Err(self.err_token(tok)),
+            }
+        }
+    }
+
+    /// Read the next char into `cur`. `col` counts the chars read on the
+    /// current line, so the col of `cur` is one less.
     fn next(&mut self, i: &mut Chars) {
         if let Some(c) = i.next() {
             self.cur = c;
@@ -397,7 +1133,7 @@ impl TomlParser {
                 self.line += 1;
                 self.col = 0;
             } else {
-                self.col = 0;
+                self.col += 1;
             }
         } else {
             self.cur = '\0';
@@ -408,7 +1144,8 @@ impl TomlParser {
         TomlErr {
             msg: format!("Unexpected token {:?} ", tok),
             line: self.line,
-            col: self.col,
+            col: self.col.saturating_sub(1),
+            key: None,
         }
     }
 
@@ -416,7 +1153,8 @@ impl TomlParser {
         TomlErr {
             msg: format!("Cannot parse toml {} ", what),
             line: self.line,
-            col: self.col,
+            col: self.col.saturating_sub(1),
+            key: None,
         }
     }
 
@@ -443,6 +1181,18 @@ impl TomlParser {
                     self.next(i);
                     return Ok(TomlTok::BlockClose);
                 }
+                '{' => {
+                    self.next(i);
+                    return Ok(TomlTok::CurlyOpen);
+                }
+                '.' => {
+                    self.next(i);
+                    return Ok(TomlTok::Dot);
+                }
+                '}' => {
+                    self.next(i);
+                    return Ok(TomlTok::CurlyClose);
+                }
                 '=' => {
                     self.next(i);
                     return Ok(TomlTok::Equals);
@@ -512,7 +1262,8 @@ impl TomlParser {
             self.next(i);
         }
 
-        if self.cur == '.' {
+        // a dot followed by anything but a bare key is left to `dotted_key`
+        if self.cur == '.' && matches!(i.clone().next(), Some(ident_chars!())) {
             start.push(self.cur);
             self.next(i);
             return self.parse_ident(i, start); // recursion here could be a problem
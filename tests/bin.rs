@@ -321,3 +321,798 @@ fn binary_crate() {
 
     assert!(test == test_deserialized);
 }
+
+#[test]
+fn std_enums() {
+    use core::cmp::Ordering;
+    use core::num::FpCategory;
+    use core::ops::Bound;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    pub struct Test {
+        a: Vec<FpCategory>,
+        b: Vec<Ordering>,
+        c: Bound<u32>,
+        d: Bound<String>,
+        e: Bound<i8>,
+    }
+
+    let test = Test {
+        a: vec![
+            FpCategory::Nan,
+            FpCategory::Infinite,
+            FpCategory::Zero,
+            FpCategory::Subnormal,
+            FpCategory::Normal,
+        ],
+        b: vec![Ordering::Less, Ordering::Equal, Ordering::Greater],
+        c: Bound::Included(5),
+        d: Bound::Excluded("end".to_string()),
+        e: Bound::Unbounded,
+    };
+
+    let bytes = SerBin::serialize_bin(&test);
+    let test_deserialized: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    assert!(<Ordering as DeBin>::deserialize_bin(&[3, 0]).is_err());
+}
+
+#[test]
+fn unchecked_utf8() {
+    use nanoserde::{de_bin_string_unchecked, DeBinErr};
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    pub struct Test {
+        // Safety: the bytes are only ever produced by `SerBin` in this test
+        #[nserde(unchecked_utf8 = "unsafe")]
+        a: String,
+        b: String,
+    }
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    pub struct TestUnnamed(#[nserde(unchecked_utf8 = "unsafe")] String, u8);
+
+    #[derive(SerBin, PartialEq, Debug)]
+    pub struct TestManual {
+        a: String,
+    }
+
+    impl DeBin for TestManual {
+        fn de_bin(o: &mut usize, d: &[u8]) -> Result<Self, DeBinErr> {
+            Ok(TestManual {
+                // Safety: the bytes were produced by `SerBin` from a `String`
+                a: unsafe { de_bin_string_unchecked(o, d)? },
+            })
+        }
+    }
+
+    let test = Test {
+        a: "trusted \u{1f980}".to_string(),
+        b: "checked".to_string(),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let test_deserialized: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    // truncated input is still rejected, only the UTF-8 validation is skipped
+    assert!(<Test as DeBin>::deserialize_bin(&bytes[..12]).is_err());
+
+    let test = TestUnnamed("trusted".to_string(), 3);
+    let bytes = SerBin::serialize_bin(&test);
+    let test_deserialized: TestUnnamed = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    let test = TestManual {
+        a: "trusted".to_string(),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let test_deserialized: TestManual = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(test, test_deserialized);
+}
+
+#[test]
+fn length_prefix_roundtrip() {
+    use nanoserde::{de_len, ser_len};
+
+    #[cfg(feature = "varint")]
+    let cases: &[(usize, usize)] = &[
+        (0, 1),
+        (0xFC, 1),
+        (0xFD, 3),
+        (0xFFFF, 3),
+        (0x1_0000, 5),
+        (0xFFFF_FFFF, 5),
+        (0x1_0000_0000, 9),
+    ];
+    #[cfg(not(feature = "varint"))]
+    let cases: &[(usize, usize)] = &[(0, 8), (0xFC, 8), (0xFFFF_FFFF, 8), (0x1_0000_0000, 8)];
+
+    for &(len, size) in cases {
+        let mut bytes = Vec::new();
+        ser_len(len, &mut bytes);
+        assert_eq!(bytes.len(), size, "length prefix size of {:#x}", len);
+
+        let mut offset = 0;
+        assert_eq!(de_len(&mut offset, &bytes).unwrap(), len);
+        assert_eq!(offset, size);
+
+        assert!(de_len(&mut 0, &bytes[..size - 1]).is_err());
+    }
+
+    for len in [0xFC, 0xFD, 0xFFFF, 0x1_0000] {
+        let test = vec![7_u8; len];
+        let bytes = SerBin::serialize_bin(&test);
+        let test_deserialized: Vec<u8> = DeBin::deserialize_bin(&bytes).unwrap();
+        assert_eq!(test, test_deserialized);
+
+        let test = "a".repeat(len);
+        let bytes = SerBin::serialize_bin(&test);
+        let test_deserialized: String = DeBin::deserialize_bin(&bytes).unwrap();
+        assert_eq!(test, test_deserialized);
+    }
+}
+
+#[test]
+fn char_roundtrip() {
+    use nanoserde::DeBinErrReason;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    pub struct Test {
+        a: char,
+        b: Vec<char>,
+    }
+
+    let test = Test {
+        a: 'x',
+        b: vec!['\u{e9}', '\u{20ac}', '\u{1f60b}', '\0', char::MAX],
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    assert_eq!(&bytes[..4], &[b'x', 0, 0, 0]);
+    let test_deserialized: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    // a lone surrogate is not a valid char
+    let err = <char as DeBin>::deserialize_bin(&0xD800_u32.to_le_bytes()).unwrap_err();
+    assert_eq!(err.o, 0);
+    assert!(matches!(err.reason, DeBinErrReason::Range(_)));
+    assert_eq!((err.l, err.s), (0, 4));
+
+    let err = <char as DeBin>::deserialize_bin(&[b'x', 0]).unwrap_err();
+    assert_eq!((err.o, err.l, err.s), (0, 4, 2));
+    assert_eq!(err.reason, DeBinErrReason::Length { l: 4, s: 2 });
+}
+
+#[test]
+fn tuple_7() {
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Test {
+        t: (u8, i32, String, bool, f64, Vec<u16>, Option<char>),
+    }
+
+    let test = Test {
+        t: (1, -2, "three".to_string(), true, 5.5, vec![6, 6], Some('7')),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let out: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn skip_serializing_if() {
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Test {
+        a: u8,
+        #[nserde(skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<u8>,
+        b: u8,
+    }
+
+    let test = Test {
+        a: 1,
+        tags: vec![],
+        b: 2,
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    assert_eq!(bytes, [1, 0, 2]);
+    let out: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    let test = Test {
+        a: 1,
+        tags: vec![7],
+        b: 2,
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    assert_eq!(bytes[..2], [1, 1]);
+    let out: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn skip_serializing_and_skip_deserializing() {
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Test {
+        a: u8,
+        #[nserde(skip_deserializing)]
+        version: u8,
+        #[nserde(skip_serializing)]
+        cache: u8,
+        b: u8,
+    }
+
+    let test = Test {
+        a: 1,
+        version: 9,
+        cache: 5,
+        b: 2,
+    };
+    // the skipped version is still read, so `b` lines up
+    let bytes = SerBin::serialize_bin(&test);
+    assert_eq!(bytes, [1, 9, 2]);
+    let out: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(
+        out,
+        Test {
+            a: 1,
+            version: 0,
+            cache: 0,
+            b: 2
+        }
+    );
+}
+
+#[test]
+fn ser_bin_len_reserves_capacity() {
+    let v: Vec<u32> = (0..10_000).collect();
+    let bytes = v.serialize_bin();
+    assert_eq!(v.ser_bin_len(), bytes.len());
+    // without the hint the Vec grows by doubling and overshoots
+    assert_eq!(bytes.capacity(), bytes.len());
+
+    #[derive(SerBin)]
+    struct Frame {
+        id: u16,
+        name: String,
+        payload: Vec<u32>,
+        checksum: Option<u64>,
+    }
+
+    let frame = Frame {
+        id: 1,
+        name: "frame".to_string(),
+        payload: v,
+        checksum: Some(7),
+    };
+    let bytes = frame.serialize_bin();
+    assert_eq!(frame.ser_bin_len(), bytes.len());
+    assert_eq!(bytes.capacity(), bytes.len());
+
+    let mut out = vec![0xAA];
+    out.reserve(frame.ser_bin_len());
+    frame.ser_bin(&mut out);
+    assert_eq!(out[1..], bytes[..]);
+}
+
+#[test]
+fn deserialize_bin_with_offset() {
+    let mut bytes = Vec::new();
+    for value in [7u32, 0xDEAD_BEEF, 42] {
+        value.ser_bin(&mut bytes);
+    }
+
+    let mut rest = &bytes[..];
+    let mut values = Vec::new();
+    while !rest.is_empty() {
+        let (value, used) = u32::deserialize_bin_with_offset(rest).unwrap();
+        values.push(value);
+        rest = &rest[used..];
+    }
+    assert_eq!(values, [7, 0xDEAD_BEEF, 42]);
+
+    let hi = "hi".to_string();
+    let (s, used) = String::deserialize_bin_with_offset(&hi.serialize_bin()).unwrap();
+    assert_eq!(s, hi);
+    assert_eq!(used, hi.ser_bin_len());
+
+    assert!(u32::deserialize_bin_with_offset(&[1, 2, 3]).is_err());
+}
+
+#[test]
+fn phantom_data() {
+    use core::marker::PhantomData;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Tagged<T> {
+        a: u32,
+        marker: PhantomData<T>,
+        b: String,
+    }
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Plain {
+        a: u32,
+        b: String,
+    }
+
+    let tagged = Tagged::<u64> {
+        a: 1,
+        marker: PhantomData,
+        b: "b".to_string(),
+    };
+    let plain = Plain {
+        a: 1,
+        b: "b".to_string(),
+    };
+    let bytes = tagged.serialize_bin();
+    assert_eq!(bytes, plain.serialize_bin());
+    assert_eq!(bytes.len(), tagged.ser_bin_len());
+
+    let out: Tagged<u64> = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, tagged);
+    assert!(PhantomData::<u64>.serialize_bin().is_empty());
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Unit;
+
+    assert!(Unit.serialize_bin().is_empty());
+    assert_eq!(Unit::deserialize_bin(&[]).unwrap(), Unit);
+}
+
+#[derive(DeBin, SerBin, PartialEq, Debug)]
+#[nserde(with = "packed")]
+pub struct Flags {
+    a: bool,
+    b: bool,
+    c: bool,
+}
+
+mod packed {
+    use super::Flags;
+    use nanoserde::{DeBin, DeBinErr, SerBin};
+
+    pub fn ser_bin(flags: &Flags, s: &mut Vec<u8>) {
+        (flags.a as u8 | (flags.b as u8) << 1 | (flags.c as u8) << 2).ser_bin(s);
+    }
+
+    pub fn de_bin(o: &mut usize, d: &[u8]) -> Result<Flags, DeBinErr> {
+        let bits = u8::de_bin(o, d)?;
+        Ok(Flags {
+            a: bits & 1 != 0,
+            b: bits & 2 != 0,
+            c: bits & 4 != 0,
+        })
+    }
+}
+
+#[test]
+fn container_with() {
+    let flags = vec![
+        Flags {
+            a: true,
+            b: false,
+            c: true,
+        },
+        Flags {
+            a: false,
+            b: true,
+            c: false,
+        },
+    ];
+    let bytes = flags.serialize_bin();
+    assert_eq!(bytes[bytes.len() - 2..], [0b101, 0b010]);
+    let out: Vec<Flags> = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, flags);
+}
+
+#[test]
+fn rc_and_arc() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Test {
+        a: Rc<String>,
+        b: Arc<Vec<u32>>,
+        c: Option<Rc<i32>>,
+    }
+
+    let test = Test {
+        a: Rc::new("shared".to_string()),
+        b: Arc::new(vec![1, 2]),
+        c: Some(Rc::new(3)),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let out: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    let rc = Rc::new("hi".to_string());
+    assert_eq!(rc.serialize_bin(), "hi".to_string().serialize_bin());
+    let out: Rc<String> = DeBin::deserialize_bin(&rc.serialize_bin()).unwrap();
+    assert_eq!(out, rc);
+}
+
+#[test]
+fn cow() {
+    use std::borrow::Cow;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Test {
+        name: Cow<'static, str>,
+        values: Cow<'static, [u32]>,
+    }
+
+    let test = Test {
+        name: Cow::Borrowed("borrowed"),
+        values: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let out: Test = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+    assert!(matches!(out.name, Cow::Owned(_)));
+
+    let name: Cow<str> = Cow::Borrowed("owned");
+    assert_eq!(name.serialize_bin(), "owned".to_string().serialize_bin());
+    let out: Cow<str> = DeBin::deserialize_bin(&name.serialize_bin()).unwrap();
+    assert_eq!(out, name);
+
+    let values: Cow<[u32]> = Cow::Borrowed(&[4, 5]);
+    assert_eq!(values.serialize_bin(), vec![4u32, 5].serialize_bin());
+}
+
+#[test]
+fn nonzero() {
+    use nanoserde::DeBinErrReason;
+    use std::num::{NonZeroI128, NonZeroU32, NonZeroU8};
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Handle {
+        id: NonZeroU32,
+        generation: Option<NonZeroU8>,
+        big: NonZeroI128,
+    }
+
+    let test = Handle {
+        id: NonZeroU32::new(7).unwrap(),
+        generation: NonZeroU8::new(1),
+        big: NonZeroI128::new(-3).unwrap(),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    assert_eq!(&bytes[..4], &7u32.to_le_bytes());
+    let out: Handle = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    let err = <NonZeroU8 as DeBin>::deserialize_bin(&[0]).unwrap_err();
+    assert_eq!(err.o, 0);
+    assert!(matches!(err.reason, DeBinErrReason::Range(_)));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn net_addrs() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Peer {
+        v4: Ipv4Addr,
+        v6: Ipv6Addr,
+        ip: IpAddr,
+        addr: SocketAddr,
+        fallback: Option<SocketAddr>,
+    }
+
+    let test = Peer {
+        v4: Ipv4Addr::new(192, 168, 0, 1),
+        v6: Ipv6Addr::LOCALHOST,
+        ip: IpAddr::V6("fe80::1".parse().unwrap()),
+        addr: "[2001:db8::1]:8080".parse().unwrap(),
+        fallback: Some("10.0.0.1:53".parse().unwrap()),
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    assert_eq!(&bytes[..4], &[192, 168, 0, 1]);
+    assert_eq!(bytes.len(), test.ser_bin_len());
+    let out: Peer = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    // the flow info isn't kept, same as in the text formats
+    let v6 = std::net::SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 7, 3);
+    let bytes = v6.serialize_bin();
+    assert_eq!(bytes.len(), 22);
+    let out: std::net::SocketAddrV6 = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(
+        out,
+        std::net::SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 0, 3)
+    );
+
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    assert_eq!(ip.serialize_bin(), [0, 0, 127, 0, 0, 1]);
+    assert!(<IpAddr as DeBin>::deserialize_bin(&[2, 0, 127, 0, 0, 1]).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn path_buf() {
+    use std::path::{Path, PathBuf};
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Config {
+        root: PathBuf,
+        cache: Option<PathBuf>,
+    }
+
+    let test = Config {
+        root: PathBuf::from("data/caf\u{e9}/\u{1f980}.txt"),
+        cache: None,
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let out: Config = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    let path: &Path = Path::new("a/b");
+    assert_eq!(path.serialize_bin(), "a/b".to_string().serialize_bin());
+
+    let mut bytes = "ab".to_string().serialize_bin();
+    *bytes.last_mut().unwrap() = 0xff;
+    assert!(<PathBuf as DeBin>::deserialize_bin(&bytes).is_err());
+}
+
+#[test]
+fn ranges() {
+    use core::ops::{Range, RangeInclusive};
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Selection {
+        lines: Range<usize>,
+        columns: RangeInclusive<i32>,
+        spans: Vec<Range<u8>>,
+    }
+
+    let test = Selection {
+        lines: 3..7,
+        columns: -3..=7,
+        spans: vec![0..1, 5..5],
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let out: Selection = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    assert_eq!((3u8..7).serialize_bin(), [3, 7]);
+    assert_eq!((3u8..=7).serialize_bin(), [3, 7]);
+}
+
+#[test]
+fn byte_buffers() {
+    use nanoserde::ser_len;
+
+    let data: Vec<u8> = (0..1024 * 1024).map(|i| (i * 7 % 251) as u8).collect();
+
+    let bytes = data.serialize_bin();
+    let out: Vec<u8> = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, data);
+
+    // same layout as any other Vec: the length prefix followed by the items
+    let mut prefix = Vec::new();
+    ser_len(data.len(), &mut prefix);
+    assert_eq!(&bytes[..prefix.len()], &prefix[..]);
+    assert_eq!(&bytes[prefix.len()..], &data[..]);
+
+    // slices and arrays have no length prefix
+    assert_eq!(data[..3].serialize_bin(), [0, 7, 14]);
+    let array: [u8; 4] = DeBin::deserialize_bin(&[1, 2, 3, 4, 5]).unwrap();
+    assert_eq!(array, [1, 2, 3, 4]);
+    assert_eq!(array.serialize_bin(), [1, 2, 3, 4]);
+
+    assert!(<[u8; 4] as DeBin>::deserialize_bin(&[1, 2, 3]).is_err());
+    assert!(<Vec<u8> as DeBin>::deserialize_bin(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn vec_deque_and_binary_heap() {
+    use std::collections::{BinaryHeap, VecDeque};
+
+    #[derive(DeBin, SerBin)]
+    struct Queues {
+        queue: VecDeque<u32>,
+        heap: BinaryHeap<i32>,
+    }
+
+    let mut queue: VecDeque<u32> = (3..6).collect();
+    queue.push_front(2);
+    queue.push_front(1);
+    let test = Queues {
+        queue,
+        heap: [5, -1, 9, 5, 0].into_iter().collect(),
+    };
+
+    let bytes = test.serialize_bin();
+    let out: Queues = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out.queue, [1, 2, 3, 4, 5]);
+    // a heap doesn't keep the insertion order, only the same items
+    assert_eq!(out.heap.into_sorted_vec(), [-1, 0, 5, 5, 9]);
+
+    let queue: VecDeque<u8> = DeBin::deserialize_bin(&vec![7u8, 8].serialize_bin()).unwrap();
+    assert_eq!(queue, [7, 8]);
+}
+
+#[test]
+fn relative_instant() {
+    use core::time::Duration;
+    use nanoserde::RelativeInstant;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Event {
+        name: String,
+        at: RelativeInstant,
+    }
+
+    let test = Event {
+        name: "loaded".to_string(),
+        at: RelativeInstant(Duration::new(3, 250_000_000)),
+    };
+    let out: Event = DeBin::deserialize_bin(&test.serialize_bin()).unwrap();
+    assert_eq!(out, test);
+    assert_eq!(
+        RelativeInstant(Duration::from_millis(1500)).serialize_bin(),
+        Duration::from_millis(1500).serialize_bin()
+    );
+
+    let mut bytes = 1u64.serialize_bin();
+    bytes.extend_from_slice(&1_000_000_000u32.serialize_bin());
+    assert!(<Duration as DeBin>::deserialize_bin(&bytes).is_err());
+}
+
+#[test]
+fn wrapping() {
+    use core::num::Wrapping;
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Checksum {
+        sum: Wrapping<u32>,
+        parts: Vec<Wrapping<u32>>,
+    }
+
+    let test = Checksum {
+        sum: Wrapping(u32::MAX) + Wrapping(2),
+        parts: vec![Wrapping(u32::MAX), Wrapping(2)],
+    };
+    let bytes = test.serialize_bin();
+    let out: Checksum = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+    assert_eq!(out.sum, Wrapping(1));
+    assert_eq!(Wrapping(7u32).serialize_bin(), 7u32.serialize_bin());
+}
+
+#[test]
+fn ser_reference() {
+    fn to_bin<T: SerBin>(value: T) -> Vec<u8> {
+        value.serialize_bin()
+    }
+
+    let items = vec![1, 2, 3];
+    assert_eq!(to_bin(&items), items.serialize_bin());
+    assert_eq!(SerBin::ser_bin_len(&&items), items.ser_bin_len());
+    assert_eq!(Vec::<i32>::deserialize_bin(&to_bin(&items)).unwrap(), items);
+}
+
+#[test]
+fn open_ranges() {
+    use core::ops::{RangeFrom, RangeFull, RangeTo};
+
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Slicing {
+        from: RangeFrom<usize>,
+        to: RangeTo<i32>,
+        full: RangeFull,
+    }
+
+    let test = Slicing {
+        from: 3..,
+        to: ..-7,
+        full: ..,
+    };
+    let bytes = SerBin::serialize_bin(&test);
+    let out: Slicing = DeBin::deserialize_bin(&bytes).unwrap();
+    assert_eq!(out, test);
+
+    assert_eq!((3u8..).serialize_bin(), [3]);
+    assert_eq!((..7u8).serialize_bin(), [7]);
+    assert!(RangeFull.serialize_bin().is_empty());
+}
+
+#[test]
+fn bin_tags() {
+    mod v1 {
+        use nanoserde::{DeBin, SerBin};
+
+        #[derive(DeBin, SerBin, PartialEq, Debug)]
+        pub enum Message {
+            #[nserde(bin_tag = 1)]
+            Ping,
+            #[nserde(bin_tag = 2)]
+            Text(String),
+            #[nserde(bin_tag = 3)]
+            Move { x: i32, y: i32 },
+        }
+    }
+
+    // reordered, with a new variant taking an unused tag
+    mod v2 {
+        use nanoserde::{DeBin, SerBin};
+
+        #[derive(DeBin, SerBin, PartialEq, Debug)]
+        pub enum Message {
+            #[nserde(bin_tag = 3)]
+            Move { x: i32, y: i32 },
+            #[nserde(bin_tag = 100)]
+            Close,
+            #[nserde(bin_tag = 2)]
+            Text(String),
+            #[nserde(bin_tag = 1)]
+            Ping,
+        }
+    }
+
+    let old = [
+        v1::Message::Ping,
+        v1::Message::Text("hi".to_string()),
+        v1::Message::Move { x: 1, y: -1 },
+    ];
+    let new = [
+        v2::Message::Ping,
+        v2::Message::Text("hi".to_string()),
+        v2::Message::Move { x: 1, y: -1 },
+    ];
+    for (old, new) in old.iter().zip(new.iter()) {
+        let bytes = old.serialize_bin();
+        assert_eq!(bytes, new.serialize_bin());
+        assert_eq!(&v2::Message::deserialize_bin(&bytes).unwrap(), new);
+        assert_eq!(&v1::Message::deserialize_bin(&bytes).unwrap(), old);
+    }
+    assert_eq!(v1::Message::Ping.serialize_bin(), [1, 0]);
+    assert_eq!(v2::Message::Close.serialize_bin(), [100, 0]);
+    assert!(v1::Message::deserialize_bin(&v2::Message::Close.serialize_bin()).is_err());
+}
+
+#[test]
+fn serialize_into() {
+    #[derive(SerBin)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let mut out = Vec::new();
+    Point { x: 1, y: 2 }.serialize_bin_into(&mut out);
+    assert_eq!(out, Point { x: 1, y: 2 }.serialize_bin());
+    let capacity = out.capacity();
+
+    out.clear();
+    Point { x: 3, y: 4 }.serialize_bin_into(&mut out);
+    assert_eq!(out, [3, 0, 0, 0, 4, 0, 0, 0]);
+    assert_eq!(out.capacity(), capacity);
+
+    // appends to what is already in the buffer
+    0xffu8.serialize_bin_into(&mut out);
+    assert_eq!(out, [3, 0, 0, 0, 4, 0, 0, 0, 0xff]);
+}
+
+#[test]
+fn result_roundtrip() {
+    #[derive(DeBin, SerBin, PartialEq, Debug)]
+    struct Outcome {
+        parsed: Result<u32, String>,
+        failed: Result<Vec<u8>, String>,
+    }
+
+    let test = Outcome {
+        parsed: Ok(42),
+        failed: Err("no input".to_string()),
+    };
+    let bytes = test.serialize_bin();
+    assert_eq!(&bytes[..6], [0, 0, 42, 0, 0, 0]);
+    assert_eq!(&bytes[6..8], [1, 0]);
+    assert_eq!(bytes.len(), test.ser_bin_len());
+    assert_eq!(Outcome::deserialize_bin(&bytes).unwrap(), test);
+
+    assert!(<Result<u8, u8> as DeBin>::deserialize_bin(&[2, 0, 1]).is_err());
+}
@@ -0,0 +1,90 @@
+#![no_std]
+
+// formats errors through `core::fmt` only, so this also covers builds without the `std` feature
+use core::fmt::Write;
+
+struct Buf {
+    data: [u8; 256],
+    len: usize,
+}
+
+impl Buf {
+    fn new() -> Self {
+        Buf {
+            data: [0; 256],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.data[..self.len]).unwrap()
+    }
+}
+
+impl Write for Buf {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > self.data.len() {
+            return Err(core::fmt::Error);
+        }
+        self.data[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_error_display() {
+    use nanoserde::DeJson;
+
+    let err = u32::deserialize_json("\n  true").unwrap_err();
+    let mut buf = Buf::new();
+    write!(buf, "{}", err).unwrap();
+    assert_eq!(
+        buf.as_str(),
+        "Json Deserialize error: Unexpected token Bool(true) expected unsigned integer , line:2 col:7 offset:7"
+    );
+}
+
+#[test]
+#[cfg(feature = "ron")]
+fn ron_error_display() {
+    use nanoserde::DeRon;
+
+    let err = u32::deserialize_ron("true").unwrap_err();
+    let mut buf = Buf::new();
+    write!(buf, "{}", err).unwrap();
+    assert_eq!(
+        buf.as_str(),
+        "Ron Deserialize error: Unexpected token Bool(true) expected unsigned integer , line:1 col:5"
+    );
+}
+
+#[test]
+#[cfg(feature = "binary")]
+fn bin_error_display() {
+    use nanoserde::DeBin;
+
+    let err = u32::deserialize_bin(&[1, 2]).unwrap_err();
+    let mut buf = Buf::new();
+    write!(buf, "{}", err).unwrap();
+    assert_eq!(
+        buf.as_str(),
+        "Bin deserialize error at:0 wanted:4 bytes but max size is 2"
+    );
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn toml_error_display() {
+    use nanoserde::TomlParser;
+
+    let err = TomlParser::parse("a = @").unwrap_err();
+    let mut buf = Buf::new();
+    write!(buf, "{}", err).unwrap();
+    assert_eq!(
+        buf.as_str(),
+        "Toml error: Cannot parse toml tokenizer , line:1 col:5"
+    );
+}
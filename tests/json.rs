@@ -649,6 +649,64 @@ fn collections() {
     assert_eq!(test, test_deserialized);
 }
 
+#[test]
+fn named_struct_transparent() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Id {
+        value: u64,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Tagged {
+        #[nserde(skip)]
+        cached: Option<u32>,
+        name: String,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Record {
+        id: Id,
+        tags: Vec<Tagged>,
+    }
+
+    let test = Record {
+        id: Id { value: 7 },
+        tags: vec![Tagged {
+            cached: None,
+            name: "a".to_string(),
+        }],
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"id":7,"tags":["a"]}"#);
+    let out: Record = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    let id: Id = DeJson::deserialize_json("12").unwrap();
+    assert_eq!(id, Id { value: 12 });
+    assert!(<Id as DeJson>::deserialize_json(r#"{"value":12}"#).is_err());
+}
+
+#[test]
+fn ser_map_from_pairs() {
+    use nanoserde::{ser_json_map, SerJsonState};
+
+    let mut s = SerJsonState::new(String::new());
+    ser_json_map((0..3).map(|i| (i, i * i)), 0, &mut s);
+    assert_eq!(s.out, r#"{"0":0,"1":1,"2":4}"#);
+    let map: std::collections::BTreeMap<String, i32> = DeJson::deserialize_json(&s.out).unwrap();
+    assert_eq!(map["2"], 4);
+
+    let mut s = SerJsonState::new(String::new());
+    ser_json_map(std::iter::empty::<(String, u8)>(), 0, &mut s);
+    assert_eq!(s.out, "{}");
+
+    let mut s = SerJsonState::new(String::new());
+    ser_json_map(vec![("a\"b".to_string(), vec![1u8])], 0, &mut s);
+    assert_eq!(s.out, r#"{"a\"b":[1]}"#);
+}
+
 #[test]
 fn jsonerror() {
     #[derive(DeJson)]
@@ -670,6 +728,26 @@ fn jsonerror() {
     }
 }
 
+#[test]
+fn jsonerror_offset() {
+    #[derive(DeJson)]
+    #[allow(dead_code)]
+    struct Foo {
+        a: u32,
+        b: u32,
+        c: u32,
+    }
+
+    let json = "{\"a\": 1,\n \"b\": true,\n \"c\": 3}";
+
+    let e = <Foo as DeJson>::deserialize_json(json).err().unwrap();
+    assert_eq!(e.line, 1);
+    // the parser stops right after `true`
+    assert_eq!(e.offset, json.find("true").unwrap() + 5);
+    assert_eq!(&json[..e.offset - 1], "{\"a\": 1,\n \"b\": true");
+    assert_eq!(e.snippet(json), " \"b\": true,\n          ^");
+}
+
 #[test]
 fn de_tuple_fields() {
     #[derive(DeJson, PartialEq, Debug)]
@@ -999,18 +1077,28 @@ fn field_option_proxy() {
 
     #[derive(DeJson, SerJson, PartialEq, Debug)]
     enum Test2 {
-        //A(#[nserde(proxy = "U32")] Option<SomeEnum>),
+        A(#[nserde(proxy = "U32")] Option<SomeEnum>),
+        C(u8, #[nserde(proxy = "U32")] SomeEnum),
         B {
             #[nserde(proxy = "U32")]
             bar: Option<SomeEnum>,
         },
     }
 
-    // TODO
-    // let test = Test2::A(Some(SomeEnum::Three));
-    // let bytes = SerJson::serialize_json(&test);
-    // let test_deserialized = DeJson::deserialize_json(&bytes).unwrap();
-    // assert!(test == test_deserialized);
+    let test = Test2::A(Some(SomeEnum::Three));
+    let bytes = SerJson::serialize_json(&test);
+    assert_eq!(bytes, r#"{"A":[2]}"#);
+    let test_deserialized = DeJson::deserialize_json(&bytes).unwrap();
+    assert!(test == test_deserialized);
+    let test = Test2::A(None);
+    let bytes = SerJson::serialize_json(&test);
+    let test_deserialized = DeJson::deserialize_json(&bytes).unwrap();
+    assert!(test == test_deserialized);
+    let test = Test2::C(7, SomeEnum::Two);
+    let bytes = SerJson::serialize_json(&test);
+    assert_eq!(bytes, r#"{"C":[7,1]}"#);
+    let test_deserialized = DeJson::deserialize_json(&bytes).unwrap();
+    assert!(test == test_deserialized);
     let test = Test2::B {
         bar: Some(SomeEnum::One),
     };
@@ -1060,6 +1148,33 @@ fn tuple_struct_transparent() {
     assert!(test == test_deserialized);
 }
 
+#[test]
+fn tuple_struct_transparent_root() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Id(i32);
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Name(String);
+
+    let id: Id = DeJson::deserialize_json("5").unwrap();
+    assert_eq!(id, Id(5));
+    let id: Id = DeJson::deserialize_json(" \n-7 ").unwrap();
+    assert_eq!(id, Id(-7));
+    assert_eq!(Id(5).serialize_json(), "5");
+
+    let name: Name = DeJson::deserialize_json(r#""root""#).unwrap();
+    assert_eq!(name, Name("root".to_string()));
+    assert_eq!(name.serialize_json(), r#""root""#);
+
+    let ids: Vec<Id> = DeJson::deserialize_json("[1,2]").unwrap();
+    assert_eq!(ids, [Id(1), Id(2)]);
+
+    assert!(<Id as DeJson>::deserialize_json("[5]").is_err());
+    assert!(<Id as DeJson>::deserialize_json(r#"{"0":5}"#).is_err());
+}
+
 #[test]
 fn tuple_struct2() {
     #[derive(DeJson, SerJson, PartialEq)]
@@ -1151,6 +1266,66 @@ fn array_leak_test() {
     assert!(TOGGLED_ON_DROP.load(std::sync::atomic::Ordering::SeqCst))
 }
 
+#[test]
+fn option_array_leak_test() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Default, SerJson, DeJson)]
+    struct IncrementOnDrop {
+        inner: u64,
+    }
+
+    impl Drop for IncrementOnDrop {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let serialized = {
+        let items = [
+            Some(IncrementOnDrop::default()),
+            None,
+            Some(IncrementOnDrop::default()),
+        ];
+        nanoserde::SerJson::serialize_json(&items)
+    };
+    assert_eq!(serialized, r#"[{"inner":0},null,{"inner":0}]"#);
+
+    // fails on the closing bracket, after every element was read
+    DROPPED.store(0, Ordering::SeqCst);
+    let corrupted = &serialized[..serialized.len() - 1];
+    assert!(<[Option<IncrementOnDrop>; 3] as DeJson>::deserialize_json(corrupted).is_err());
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+
+    // fails in the middle of the third element
+    DROPPED.store(0, Ordering::SeqCst);
+    let corrupted = r#"[{"inner":1},null,{"inner":"x"}]"#;
+    assert!(<[Option<IncrementOnDrop>; 3] as DeJson>::deserialize_json(corrupted).is_err());
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+
+    DROPPED.store(0, Ordering::SeqCst);
+    let items: [Option<IncrementOnDrop>; 3] = DeJson::deserialize_json(&serialized).unwrap();
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+    drop(items);
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn large_array() {
+    let items: Box<[u64; 4096]> = Box::new(core::array::from_fn(|i| i as u64 * 3));
+    let serialized = nanoserde::SerJson::serialize_json(&*items);
+
+    let deserialized: Box<[u64; 4096]> =
+        Box::new(<[u64; 4096] as DeJson>::deserialize_json(&serialized).unwrap());
+    assert_eq!(items, deserialized);
+
+    let truncated = &serialized[..serialized.rfind(',').unwrap()];
+    assert!(<[u64; 4096] as DeJson>::deserialize_json(truncated).is_err());
+    assert!(<[u64; 4096] as DeJson>::deserialize_json("[1, 2, 3]").is_err());
+}
+
 // https://github.com/not-fl3/nanoserde/issues/89
 #[test]
 fn test_deser_oversized_value() {
@@ -1202,3 +1377,2399 @@ fn json_crate() {
     assert_eq!(test.d.unwrap(), "hello");
     assert_eq!(test.c, None);
 }
+
+#[test]
+fn std_enums() {
+    use core::cmp::Ordering;
+    use core::num::FpCategory;
+    use core::ops::Bound;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Test {
+        a: FpCategory,
+        b: Ordering,
+        c: Bound<u32>,
+        d: Bound<String>,
+        e: Bound<i8>,
+    }
+
+    let test = Test {
+        a: FpCategory::Subnormal,
+        b: Ordering::Greater,
+        c: Bound::Included(5),
+        d: Bound::Excluded("end".to_string()),
+        e: Bound::Unbounded,
+    };
+
+    let json = SerJson::serialize_json(&test);
+    assert_eq!(
+        json,
+        r#"{"a":"Subnormal","b":"Greater","c":{"Included":[5]},"d":{"Excluded":["end"]},"e":"Unbounded"}"#
+    );
+    let test_deserialized: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    let categories = vec![
+        FpCategory::Nan,
+        FpCategory::Infinite,
+        FpCategory::Zero,
+        FpCategory::Subnormal,
+        FpCategory::Normal,
+    ];
+    let json = SerJson::serialize_json(&categories);
+    assert_eq!(
+        categories,
+        <Vec<FpCategory>>::deserialize_json(&json).unwrap()
+    );
+
+    let orderings = vec![Ordering::Less, Ordering::Equal, Ordering::Greater];
+    let json = SerJson::serialize_json(&orderings);
+    assert_eq!(orderings, <Vec<Ordering>>::deserialize_json(&json).unwrap());
+
+    assert!(<Ordering as DeJson>::deserialize_json(r#""Smaller""#).is_err());
+}
+
+#[test]
+fn i128_bare_number() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Test {
+        a: i128,
+        b: i128,
+        c: i128,
+    }
+
+    let json = r#"{
+        "a": -170141183460469231731687303715884105728,
+        "b": 170141183460469231731687303715884105727,
+        "c": -5
+    }"#;
+    let test: Test = DeJson::deserialize_json(json).unwrap();
+    assert_eq!(
+        test,
+        Test {
+            a: i128::MIN,
+            b: i128::MAX,
+            c: -5
+        }
+    );
+
+    let json = SerJson::serialize_json(&test);
+    assert_eq!(
+        json,
+        r#"{"a":-170141183460469231731687303715884105728,"b":170141183460469231731687303715884105727,"c":-5}"#
+    );
+    assert_eq!(test, DeJson::deserialize_json(&json).unwrap());
+
+    // one past i128::MAX still tokenizes, but is out of range for the field
+    assert!(<i128 as DeJson>::deserialize_json("170141183460469231731687303715884105728").is_err());
+    assert!(<i64 as DeJson>::deserialize_json("-9223372036854775809").is_err());
+
+    // values too wide for 64 bits are out of range for the smaller integers
+    for (json, reason) in [
+        (
+            "-9223372036854775809",
+            "-9223372036854775809<-9223372036854775808",
+        ),
+        (
+            "18446744073709551616",
+            "18446744073709551616>9223372036854775807",
+        ),
+    ] {
+        let err = <i64 as DeJson>::deserialize_json(json).unwrap_err();
+        assert_eq!(
+            err.reason,
+            nanoserde::DeJsonErrReason::OutOfRange(reason.to_string())
+        );
+    }
+    let err = <u64 as DeJson>::deserialize_json("18446744073709551616").unwrap_err();
+    assert_eq!(
+        err.reason,
+        nanoserde::DeJsonErrReason::OutOfRange(
+            "18446744073709551616>18446744073709551615".to_string()
+        )
+    );
+}
+
+#[test]
+fn wide_integer_boundaries() {
+    use std::num::NonZeroU128;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Test {
+        a: u64,
+        b: i64,
+        c: u128,
+        d: NonZeroU128,
+    }
+
+    let test = Test {
+        a: u64::MAX,
+        b: i64::MIN,
+        c: u128::MAX,
+        d: NonZeroU128::new(u128::MAX).unwrap(),
+    };
+    let json = SerJson::serialize_json(&test);
+    assert_eq!(
+        json,
+        r#"{"a":18446744073709551615,"b":-9223372036854775808,"c":340282366920938463463374607431768211455,"d":340282366920938463463374607431768211455}"#
+    );
+    assert_eq!(test, DeJson::deserialize_json(&json).unwrap());
+
+    // above i64::MAX but within u64 used to wrap around to a negative i64
+    assert!(<i64 as DeJson>::deserialize_json("18446744073709551615").is_err());
+    assert!(<i64 as DeJson>::deserialize_json("9223372036854775808").is_err());
+    assert!(<u64 as DeJson>::deserialize_json("18446744073709551616").is_err());
+    assert!(<u128 as DeJson>::deserialize_json("-1").is_err());
+    assert!(<u128 as DeJson>::deserialize_json("340282366920938463463374607431768211456").is_err());
+}
+
+#[test]
+fn duration() {
+    use std::time::Duration;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Test {
+        a: Duration,
+        b: Vec<Duration>,
+    }
+
+    let test = Test {
+        a: Duration::new(5, 999_999_999),
+        b: vec![Duration::ZERO, Duration::from_millis(1500), Duration::MAX],
+    };
+
+    let json = SerJson::serialize_json(&test.a);
+    assert_eq!(json, r#"{"secs":5,"nanos":999999999}"#);
+
+    let json = SerJson::serialize_json(&test);
+    let test_deserialized: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    let reordered: Duration = DeJson::deserialize_json(r#"{"nanos": 1, "secs": 2}"#).unwrap();
+    assert_eq!(reordered, Duration::new(2, 1));
+
+    assert!(<Duration as DeJson>::deserialize_json(r#"{"secs":1,"nanos":1000000000}"#).is_err());
+    assert!(<Duration as DeJson>::deserialize_json(r#"{"secs":1}"#).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn system_time() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let json = SerJson::serialize_json(&UNIX_EPOCH);
+    assert_eq!(json, r#"{"secs":0,"nanos":0}"#);
+    assert_eq!(
+        <SystemTime as DeJson>::deserialize_json(&json).unwrap(),
+        UNIX_EPOCH
+    );
+
+    let time = UNIX_EPOCH + Duration::new(1_700_000_000, 999_999_999);
+    let json = SerJson::serialize_json(&time);
+    assert_eq!(
+        <SystemTime as DeJson>::deserialize_json(&json).unwrap(),
+        time
+    );
+
+    let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+    let json = SerJson::serialize_json(&before_epoch);
+    assert_eq!(json, "null");
+    assert!(<SystemTime as DeJson>::deserialize_json(&json).is_err());
+}
+
+#[test]
+fn field_groups() {
+    #[derive(SerJson)]
+    pub struct User {
+        #[nserde(group = "public")]
+        #[nserde(group = "admin")]
+        id: u32,
+        #[nserde(group = "public", rename = "displayName")]
+        name: String,
+        #[nserde(group = "admin")]
+        email: Option<String>,
+        password_hash: String,
+    }
+
+    let user = User {
+        id: 7,
+        name: "ferris".to_string(),
+        email: Some("ferris@example.com".to_string()),
+        password_hash: "hunter2".to_string(),
+    };
+
+    assert_eq!(
+        user.serialize_json_group("public"),
+        r#"{"id":7,"displayName":"ferris"}"#
+    );
+    assert_eq!(
+        user.serialize_json_group("admin"),
+        r#"{"id":7,"email":"ferris@example.com"}"#
+    );
+    assert_eq!(user.serialize_json_group("unknown"), "{}");
+    assert_eq!(
+        user.serialize_json(),
+        r#"{"id":7,"displayName":"ferris","email":"ferris@example.com","password_hash":"hunter2"}"#
+    );
+}
+
+#[test]
+fn top_level_result_option() {
+    let ok: Result<i32, String> = DeJson::deserialize_json(r#"{"Ok": 5}"#).unwrap();
+    assert_eq!(ok, Ok(5));
+    let err: Result<i32, String> = DeJson::deserialize_json(r#"{"Err": "boom"}"#).unwrap();
+    assert_eq!(err, Err("boom".to_string()));
+    assert!(<Result<i32, String> as DeJson>::deserialize_json(r#"{"Maybe": 5}"#).is_err());
+
+    assert_eq!(SerJson::serialize_json(&ok), r#"{"Ok":5}"#);
+    assert_eq!(SerJson::serialize_json(&err), r#"{"Err":"boom"}"#);
+
+    let none: Option<i32> = DeJson::deserialize_json("null").unwrap();
+    assert_eq!(none, None);
+    let some: Option<i32> = DeJson::deserialize_json("5").unwrap();
+    assert_eq!(some, Some(5));
+    assert_eq!(SerJson::serialize_json(&none), "null");
+    assert_eq!(SerJson::serialize_json(&some), "5");
+
+    let nested: Option<Result<Vec<u8>, ()>> = DeJson::deserialize_json(r#"{"Ok":[1,2]}"#).unwrap();
+    assert_eq!(nested, Some(Ok(vec![1, 2])));
+}
+
+#[test]
+fn missing_key_lists_expected_keys() {
+    use nanoserde::DeJsonErrReason;
+
+    #[derive(DeJson, Debug)]
+    #[allow(dead_code)]
+    pub struct Test {
+        #[nserde(rename = "firstName")]
+        first_name: String,
+        age: u32,
+        #[nserde(skip)]
+        cache: u32,
+    }
+
+    let err = <Test as DeJson>::deserialize_json(r#"{"age": 3}"#).unwrap_err();
+    assert_eq!(
+        err.reason,
+        DeJsonErrReason::MissingKey {
+            key: "firstName".to_string(),
+            expected: &["firstName", "age"],
+        }
+    );
+    assert_eq!(
+        err.msg,
+        "Key not found firstName, expected one of: firstName, age"
+    );
+}
+
+#[test]
+fn field_alias() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Test {
+        a: i32,
+        #[nserde(alias = "beta")]
+        #[nserde(alias = "old_b")]
+        b: i32,
+        #[nserde(rename = "Gamma", alias = "gamma")]
+        c: i32,
+    }
+
+    let expected = Test { a: 1, b: 2, c: 3 };
+    for json in [
+        r#"{"a": 1, "b": 2, "Gamma": 3}"#,
+        r#"{"a": 1, "beta": 2, "gamma": 3}"#,
+        r#"{"a": 1, "old_b": 2, "Gamma": 3}"#,
+    ] {
+        let test: Test = DeJson::deserialize_json(json).unwrap();
+        assert_eq!(test, expected);
+    }
+
+    assert_eq!(expected.serialize_json(), r#"{"a":1,"b":2,"Gamma":3}"#);
+}
+
+#[test]
+fn json5_unquoted_keys_and_single_quotes() {
+    #[derive(DeJson, PartialEq, Debug)]
+    pub struct Test {
+        name: String,
+        #[nserde(rename = "item_2")]
+        items: Vec<String>,
+        quoted: Option<String>,
+    }
+
+    let json = r#"{
+        // unquoted keys
+        name: 'it\'s "single" quoted',
+        item_2: ['a', "b"],
+        /* regular keys still work */
+        "quoted": 'c',
+    }"#;
+
+    let test: Test = DeJson::deserialize_json5(json).unwrap();
+    assert_eq!(
+        test,
+        Test {
+            name: "it's \"single\" quoted".to_string(),
+            items: vec!["a".to_string(), "b".to_string()],
+            quoted: Some("c".to_string()),
+        }
+    );
+
+    // plain JSON keeps rejecting both extensions
+    assert!(<Test as DeJson>::deserialize_json(json).is_err());
+    assert!(<String as DeJson>::deserialize_json("'a'").is_err());
+}
+
+#[test]
+fn json5_unknown_keys_are_skipped() {
+    #[derive(DeJson, PartialEq, Debug)]
+    pub struct A {
+        x: i32,
+    }
+
+    assert_eq!(A::deserialize_json5("{x: 1, y: foo}").unwrap(), A { x: 1 });
+    assert!(A::deserialize_json("{\"x\": 1, \"y\": foo}").is_err());
+
+    // an unknown value cut off inside brackets is an error
+    assert!(A::deserialize_json(r#"{"x":1,"y":[1,"#).is_err());
+    assert!(A::deserialize_json5("{x: 1, y: {z: [1,").is_err());
+}
+
+#[test]
+fn flatten_struct() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Entity {
+        id: u32,
+        #[nserde(flatten)]
+        position: Point,
+    }
+
+    let entity = Entity {
+        id: 1,
+        position: Point { x: 2, y: 3 },
+    };
+    let json = entity.serialize_json();
+    assert_eq!(json, r#"{"id":1,"x":2,"y":3}"#);
+    let out: Entity = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, entity);
+
+    let out: Entity = DeJson::deserialize_json(r#"{"y":3,"id":1,"x":2}"#).unwrap();
+    assert_eq!(out, entity);
+
+    let err = <Entity as DeJson>::deserialize_json(r#"{"id":1,"x":2}"#).unwrap_err();
+    assert_eq!(err.msg, "Key not found y, expected one of: x, y");
+}
+
+#[test]
+fn flatten_internally_tagged_enum() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(tag = "type")]
+    pub enum Shape {
+        Circle {
+            radius: f32,
+        },
+        #[nserde(rename = "rect")]
+        Rectangle {
+            width: f32,
+            height: f32,
+            label: Option<String>,
+        },
+        Empty,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Node {
+        id: u32,
+        #[nserde(flatten)]
+        shape: Shape,
+        visible: bool,
+    }
+
+    let nodes = vec![
+        Node {
+            id: 1,
+            shape: Shape::Circle { radius: 2.5 },
+            visible: true,
+        },
+        Node {
+            id: 2,
+            shape: Shape::Rectangle {
+                width: 1.,
+                height: 2.,
+                label: Some("box".to_string()),
+            },
+            visible: false,
+        },
+        Node {
+            id: 3,
+            shape: Shape::Empty,
+            visible: true,
+        },
+    ];
+
+    let json = nodes.serialize_json();
+    assert_eq!(
+        json,
+        r#"[{"id":1,"type":"Circle","radius":2.5,"visible":true},{"id":2,"type":"rect","width":1.0,"height":2.0,"label":"box","visible":false},{"id":3,"type":"Empty","visible":true}]"#
+    );
+    let deserialized: Vec<Node> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(deserialized, nodes);
+
+    // the tag and the variant fields may appear anywhere between the parent fields
+    let node: Node = DeJson::deserialize_json(
+        r#"{"width": 3, "visible": true, "height": 4, "id": 7, "type": "rect", "extra": [1, {"a": null}]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        node,
+        Node {
+            id: 7,
+            shape: Shape::Rectangle {
+                width: 3.,
+                height: 4.,
+                label: None,
+            },
+            visible: true,
+        }
+    );
+
+    assert!(<Node as DeJson>::deserialize_json(r#"{"id": 1, "visible": true}"#).is_err());
+    assert!(
+        <Node as DeJson>::deserialize_json(r#"{"id": 1, "type": "Square", "visible": true}"#)
+            .is_err()
+    );
+    assert_eq!(
+        <Shape as DeJson>::deserialize_json(r#"{"radius": 1, "type": "Circle"}"#).unwrap(),
+        Shape::Circle { radius: 1. }
+    );
+}
+
+#[test]
+fn char_roundtrip() {
+    for (c, json) in [
+        ('a', r#""a""#),
+        ('\n', r#""\n""#),
+        ('"', r#""\"""#),
+        ('\'', r#""'""#),
+        ('\\', r#""\\""#),
+        ('\u{e9}', "\"\u{e9}\""),
+        ('\u{1f980}', "\"\u{1f980}\""),
+    ] {
+        assert_eq!(c.serialize_json(), json);
+        let out: char = DeJson::deserialize_json(json).unwrap();
+        assert_eq!(out, c);
+    }
+
+    let out: char = DeJson::deserialize_json(r#""🦀""#).unwrap();
+    assert_eq!(out, '\u{1f980}');
+
+    for json in [r#""""#, r#""ab""#, "1"] {
+        assert!(
+            <char as DeJson>::deserialize_json(json).is_err(),
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn unit_as_object() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(unit_as_object)]
+    enum Message {
+        Quit,
+        #[nserde(rename = "ping")]
+        Ping,
+        Move {
+            x: i32,
+            y: i32,
+        },
+        Write(String),
+    }
+
+    let values = vec![
+        Message::Quit,
+        Message::Ping,
+        Message::Move { x: 1, y: -2 },
+        Message::Write("hi".to_string()),
+    ];
+    let json = values.serialize_json();
+    assert_eq!(
+        json,
+        r#"[{"Quit":null},{"ping":null},{"Move":{"x":1,"y":-2}},{"Write":["hi"]}]"#
+    );
+    let out: Vec<Message> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, values);
+
+    // the plain string form is still accepted
+    let out: Message = DeJson::deserialize_json(r#""Quit""#).unwrap();
+    assert_eq!(out, Message::Quit);
+
+    assert!(<Message as DeJson>::deserialize_json(r#"{"Quit":1}"#).is_err());
+}
+
+#[test]
+fn duration_iso8601() {
+    use core::time::Duration;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Timeouts {
+        #[nserde(duration = "iso8601")]
+        connect: Duration,
+        #[nserde(duration = "iso8601")]
+        idle: Option<Duration>,
+    }
+
+    for (duration, json) in [
+        (Duration::ZERO, "PT0S"),
+        (Duration::from_secs(90), "PT90S"),
+        (Duration::from_millis(1500), "PT1.5S"),
+        (Duration::new(3, 1), "PT3.000000001S"),
+    ] {
+        let test = Timeouts {
+            connect: duration,
+            idle: Some(duration),
+        };
+        let serialized = test.serialize_json();
+        assert_eq!(
+            serialized,
+            format!(r#"{{"connect":"{json}","idle":"{json}"}}"#)
+        );
+        let out: Timeouts = DeJson::deserialize_json(&serialized).unwrap();
+        assert_eq!(out, test);
+    }
+
+    for (json, duration) in [
+        ("P1D", Duration::from_secs(86400)),
+        ("P1W", Duration::from_secs(7 * 86400)),
+        ("PT1H2M3S", Duration::from_secs(3723)),
+        ("P1DT0.25S", Duration::new(86400, 250_000_000)),
+        ("PT0,5S", Duration::from_millis(500)),
+        ("PT1.1234567891S", Duration::new(1, 123_456_789)),
+    ] {
+        let out: Timeouts =
+            DeJson::deserialize_json(&format!(r#"{{"connect":"{json}"}}"#)).unwrap();
+        assert_eq!(out.connect, duration, "{}", json);
+        assert_eq!(out.idle, None);
+    }
+
+    for json in [
+        "", "P", "PT", "1S", "PT1", "PT.5S", "PT1.5M", "PT1S1M", "P1Y", "P1M", "PT-1S",
+    ] {
+        let json = format!(r#"{{"connect":"{json}"}}"#);
+        assert!(
+            <Timeouts as DeJson>::deserialize_json(&json).is_err(),
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn tuple_7() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Test {
+        t: (u8, i32, String, bool, f64, Vec<u16>, Option<char>),
+    }
+
+    let test = Test {
+        t: (1, -2, "three".to_string(), true, 5.5, vec![6, 6], Some('7')),
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"t":[1,-2,"three",true,5.5,[6,6],"7"]}"#);
+    let out: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn skip_serializing_if() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Test {
+        name: String,
+        #[nserde(skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[nserde(skip_serializing_if = "is_zero")]
+        count: u32,
+    }
+
+    fn is_zero(value: &u32) -> bool {
+        *value == 0
+    }
+
+    let test = Test {
+        name: "a".to_string(),
+        tags: vec![],
+        count: 0,
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"name":"a"}"#);
+    let out: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    let test = Test {
+        name: "b".to_string(),
+        tags: vec!["x".to_string()],
+        count: 2,
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"name":"b","tags":["x"],"count":2}"#);
+    let out: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn btreemap_char_keys() {
+    let mut map = BTreeMap::new();
+    map.insert('b', 2);
+    map.insert('\u{1f980}', 4);
+    map.insert('a', 1);
+    map.insert('"', 3);
+
+    let json = map.serialize_json();
+    assert_eq!(json, "{\"\\\"\":3,\"a\":1,\"b\":2,\"\u{1f980}\":4}");
+    let out: BTreeMap<char, i32> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, map);
+
+    let out: BTreeMap<char, i32> = DeJson::deserialize_json(r#"{"z":26,"a":1}"#).unwrap();
+    assert_eq!(out.keys().collect::<Vec<_>>(), [&'a', &'z']);
+
+    assert!(<BTreeMap<char, i32> as DeJson>::deserialize_json(r#"{"ab":1}"#).is_err());
+}
+
+#[test]
+fn infallible() {
+    use core::convert::Infallible;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Test {
+        result: Result<i32, Infallible>,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    enum Event {
+        Value(i32),
+        Never(Infallible),
+    }
+
+    let test = Test { result: Ok(5) };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"result":{"Ok":5}}"#);
+    let out: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+    assert!(<Test as DeJson>::deserialize_json(r#"{"result":{"Err":null}}"#).is_err());
+
+    let json = Event::Value(1).serialize_json();
+    let out: Event = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, Event::Value(1));
+    assert!(<Event as DeJson>::deserialize_json(r#"{"Never":[1]}"#).is_err());
+}
+
+#[test]
+fn deny_unknown_fields() {
+    use nanoserde::DeJsonErrReason;
+
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(deny_unknown_fields)]
+    pub struct Strict {
+        a: i32,
+        #[nserde(rename = "bee")]
+        b: Option<i32>,
+    }
+
+    #[derive(DeJson, PartialEq, Debug)]
+    pub struct Lenient {
+        a: i32,
+    }
+
+    let test: Strict = DeJson::deserialize_json(r#"{"bee": 2, "a": 1}"#).unwrap();
+    assert_eq!(test, Strict { a: 1, b: Some(2) });
+
+    let err = <Strict as DeJson>::deserialize_json(r#"{"a": 1, "b": 2}"#).unwrap_err();
+    assert_eq!(
+        err.reason,
+        DeJsonErrReason::UnexpectedKey {
+            key: "b".to_string(),
+            expected: &["a", "bee"],
+        }
+    );
+    assert_eq!(err.msg, "Unexpected key b, expected one of: a, bee");
+
+    let test: Lenient = DeJson::deserialize_json(r#"{"a": 1, "b": 2}"#).unwrap();
+    assert_eq!(test, Lenient { a: 1 });
+}
+
+#[test]
+fn deny_unknown_fields_enum_variants() {
+    use nanoserde::DeJsonErrReason;
+
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(deny_unknown_fields)]
+    pub enum Strict {
+        Move { x: i32 },
+    }
+
+    #[derive(DeJson, PartialEq, Debug)]
+    pub enum PerVariant {
+        #[nserde(deny_unknown_fields)]
+        Strict {
+            x: i32,
+        },
+        Lenient {
+            x: i32,
+        },
+    }
+
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(tag = "type", deny_unknown_fields)]
+    pub enum Tagged {
+        Move { x: i32 },
+    }
+
+    let test: Strict = DeJson::deserialize_json(r#"{"Move": {"x": 1}}"#).unwrap();
+    assert_eq!(test, Strict::Move { x: 1 });
+    let err = <Strict as DeJson>::deserialize_json(r#"{"Move": {"x": 1, "y": 2}}"#).unwrap_err();
+    assert_eq!(
+        err.reason,
+        DeJsonErrReason::UnexpectedKey {
+            key: "y".to_string(),
+            expected: &["x"],
+        }
+    );
+
+    assert!(<PerVariant as DeJson>::deserialize_json(r#"{"Strict": {"x": 1, "y": 2}}"#).is_err());
+    let test: PerVariant = DeJson::deserialize_json(r#"{"Lenient": {"x": 1, "y": 2}}"#).unwrap();
+    assert_eq!(test, PerVariant::Lenient { x: 1 });
+
+    let test: Tagged = DeJson::deserialize_json(r#"{"type": "Move", "x": 1}"#).unwrap();
+    assert_eq!(test, Tagged::Move { x: 1 });
+    assert!(<Tagged as DeJson>::deserialize_json(r#"{"type": "Move", "x": 1, "y": 2}"#).is_err());
+}
+
+#[derive(DeJson, SerJson, PartialEq, Debug)]
+#[nserde(with = "hex_color")]
+pub struct Color {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+mod hex_color {
+    use super::Color;
+    use nanoserde::{DeJson, DeJsonErr, DeJsonState, SerJson, SerJsonState};
+
+    pub fn ser_json(color: &Color, d: usize, s: &mut SerJsonState) {
+        format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b).ser_json(d, s);
+    }
+
+    pub fn de_json(s: &mut DeJsonState, i: &mut core::str::Chars) -> Result<Color, DeJsonErr> {
+        let hex = String::de_json(s, i)?;
+        let channel = |range| {
+            hex.get(range)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+                .ok_or_else(|| s.err_parse("color"))
+        };
+        if hex.len() != 7 || !hex.starts_with('#') {
+            return Err(s.err_parse("color"));
+        }
+        Ok(Color {
+            r: channel(1..3)?,
+            g: channel(3..5)?,
+            b: channel(5..7)?,
+        })
+    }
+}
+
+#[test]
+fn container_with() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    pub struct Theme {
+        background: Color,
+        accents: Vec<Color>,
+    }
+
+    let theme = Theme {
+        background: Color {
+            r: 255,
+            g: 128,
+            b: 0,
+        },
+        accents: vec![Color { r: 0, g: 1, b: 2 }],
+    };
+    let json = theme.serialize_json();
+    assert_eq!(json, r##"{"background":"#ff8000","accents":["#000102"]}"##);
+    let out: Theme = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, theme);
+
+    assert!(<Color as DeJson>::deserialize_json(r#""ff8000""#).is_err());
+}
+
+#[test]
+fn internally_tagged_enum() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(tag = "kind")]
+    pub enum Event {
+        Started,
+        #[nserde(rename = "stopped")]
+        Stopped,
+        Moved {
+            x: i32,
+            y: i32,
+        },
+        Renamed {
+            #[nserde(rename = "new_name")]
+            name: String,
+            old: Option<String>,
+        },
+    }
+
+    let events = vec![
+        Event::Started,
+        Event::Stopped,
+        Event::Moved { x: 5, y: -1 },
+        Event::Renamed {
+            name: "b".to_string(),
+            old: None,
+        },
+    ];
+    let json = events.serialize_json();
+    assert_eq!(
+        json,
+        r#"[{"kind":"Started"},{"kind":"stopped"},{"kind":"Moved","x":5,"y":-1},{"kind":"Renamed","new_name":"b"}]"#
+    );
+    let out: Vec<Event> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, events);
+
+    // the tag doesn't have to come first
+    let out: Event = DeJson::deserialize_json(r#"{"y": 2, "x": 1, "kind": "Moved"}"#).unwrap();
+    assert_eq!(out, Event::Moved { x: 1, y: 2 });
+
+    assert!(<Event as DeJson>::deserialize_json(r#"{"x": 1, "y": 2}"#).is_err());
+    assert!(<Event as DeJson>::deserialize_json(r#"{"kind": "Jumped"}"#).is_err());
+    assert!(<Event as DeJson>::deserialize_json(r#""Started""#).is_err());
+}
+
+#[test]
+fn byte_order_mark() {
+    let out: Vec<u32> = DeJson::deserialize_json("\u{feff}[1, 2]").unwrap();
+    assert_eq!(out, [1, 2]);
+    let out: Vec<u32> = DeJson::deserialize_json_bytes(b"\xEF\xBB\xBF[1, 2]").unwrap();
+    assert_eq!(out, [1, 2]);
+    let out: Vec<u32> = DeJson::deserialize_json_bytes(b"[3]").unwrap();
+    assert_eq!(out, [3]);
+
+    // "[1]" saved as UTF-16, little and big endian
+    for bytes in [
+        &b"\xFF\xFE[\x001\x00]\x00"[..],
+        &b"\xFE\xFF\x00[\x001\x00]"[..],
+    ] {
+        let err = <Vec<u32> as DeJson>::deserialize_json_bytes(bytes).unwrap_err();
+        assert!(err.msg.contains("UTF-16, expected UTF-8"), "{}", err);
+    }
+}
+
+#[test]
+fn invalid_utf8_bytes() {
+    #[derive(DeJson, PartialEq, Debug)]
+    struct Greeting {
+        text: String,
+    }
+
+    let out: Greeting =
+        DeJson::deserialize_json_bytes("{\"text\": \"h\u{e9}llo\"}".as_bytes()).unwrap();
+    assert_eq!(out.text, "h\u{e9}llo");
+
+    let bytes = b"{\n\"text\": \"h\xC3\xA9\xFFllo\"}";
+    let err = <Greeting as DeJson>::deserialize_json_bytes(bytes).unwrap_err();
+    assert_eq!(
+        err.reason,
+        nanoserde::DeJsonErrReason::CannotParse(
+            "input, it is not valid UTF-8 at byte 14".to_string()
+        )
+    );
+    assert_eq!(bytes[14], 0xFF);
+    assert_eq!((err.line, err.col, err.offset), (1, 11, 13));
+
+    // truncated multi byte sequence at the end
+    let err = <Greeting as DeJson>::deserialize_json_bytes(b"\"\xE2\x82").unwrap_err();
+    assert!(err.to_string().contains("at byte 1"), "{}", err);
+}
+
+#[test]
+fn untagged_enum() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(untagged)]
+    pub enum Value {
+        A(i32),
+        B(String),
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(untagged)]
+    pub enum Shape {
+        Nothing,
+        Circle {
+            radius: f32,
+        },
+        Rect {
+            w: f32,
+            h: f32,
+            label: Option<String>,
+        },
+        Point(f32, f32),
+    }
+
+    let values = vec![Value::A(5), Value::B("hi".to_string())];
+    let json = values.serialize_json();
+    assert_eq!(json, r#"[5,"hi"]"#);
+    let out: Vec<Value> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, values);
+    assert!(<Value as DeJson>::deserialize_json("true").is_err());
+
+    let shapes = vec![
+        Shape::Nothing,
+        Shape::Circle { radius: 1.5 },
+        Shape::Rect {
+            w: 1.,
+            h: 2.,
+            label: None,
+        },
+        Shape::Point(3., 4.),
+    ];
+    let json = shapes.serialize_json();
+    assert_eq!(json, r#"[null,{"radius":1.5},{"w":1.0,"h":2.0},[3.0,4.0]]"#);
+    let out: Vec<Shape> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, shapes);
+
+    // the first variant that fits wins, Circle ignores the unknown keys
+    let out: Shape = DeJson::deserialize_json(r#"{"w": 1, "h": 2, "radius": 3}"#).unwrap();
+    assert_eq!(out, Shape::Circle { radius: 3. });
+}
+
+#[test]
+fn untagged_enum_keeps_state_settings() {
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(untagged)]
+    pub enum V {
+        L(Vec<V>),
+        N(i32),
+        P { x: i32 },
+    }
+
+    let json = "[".repeat(100) + &"]".repeat(100);
+    assert!(V::deserialize_json(&json).is_ok());
+
+    for depth in [200, 100_000] {
+        let json = "[".repeat(depth) + &"]".repeat(depth);
+        let e = V::deserialize_json(&json).unwrap_err();
+        assert_eq!(e.reason, nanoserde::DeJsonErrReason::TooDeep);
+    }
+
+    let mut state = nanoserde::DeJsonState::default();
+    state.max_depth = 3;
+    let mut chars = "[[[[1]]]]".chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars).unwrap();
+    assert!(V::de_json(&mut state, &mut chars).is_err());
+
+    // JSON5 applies to the captured value too
+    let out = V::deserialize_json5("[{x: +1}, 2]").unwrap();
+    assert_eq!(out, V::L(vec![V::P { x: 1 }, V::N(2)]));
+    assert!(V::deserialize_json("[{x: 1}]").is_err());
+}
+
+#[test]
+fn captured_fields_keep_state_settings() {
+    #[derive(DeJson, PartialEq, Debug)]
+    pub struct Inner {
+        xs: Vec<i32>,
+    }
+
+    #[derive(DeJson, PartialEq, Debug)]
+    pub struct Outer {
+        id: u32,
+        xs: Vec<i32>,
+    }
+
+    #[derive(DeJson, PartialEq, Debug)]
+    pub struct Flat {
+        id: u32,
+        #[nserde(flatten)]
+        inner: Inner,
+    }
+
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(tag = "type")]
+    pub enum Tagged {
+        A { id: u32, xs: Vec<i32> },
+    }
+
+    trait Shape: nanoserde::JsonTagged {
+        fn len(&self) -> usize;
+    }
+
+    #[derive(DeJson, SerJson)]
+    struct Listed {
+        id: u32,
+        xs: Vec<i32>,
+    }
+
+    impl Shape for Listed {
+        fn len(&self) -> usize {
+            self.id as usize + self.xs.len()
+        }
+    }
+
+    nanoserde::json_tagged_trait!(Shape, "type", { "A" => Listed });
+
+    #[derive(DeJson, SerJson)]
+    struct Drawing {
+        #[nserde(tagged_trait)]
+        shape: Box<dyn Shape>,
+    }
+
+    // errors inside the captured fields point into the input
+    let json = "{\n  \"type\": \"A\",\n  \"id\": 1,\n  \"xs\": [1,\n    true]\n}";
+    let expected = Outer::deserialize_json(json).unwrap_err();
+    assert_eq!(expected.line, 4);
+    for err in [
+        Flat::deserialize_json(json).unwrap_err(),
+        Tagged::deserialize_json(json).unwrap_err(),
+        Drawing::deserialize_json(&format!("{{\"shape\": {}}}", json))
+            .err()
+            .unwrap(),
+    ] {
+        assert_eq!(err.msg, expected.msg);
+        assert_eq!((err.line, err.col), (expected.line, expected.col));
+    }
+
+    // JSON5 and the depth limit apply to the captured fields too
+    let json5 = "{type: 'A', id: 1, xs: [+1, 2,],}";
+    assert_eq!(
+        Flat::deserialize_json5(json5).unwrap(),
+        Flat {
+            id: 1,
+            inner: Inner { xs: vec![1, 2] }
+        }
+    );
+    assert_eq!(
+        Tagged::deserialize_json5(json5).unwrap(),
+        Tagged::A {
+            id: 1,
+            xs: vec![1, 2]
+        }
+    );
+    let drawing = Drawing::deserialize_json5(&format!("{{shape: {}}}", json5)).unwrap();
+    assert_eq!(drawing.shape.len(), 3);
+
+    let mut state = nanoserde::DeJsonState::default();
+    state.max_depth = 2;
+    let mut chars = r#"{"shape": {"type": "A", "id": 1, "xs": [1]}}"#.chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars).unwrap();
+    let err = Drawing::de_json(&mut state, &mut chars).err().unwrap();
+    assert_eq!(err.reason, nanoserde::DeJsonErrReason::TooDeep);
+}
+
+#[test]
+fn rc_and_arc() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Test {
+        a: Rc<String>,
+        b: Arc<Vec<u32>>,
+        c: Option<Rc<i32>>,
+    }
+
+    let test = Test {
+        a: Rc::new("shared".to_string()),
+        b: Arc::new(vec![1, 2]),
+        c: Some(Rc::new(3)),
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"a":"shared","b":[1,2],"c":3}"#);
+    let out: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    let rc = Rc::new("hi".to_string());
+    let out: Rc<String> = DeJson::deserialize_json(&rc.serialize_json()).unwrap();
+    assert_eq!(out, rc);
+}
+
+#[test]
+fn top_level_slice() {
+    let values: Vec<u32> = (1..=3).collect();
+    let slice: &[u32] = &values;
+    assert_eq!(slice.serialize_json(), "[1,2,3]");
+    assert_eq!(values[1..].serialize_json(), "[2,3]");
+
+    let empty: &[u32] = &[];
+    assert_eq!(empty.serialize_json(), "[]");
+
+    let names = ["a".to_string(), "b".to_string()];
+    let json = names[..].serialize_json();
+    assert_eq!(json, r#"["a","b"]"#);
+    let out: Vec<String> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, names);
+}
+
+#[test]
+fn de_field_default_const() {
+    #[derive(DeJson, PartialEq, Debug)]
+    struct Limits {
+        #[nserde(default = const { Limits::MAX_RETRIES })]
+        retries: u32,
+        #[nserde(default = const { u16::MAX })]
+        port: u16,
+        #[nserde(default = const { Some(Limits::MAX_RETRIES * 2) })]
+        backoff: Option<u32>,
+        #[nserde(default = const { [1, 2, 3] })]
+        weights: [u8; 3],
+    }
+    impl Limits {
+        const MAX_RETRIES: u32 = 3;
+    }
+
+    let test: Limits = DeJson::deserialize_json("{}").unwrap();
+    assert_eq!(
+        test,
+        Limits {
+            retries: 3,
+            port: u16::MAX,
+            backoff: Some(6),
+            weights: [1, 2, 3],
+        }
+    );
+
+    let test: Limits = DeJson::deserialize_json(r#"{"retries":1,"backoff":null}"#).unwrap();
+    assert_eq!(test.retries, 1);
+    assert_eq!(test.backoff, None);
+}
+
+#[test]
+fn cow() {
+    use std::borrow::Cow;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Test {
+        name: Cow<'static, str>,
+        values: Cow<'static, [u32]>,
+    }
+
+    let test = Test {
+        name: Cow::Borrowed("borrowed"),
+        values: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"name":"borrowed","values":[1,2,3]}"#);
+    let out: Test = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+    assert!(matches!(out.name, Cow::Owned(_)));
+
+    let name: Cow<str> = Cow::Owned("owned".to_string());
+    let out: Cow<str> = DeJson::deserialize_json(&name.serialize_json()).unwrap();
+    assert_eq!(out, name);
+}
+
+#[test]
+fn nonzero() {
+    use std::num::{NonZeroI64, NonZeroU32, NonZeroU8};
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Handle {
+        id: NonZeroU32,
+        generation: Option<NonZeroU8>,
+        offset: NonZeroI64,
+    }
+
+    let test = Handle {
+        id: NonZeroU32::new(7).unwrap(),
+        generation: NonZeroU8::new(1),
+        offset: NonZeroI64::new(-3).unwrap(),
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"id":7,"generation":1,"offset":-3}"#);
+    let out: Handle = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    let err = <NonZeroU8 as DeJson>::deserialize_json("0").unwrap_err();
+    assert!(matches!(
+        err.reason,
+        nanoserde::DeJsonErrReason::CannotParse(_)
+    ));
+    assert!(<NonZeroU8 as DeJson>::deserialize_json("256").is_err());
+}
+
+#[test]
+fn array_tagged_enum() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(array_tagged)]
+    pub enum Message {
+        Ping,
+        Text(String),
+        #[nserde(rename = "move")]
+        Move(i32, i32),
+        Resize {
+            w: u32,
+            h: u32,
+        },
+    }
+
+    let messages = vec![
+        Message::Ping,
+        Message::Text("hi".to_string()),
+        Message::Move(-1, 2),
+        Message::Resize { w: 3, h: 4 },
+    ];
+    let json = messages.serialize_json();
+    assert_eq!(
+        json,
+        r#"[["Ping",null],["Text","hi"],["move",[-1,2]],["Resize",{"w":3,"h":4}]]"#
+    );
+    let out: Vec<Message> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, messages);
+
+    let out: Message = DeJson::deserialize_json(r#"[ "Text" , "Ping" ]"#).unwrap();
+    assert_eq!(out, Message::Text("Ping".to_string()));
+
+    assert!(<Message as DeJson>::deserialize_json(r#"["Jump",null]"#).is_err());
+    assert!(<Message as DeJson>::deserialize_json(r#"["Ping"]"#).is_err());
+    assert!(<Message as DeJson>::deserialize_json(r#"["Text","a","b"]"#).is_err());
+}
+
+#[test]
+fn tuple_variant_as_array() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(tuple_variant_as_array)]
+    pub enum Foo {
+        A,
+        B { x: i32 },
+        C(i32, String),
+        D(String),
+    }
+
+    let values = vec![
+        Foo::A,
+        Foo::B { x: 5 },
+        Foo::C(6, "HELLO".to_string()),
+        Foo::D("D".to_string()),
+    ];
+    let json = values.serialize_json();
+    assert_eq!(json, r#"["A",{"B":{"x":5}},["C",6,"HELLO"],["D","D"]]"#);
+    let out: Vec<Foo> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, values);
+
+    assert!(<Foo as DeJson>::deserialize_json(r#"["E",1]"#).is_err());
+    assert!(<Foo as DeJson>::deserialize_json(r#"["C",6]"#).is_err());
+    assert!(<Foo as DeJson>::deserialize_json(r#"{"C":[6,"HELLO"]}"#).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn net_addrs() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Peer {
+        v4: Ipv4Addr,
+        v6: Ipv6Addr,
+        ip: IpAddr,
+        addr: SocketAddr,
+        fallback: Option<SocketAddr>,
+    }
+
+    let test = Peer {
+        v4: Ipv4Addr::new(192, 168, 0, 1),
+        v6: Ipv6Addr::LOCALHOST,
+        ip: IpAddr::V6("fe80::1".parse().unwrap()),
+        addr: "[2001:db8::1]:8080".parse().unwrap(),
+        fallback: Some("10.0.0.1:53".parse().unwrap()),
+    };
+    let json = test.serialize_json();
+    assert_eq!(
+        json,
+        r#"{"v4":"192.168.0.1","v6":"::1","ip":"fe80::1","addr":"[2001:db8::1]:8080","fallback":"10.0.0.1:53"}"#
+    );
+    let out: Peer = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    assert!(<Ipv4Addr as DeJson>::deserialize_json(r#""256.0.0.1""#).is_err());
+    assert!(<SocketAddr as DeJson>::deserialize_json(r#""::1:80""#).is_err());
+
+    // the scope id is part of the text form, the flow info isn't
+    let v6 = std::net::SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 7, 3);
+    assert_eq!(v6.serialize_json(), r#""[::1%3]:80""#);
+    let out: std::net::SocketAddrV6 = DeJson::deserialize_json(&v6.serialize_json()).unwrap();
+    assert_eq!(
+        out,
+        std::net::SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 0, 3)
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn serialize_canonical() {
+    use std::collections::HashSet;
+
+    #[derive(SerJson)]
+    struct Test {
+        map: HashMap<String, HashMap<i32, u8>>,
+        set: HashSet<String>,
+        nested: Vec<HashSet<u32>>,
+    }
+
+    let build = |keys: &[&str]| Test {
+        map: keys
+            .iter()
+            .map(|key| {
+                let inner = (0..20).map(|i| (i, i as u8)).collect();
+                (key.to_string(), inner)
+            })
+            .collect(),
+        set: keys.iter().map(|key| key.to_string()).collect(),
+        nested: vec![(0..20).rev().collect()],
+    };
+
+    let a = build(&["x", "b", "a", "long-key", "z"]);
+    let b = build(&["z", "long-key", "a", "b", "x"]);
+    assert_eq!(a.serialize_json_canonical(), b.serialize_json_canonical());
+
+    let json = a.serialize_json_canonical();
+    assert!(
+        json.contains(r#""set":["a","b","long-key","x","z"]"#),
+        "{}",
+        json
+    );
+    // sorted by the serialized keys, not by their value
+    assert!(
+        json.contains(r#""a":{"0":0,"1":1,"10":10,"11":11"#),
+        "{}",
+        json
+    );
+    assert!(json.contains(r#""nested":[[0,1,10,11"#), "{}", json);
+
+    // the regular output has the same content
+    let map: HashMap<String, i32> = [("b".to_string(), 2), ("a".to_string(), 1)].into();
+    let out: HashMap<String, i32> = DeJson::deserialize_json(&map.serialize_json()).unwrap();
+    assert_eq!(out, map);
+    assert_eq!(map.serialize_json_canonical(), r#"{"a":1,"b":2}"#);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn sort_keys() {
+    use std::collections::HashMap;
+
+    #[derive(SerJson)]
+    struct Scores {
+        #[nserde(sort_keys)]
+        scores: HashMap<String, u32>,
+    }
+
+    #[derive(SerJson)]
+    #[nserde(sort_keys)]
+    struct Report {
+        scores: HashMap<String, u32>,
+        totals: HashMap<String, u32>,
+    }
+
+    let names = ["x", "b", "a", "long-key", "z"];
+    let forward: HashMap<String, u32> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), i as u32))
+        .collect();
+    let backward: HashMap<String, u32> = names
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, name)| (name.to_string(), i as u32))
+        .collect();
+
+    let a = Scores { scores: forward };
+    let b = Scores { scores: backward };
+    assert_eq!(a.serialize_json(), b.serialize_json());
+    assert_eq!(
+        a.serialize_json(),
+        r#"{"scores":{"a":2,"b":1,"long-key":3,"x":0,"z":4}}"#
+    );
+
+    let a = Report {
+        scores: a.scores,
+        totals: (0..3).map(|i| (format!("k{}", i), i)).collect(),
+    };
+    let b = Report {
+        scores: b.scores,
+        totals: (0..3).rev().map(|i| (format!("k{}", i), i)).collect(),
+    };
+    assert_eq!(a.serialize_json(), b.serialize_json());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn path_buf() {
+    use std::path::{Path, PathBuf};
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Config {
+        root: PathBuf,
+        cache: Option<PathBuf>,
+    }
+
+    let test = Config {
+        root: PathBuf::from("data/caf\u{e9}/\u{1f980}.txt"),
+        cache: None,
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, "{\"root\":\"data/caf\u{e9}/\u{1f980}.txt\"}");
+    let out: Config = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    let path: &Path = Path::new("a/b");
+    assert_eq!(path.serialize_json(), r#""a/b""#);
+}
+
+#[test]
+fn ranges() {
+    use core::ops::{Range, RangeInclusive};
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Selection {
+        lines: Range<usize>,
+        columns: RangeInclusive<i32>,
+        spans: Vec<Range<u8>>,
+    }
+
+    let test = Selection {
+        lines: 3..7,
+        columns: -3..=7,
+        spans: vec![0..1, 5..5],
+    };
+    let json = test.serialize_json();
+    assert_eq!(
+        json,
+        r#"{"lines":{"start":3,"end":7},"columns":{"start":-3,"end":7},"spans":[{"start":0,"end":1},{"start":5,"end":5}]}"#
+    );
+    let out: Selection = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    let range: RangeInclusive<i32> = DeJson::deserialize_json(r#"{"end":7,"start":3}"#).unwrap();
+    assert_eq!(range, 3..=7);
+    assert!(<Range<i32> as DeJson>::deserialize_json(r#"{"start":3}"#).is_err());
+
+    // unquoted keys in JSON5, like the fields of derived structs
+    let range: Range<i32> = DeJson::deserialize_json5("{start: 3, end: 7}").unwrap();
+    assert_eq!(range, 3..7);
+    let duration: std::time::Duration = DeJson::deserialize_json5("{secs: 1, nanos: 5}").unwrap();
+    assert_eq!(duration, std::time::Duration::new(1, 5));
+}
+
+#[test]
+fn nesting_too_deep() {
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Node(Vec<Node>);
+
+    let json = "[".repeat(10000) + &"]".repeat(10000);
+    let e = <Node as DeJson>::deserialize_json(&json).err().unwrap();
+    assert_eq!(e.reason, nanoserde::DeJsonErrReason::TooDeep);
+
+    let json = "[".repeat(100) + &"]".repeat(100);
+    assert!(<Node as DeJson>::deserialize_json(&json).is_ok());
+
+    let mut state = nanoserde::DeJsonState::default();
+    state.max_depth = 3;
+    let mut chars = "[[[]]]".chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars).unwrap();
+    assert!(Node::de_json(&mut state, &mut chars).is_ok());
+
+    let mut state = nanoserde::DeJsonState::default();
+    state.max_depth = 3;
+    let mut chars = "[[[[]]]]".chars();
+    state.next(&mut chars);
+    state.next_tok(&mut chars).unwrap();
+    assert!(Node::de_json(&mut state, &mut chars).is_err());
+}
+
+#[test]
+fn vec_deque_and_binary_heap() {
+    use std::collections::{BinaryHeap, VecDeque};
+
+    #[derive(DeJson, SerJson)]
+    struct Queues {
+        queue: VecDeque<u32>,
+        heap: BinaryHeap<i32>,
+    }
+
+    let mut queue: VecDeque<u32> = (3..6).collect();
+    queue.push_front(2);
+    queue.push_front(1);
+    let test = Queues {
+        queue,
+        heap: [5, -1, 9, 5, 0].into_iter().collect(),
+    };
+
+    let bytes = test.serialize_json();
+    let out: Queues = DeJson::deserialize_json(&bytes).unwrap();
+    assert_eq!(out.queue, [1, 2, 3, 4, 5]);
+    // a heap doesn't keep the insertion order, only the same items
+    assert_eq!(out.heap.into_sorted_vec(), [-1, 0, 5, 5, 9]);
+
+    let heap: BinaryHeap<u8> = DeJson::deserialize_json(&[3u8, 1, 2].serialize_json()).unwrap();
+    assert_eq!(heap.peek(), Some(&3));
+}
+
+#[test]
+fn relative_instant() {
+    use core::time::Duration;
+    use nanoserde::RelativeInstant;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Event {
+        name: String,
+        at: RelativeInstant,
+    }
+
+    let test = Event {
+        name: "loaded".to_string(),
+        at: RelativeInstant(Duration::new(3, 250_000_000)),
+    };
+    let out: Event = DeJson::deserialize_json(&test.serialize_json()).unwrap();
+    assert_eq!(out, test);
+    assert_eq!(
+        RelativeInstant(Duration::from_millis(1500)).serialize_json(),
+        Duration::from_millis(1500).serialize_json()
+    );
+}
+
+#[test]
+fn non_finite_floats() {
+    #[derive(DeJson, SerJson, Debug)]
+    struct Sample {
+        #[nserde(float_as_string)]
+        a: f64,
+        #[nserde(float_as_string)]
+        b: Option<f32>,
+        c: f64,
+    }
+
+    let test = Sample {
+        a: f64::NAN,
+        b: Some(f32::NEG_INFINITY),
+        c: 1.5,
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"a":"NaN","b":"-inf","c":1.5}"#);
+    let out: Sample = DeJson::deserialize_json(&json).unwrap();
+    assert!(out.a.is_nan());
+    assert_eq!(out.b, Some(f32::NEG_INFINITY));
+    assert_eq!(out.c, 1.5);
+
+    let test = Sample {
+        a: f64::INFINITY,
+        b: None,
+        c: f64::INFINITY,
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"a":"inf","c":null}"#);
+    let out: Sample = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out.a, f64::INFINITY);
+    assert_eq!(out.b, None);
+    // without the attribute non-finite values are written as null and come back as NaN
+    assert!(out.c.is_nan());
+
+    assert_eq!(f64::NAN.serialize_json(), "null");
+    assert!(f64::deserialize_json("null").unwrap().is_nan());
+}
+
+#[test]
+fn tuple_defaults() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Point {
+        #[nserde(tuple_defaults)]
+        xy: (i32, i32),
+        #[nserde(tuple_defaults)]
+        rest: (String, Vec<u8>, bool),
+        strict: (i32, i32),
+    }
+
+    let out: Point =
+        DeJson::deserialize_json(r#"{"xy": [1], "rest": [], "strict": [3, 4]}"#).unwrap();
+    assert_eq!(
+        out,
+        Point {
+            xy: (1, 0),
+            rest: (String::new(), vec![], false),
+            strict: (3, 4),
+        }
+    );
+
+    let out: Point =
+        DeJson::deserialize_json(r#"{"xy": [1, 2], "rest": ["a", [5]], "strict": [3, 4]}"#)
+            .unwrap();
+    assert_eq!(out.xy, (1, 2));
+    assert_eq!(out.rest, ("a".to_string(), vec![5], false));
+
+    // only the fields with the attribute accept a short array
+    assert!(Point::deserialize_json(r#"{"xy": [1], "rest": [], "strict": [3]}"#).is_err());
+    assert!(Point::deserialize_json(r#"{"xy": [1, 2, 3], "rest": [], "strict": [3, 4]}"#).is_err());
+}
+
+#[test]
+fn wrapping() {
+    use core::num::Wrapping;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Checksum {
+        sum: Wrapping<u32>,
+        parts: Vec<Wrapping<u32>>,
+    }
+
+    let test = Checksum {
+        sum: Wrapping(u32::MAX) + Wrapping(2),
+        parts: vec![Wrapping(u32::MAX), Wrapping(2)],
+    };
+    let json = test.serialize_json();
+    let out: Checksum = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+    assert_eq!(out.sum, Wrapping(1));
+    assert_eq!(Wrapping(7u32).serialize_json(), 7u32.serialize_json());
+}
+
+#[test]
+fn ser_reference() {
+    fn to_json<T: SerJson>(value: T) -> String {
+        value.serialize_json()
+    }
+
+    #[derive(SerJson)]
+    struct Borrowed<'a> {
+        items: &'a Vec<i32>,
+        pairs: Vec<&'a (i32, &'a str)>,
+    }
+
+    let items = vec![1, 2, 3];
+    assert_eq!(to_json(&items), "[1,2,3]");
+    assert_eq!(to_json(&items[1..]), "[2,3]");
+
+    let pair = (4, "four");
+    let test = Borrowed {
+        items: &items,
+        pairs: vec![&pair],
+    };
+    assert_eq!(
+        test.serialize_json(),
+        r#"{"items":[1,2,3],"pairs":[[4,"four"]]}"#
+    );
+}
+
+#[test]
+fn skip_serializing_if_and_none_as_null() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Fields {
+        plain: Option<i32>,
+        #[nserde(serialize_none_as_null)]
+        null: Option<i32>,
+        #[nserde(skip_serializing_if = "Option::is_none")]
+        skip: Option<i32>,
+        #[nserde(serialize_none_as_null, skip_serializing_if = "Option::is_none")]
+        both: Option<i32>,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(serialize_none_as_null)]
+    struct Container {
+        null: Option<i32>,
+        #[nserde(skip_serializing_if = "Option::is_none")]
+        skip: Option<i32>,
+    }
+
+    let none = Fields {
+        plain: None,
+        null: None,
+        skip: None,
+        both: None,
+    };
+    let json = none.serialize_json();
+    assert_eq!(json, r#"{"null":null}"#);
+    assert_eq!(Fields::deserialize_json(&json).unwrap(), none);
+
+    let some = Fields {
+        plain: Some(1),
+        null: Some(2),
+        skip: Some(3),
+        both: Some(4),
+    };
+    let json = some.serialize_json();
+    assert_eq!(json, r#"{"plain":1,"null":2,"skip":3,"both":4}"#);
+    assert_eq!(Fields::deserialize_json(&json).unwrap(), some);
+
+    let none = Container {
+        null: None,
+        skip: None,
+    };
+    let json = none.serialize_json();
+    assert_eq!(json, r#"{"null":null}"#);
+    assert_eq!(Container::deserialize_json(&json).unwrap(), none);
+
+    let some = Container {
+        null: Some(1),
+        skip: Some(2),
+    };
+    assert_eq!(some.serialize_json(), r#"{"null":1,"skip":2}"#);
+}
+
+#[test]
+fn container_default_with_field_default() {
+    use std::num::NonZeroU32;
+
+    // NonZeroU32 has no Default, the field needs its own for the container default
+    #[derive(DeJson, PartialEq, Debug)]
+    #[nserde(default)]
+    struct Entity {
+        name: String,
+        #[nserde(default_with = "first_id")]
+        id: NonZeroU32,
+        parent: Option<NonZeroU32>,
+    }
+
+    fn first_id() -> NonZeroU32 {
+        NonZeroU32::new(1).unwrap()
+    }
+
+    let out: Entity = DeJson::deserialize_json("{}").unwrap();
+    assert_eq!(
+        out,
+        Entity {
+            name: String::new(),
+            id: first_id(),
+            parent: None,
+        }
+    );
+}
+
+#[test]
+fn open_ranges() {
+    use core::ops::{RangeFrom, RangeFull, RangeTo};
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Slicing {
+        from: RangeFrom<usize>,
+        to: RangeTo<i32>,
+        full: RangeFull,
+    }
+
+    let test = Slicing {
+        from: 3..,
+        to: ..-7,
+        full: ..,
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"from":{"start":3},"to":{"end":-7},"full":{}}"#);
+    let out: Slicing = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, test);
+
+    assert_eq!(RangeFull::deserialize_json("null").unwrap(), ..);
+    assert!(<RangeFrom<i32> as DeJson>::deserialize_json(r#"{"start":3,"end":7}"#).is_err());
+    assert!(<RangeTo<i32> as DeJson>::deserialize_json(r#"{"start":3}"#).is_err());
+    assert!(<RangeTo<i32> as DeJson>::deserialize_json("{}").is_err());
+    assert!(RangeFull::deserialize_json(r#"{"start":3}"#).is_err());
+}
+
+#[test]
+fn digit_separators() {
+    assert_eq!(u32::deserialize_json("1_000_000").unwrap(), 1_000_000);
+    assert_eq!(i64::deserialize_json("-1_000").unwrap(), -1_000);
+    assert_eq!(f64::deserialize_json("1_000.000_5").unwrap(), 1_000.000_5);
+    assert_eq!(
+        Vec::<u32>::deserialize_json("[1_0, 2_0]").unwrap(),
+        vec![10, 20]
+    );
+
+    for invalid in ["1__0", "1_", "-_1", "1_.5", "1._5"] {
+        let err = f64::deserialize_json(invalid).unwrap_err();
+        assert!(
+            matches!(err.reason, nanoserde::DeJsonErrReason::CannotParse(_)),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn tuple_position_defaults() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct T(i32, #[nserde(default)] i32);
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Color(
+        u8,
+        #[nserde(default = 128)] u8,
+        #[nserde(default_with = "opaque")] u8,
+    );
+
+    fn opaque() -> u8 {
+        255
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    enum Shape {
+        Circle(f32, #[nserde(default = "unit")] String),
+        Line(i32, i32),
+    }
+
+    assert_eq!(T::deserialize_json("[5]").unwrap(), T(5, 0));
+    assert_eq!(T::deserialize_json("[5, 6]").unwrap(), T(5, 6));
+    assert_eq!(
+        T::deserialize_json(&T(1, 2).serialize_json()).unwrap(),
+        T(1, 2)
+    );
+    // positions without a default are still required
+    assert!(T::deserialize_json("[]").is_err());
+
+    assert_eq!(Color::deserialize_json("[1]").unwrap(), Color(1, 128, 255));
+    assert_eq!(Color::deserialize_json("[1, 2]").unwrap(), Color(1, 2, 255));
+
+    assert_eq!(
+        Shape::deserialize_json(r#"{"Circle":[1.5]}"#).unwrap(),
+        Shape::Circle(1.5, "unit".to_string())
+    );
+    assert_eq!(
+        Shape::deserialize_json(r#"{"Circle":[1.5,"cm"]}"#).unwrap(),
+        Shape::Circle(1.5, "cm".to_string())
+    );
+    assert!(Shape::deserialize_json(r#"{"Line":[1]}"#).is_err());
+}
+
+#[test]
+fn tagged_trait() {
+    trait Shape: nanoserde::JsonTagged {
+        fn area(&self) -> f32;
+    }
+
+    #[derive(DeJson, SerJson)]
+    struct Circle {
+        r: f32,
+    }
+
+    impl Shape for Circle {
+        fn area(&self) -> f32 {
+            3.0 * self.r * self.r
+        }
+    }
+
+    #[derive(DeJson, SerJson)]
+    struct Square {
+        side: f32,
+    }
+
+    impl Shape for Square {
+        fn area(&self) -> f32 {
+            self.side * self.side
+        }
+    }
+
+    nanoserde::json_tagged_trait!(Shape, "type", {
+        "circle" => Circle,
+        "square" => Square,
+    });
+
+    #[derive(DeJson, SerJson)]
+    struct Drawing {
+        name: String,
+        #[nserde(tagged_trait)]
+        shape: Box<dyn Shape>,
+        #[nserde(tagged_trait)]
+        extra: Option<Box<dyn Shape>>,
+    }
+
+    let drawing = Drawing {
+        name: "d".to_string(),
+        shape: Box::new(Circle { r: 1.0 }),
+        extra: Some(Box::new(Square { side: 2.0 })),
+    };
+    let json = drawing.serialize_json();
+    assert_eq!(
+        json,
+        r#"{"name":"d","shape":{"type":"circle","r":1.0},"extra":{"type":"square","side":2.0}}"#
+    );
+    let out = Drawing::deserialize_json(&json).unwrap();
+    assert_eq!(out.name, "d");
+    assert_eq!(out.shape.area(), 3.0);
+    assert_eq!(out.extra.unwrap().area(), 4.0);
+
+    // the tag doesn't have to come first
+    let out = Drawing::deserialize_json(
+        r#"{"name":"e","shape":{"side":3.0,"type":"square"},"extra":null}"#,
+    )
+    .unwrap();
+    assert_eq!(out.shape.area(), 9.0);
+    assert!(out.extra.is_none());
+    let out =
+        Drawing::deserialize_json(r#"{"name":"e","shape":{"type":"circle","r":2.0}}"#).unwrap();
+    assert_eq!(out.shape.area(), 12.0);
+    assert!(out.extra.is_none());
+
+    assert!(
+        Drawing::deserialize_json(r#"{"name":"e","shape":{"type":"hexagon","r":2.0}}"#).is_err()
+    );
+    assert!(Drawing::deserialize_json(r#"{"name":"e","shape":{"r":2.0}}"#).is_err());
+}
+
+#[test]
+fn serialize_into() {
+    #[derive(SerJson)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let mut out = String::new();
+    Point { x: 1, y: 2 }.serialize_json_into(&mut out);
+    assert_eq!(out, Point { x: 1, y: 2 }.serialize_json());
+    let capacity = out.capacity();
+
+    out.clear();
+    Point { x: 3, y: 4 }.serialize_json_into(&mut out);
+    assert_eq!(out, r#"{"x":3,"y":4}"#);
+    assert_eq!(out.capacity(), capacity);
+
+    // appends to what is already in the buffer
+    out.push(' ');
+    true.serialize_json_into(&mut out);
+    assert_eq!(out, r#"{"x":3,"y":4} true"#);
+}
+
+#[test]
+fn ser_json_to() {
+    #[derive(SerJson)]
+    struct Entry {
+        id: u32,
+        name: String,
+        tags: Vec<String>,
+        scores: BTreeMap<u32, f32>,
+    }
+
+    let entries: Vec<Entry> = (0..200)
+        .map(|id| Entry {
+            id,
+            name: format!("entry {}", id),
+            tags: vec!["a".to_string(), "b\"c".to_string()],
+            scores: [(id, 0.5), (id + 1, 1.5)].into_iter().collect(),
+        })
+        .collect();
+
+    // large enough to be handed to the writer in several chunks
+    struct Chunks {
+        out: String,
+        writes: usize,
+    }
+
+    impl core::fmt::Write for Chunks {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.out.push_str(s);
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    let mut chunks = Chunks {
+        out: String::new(),
+        writes: 0,
+    };
+    entries.ser_json_to(&mut chunks).unwrap();
+    assert_eq!(chunks.out, entries.serialize_json());
+    assert!(chunks.writes > 1);
+
+    let mut out = String::new();
+    entries[0].ser_json_to(&mut out).unwrap();
+    assert_eq!(out, entries[0].serialize_json());
+
+    struct Full;
+
+    impl core::fmt::Write for Full {
+        fn write_str(&mut self, _: &str) -> core::fmt::Result {
+            Err(core::fmt::Error)
+        }
+    }
+
+    assert!(entries.ser_json_to(&mut Full).is_err());
+    assert!(1u32.ser_json_to(&mut Full).is_err());
+}
+
+#[test]
+fn leading_plus_sign() {
+    // JSON only allows a leading `-`
+    assert!(<i32 as DeJson>::deserialize_json("+5").is_err());
+    assert!(<u32 as DeJson>::deserialize_json("+5").is_err());
+    assert!(<f64 as DeJson>::deserialize_json("+1.5").is_err());
+    assert!(<Vec<i32> as DeJson>::deserialize_json("[1, +2]").is_err());
+
+    // JSON5 accepts it
+    assert_eq!(<i32 as DeJson>::deserialize_json5("+5").unwrap(), 5);
+    assert_eq!(<u32 as DeJson>::deserialize_json5("+5").unwrap(), 5);
+    assert_eq!(<f64 as DeJson>::deserialize_json5("+1.5").unwrap(), 1.5);
+    assert_eq!(
+        <Vec<i32> as DeJson>::deserialize_json5("[1, +2, -3]").unwrap(),
+        [1, 2, -3]
+    );
+}
+
+#[test]
+fn result_roundtrip() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Outcome {
+        parsed: Result<u32, String>,
+        failed: Result<Vec<u8>, String>,
+    }
+
+    let test = Outcome {
+        parsed: Ok(42),
+        failed: Err("no input".to_string()),
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"parsed":{"Ok":42},"failed":{"Err":"no input"}}"#);
+    assert_eq!(Outcome::deserialize_json(&json).unwrap(), test);
+}
+
+#[test]
+fn phantom_data() {
+    use core::marker::PhantomData;
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Tagged<T> {
+        a: u32,
+        marker: PhantomData<T>,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Unit;
+
+    let tagged = Tagged::<u64> {
+        a: 1,
+        marker: PhantomData,
+    };
+    let json = tagged.serialize_json();
+    assert_eq!(json, r#"{"a":1,"marker":null}"#);
+    let out: Tagged<u64> = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(out, tagged);
+    assert!(<PhantomData<u64> as DeJson>::deserialize_json("1").is_err());
+
+    let json = Unit.serialize_json();
+    assert_eq!(json, "{}");
+    assert_eq!(Unit::deserialize_json(&json).unwrap(), Unit);
+}
+
+#[test]
+fn json5_bare_keys() {
+    #[derive(DeJson, PartialEq, Debug)]
+    struct Config {
+        a: u32,
+        b: String,
+    }
+
+    let config: Config = DeJson::deserialize_json5("{a: 1, b: 'two'}").unwrap();
+    assert_eq!(
+        config,
+        Config {
+            a: 1,
+            b: "two".to_string()
+        }
+    );
+    assert!(<Config as DeJson>::deserialize_json("{a: 1, b: 'two'}").is_err());
+
+    // keys of maps and nested objects may be bare too
+    let map: BTreeMap<String, Vec<String>> =
+        DeJson::deserialize_json5("{first: ['x'], $second_2: []}").unwrap();
+    assert_eq!(map["first"], ["x"]);
+    assert!(map["$second_2"].is_empty());
+}
+
+#[test]
+fn non_string_map_keys() {
+    #[cfg(feature = "std")]
+    {
+        let map: HashMap<u32, String> = [(1, "x".to_string())].into_iter().collect();
+        let json = map.serialize_json();
+        assert_eq!(json, r#"{"1":"x"}"#);
+        assert_eq!(
+            <HashMap<u32, String>>::deserialize_json(&json).unwrap(),
+            map
+        );
+    }
+
+    let map: BTreeMap<i64, char> = [(-5, 'a'), (7, 'b')].into_iter().collect();
+    let json = map.serialize_json();
+    assert_eq!(json, r#"{"-5":"a","7":"b"}"#);
+    assert_eq!(<BTreeMap<i64, char>>::deserialize_json(&json).unwrap(), map);
+
+    let map: BTreeMap<char, bool> = [('k', true)].into_iter().collect();
+    let json = map.serialize_json();
+    assert_eq!(json, r#"{"k":true}"#);
+    assert_eq!(
+        <BTreeMap<char, bool>>::deserialize_json(&json).unwrap(),
+        map
+    );
+
+    let map: BTreeMap<bool, u8> = [(false, 0), (true, 1)].into_iter().collect();
+    let json = map.serialize_json();
+    assert_eq!(json, r#"{"false":0,"true":1}"#);
+    assert_eq!(<BTreeMap<bool, u8>>::deserialize_json(&json).unwrap(), map);
+
+    // bare numbers, as written by older versions, are still read
+    let map: BTreeMap<u32, u8> = DeJson::deserialize_json(r#"{1:2}"#).unwrap();
+    assert_eq!(map[&1], 2);
+
+    // a key has to parse as the key type
+    assert!(<BTreeMap<u32, u8>>::deserialize_json(r#"{"x":2}"#).is_err());
+    assert!(<BTreeMap<u8, u8>>::deserialize_json(r#"{"256":2}"#).is_err());
+
+    // other key types implement the map key traits themselves
+    use nanoserde::{DeJsonErr, DeJsonState, FromJsonMapKey, ToJsonMapKey};
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+    struct UserId(u32);
+
+    impl ToJsonMapKey for UserId {
+        fn to_json_map_key(&self) -> std::borrow::Cow<'_, str> {
+            format!("user-{}", self.0).into()
+        }
+    }
+
+    impl FromJsonMapKey for UserId {
+        fn from_json_map_key(s: &DeJsonState, key: String) -> Result<Self, DeJsonErr> {
+            key.strip_prefix("user-")
+                .and_then(|id| id.parse().ok())
+                .map(UserId)
+                .ok_or_else(|| s.err_parse("user id"))
+        }
+    }
+
+    let map: BTreeMap<UserId, u8> = [(UserId(1), 2)].into_iter().collect();
+    let json = map.serialize_json();
+    assert_eq!(json, r#"{"user-1":2}"#);
+    assert_eq!(
+        <BTreeMap<UserId, u8>>::deserialize_json(&json).unwrap(),
+        map
+    );
+    assert!(<BTreeMap<UserId, u8>>::deserialize_json(r#"{"1":2}"#).is_err());
+}
+
+#[test]
+fn serialize_with_deserialize_with() {
+    use core::str::Chars;
+    use nanoserde::{DeJsonErr, DeJsonState, SerJsonState};
+
+    fn ser_hex(bytes: &Vec<u8>, _d: usize, s: &mut SerJsonState) {
+        s.out.push('"');
+        for byte in bytes {
+            s.out.push_str(&format!("{:02x}", byte));
+        }
+        s.out.push('"');
+    }
+
+    fn de_hex(s: &mut DeJsonState, i: &mut Chars) -> Result<Vec<u8>, DeJsonErr> {
+        let hex = s.as_string()?;
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|start| {
+                hex.get(start..start + 2)
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| s.err_parse("hex"))?;
+        s.next_tok(i)?;
+        Ok(bytes)
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Packet {
+        id: u32,
+        #[nserde(serialize_with = "ser_hex", deserialize_with = "de_hex")]
+        payload: Vec<u8>,
+        raw: Vec<u8>,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    enum Message {
+        Data {
+            #[nserde(serialize_with = "ser_hex", deserialize_with = "de_hex")]
+            payload: Vec<u8>,
+        },
+    }
+
+    let packet = Packet {
+        id: 1,
+        payload: vec![0xde, 0xad, 0x01],
+        raw: vec![2],
+    };
+    let json = packet.serialize_json();
+    assert_eq!(json, r#"{"id":1,"payload":"dead01","raw":[2]}"#);
+    assert_eq!(Packet::deserialize_json(&json).unwrap(), packet);
+    assert!(Packet::deserialize_json(r#"{"id":1,"payload":"xyz","raw":[]}"#).is_err());
+
+    let message = Message::Data {
+        payload: vec![0xff],
+    };
+    let json = message.serialize_json();
+    assert_eq!(json, r#"{"Data":{"payload":"ff"}}"#);
+    assert_eq!(Message::deserialize_json(&json).unwrap(), message);
+}
+
+#[test]
+fn introspect_fields() {
+    #[derive(SerJson, DeJson)]
+    #[nserde(introspect)]
+    struct User {
+        id: u32,
+        #[nserde(rename = "displayName")]
+        display_name: String,
+        #[nserde(skip)]
+        cache: Option<String>,
+        email: Option<String>,
+    }
+
+    #[derive(SerJson)]
+    #[nserde(introspect)]
+    struct Wrapper<T> {
+        #[nserde(rename = "type")]
+        kind: u8,
+        value: T,
+    }
+
+    assert_eq!(User::NSERDE_FIELDS, ["id", "displayName", "email"]);
+    assert_eq!(Wrapper::<u32>::NSERDE_FIELDS, ["type", "value"]);
+
+    let user = User {
+        id: 1,
+        display_name: "a".to_string(),
+        cache: Some("c".to_string()),
+        email: Some("e".to_string()),
+    };
+    assert_eq!(
+        user.serialize_json(),
+        r#"{"id":1,"displayName":"a","email":"e"}"#
+    );
+    assert!(user.cache.is_some());
+}
+
+#[test]
+fn nested_option_null_vs_absent() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(serialize_none_as_null)]
+    struct Patch {
+        name: Option<Option<String>>,
+        age: Option<Option<u32>>,
+    }
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    enum Change {
+        Set { value: Option<Option<u32>> },
+    }
+
+    let json = r#"{"name":null,"age":42}"#;
+    let patch: Patch = DeJson::deserialize_json(json).unwrap();
+    assert_eq!(patch.name, Some(None));
+    assert_eq!(patch.age, Some(Some(42)));
+    assert_eq!(patch.serialize_json(), json);
+
+    let patch: Patch = DeJson::deserialize_json(r#"{"name":"x"}"#).unwrap();
+    assert_eq!(patch.name, Some(Some("x".to_string())));
+    assert_eq!(patch.age, None);
+    assert_eq!(patch.serialize_json(), r#"{"name":"x"}"#);
+
+    let patch = Patch {
+        name: None,
+        age: None,
+    };
+    assert_eq!(patch.serialize_json(), "{}");
+    assert_eq!(Patch::deserialize_json("{}").unwrap(), patch);
+
+    for change in [
+        Change::Set { value: None },
+        Change::Set { value: Some(None) },
+        Change::Set {
+            value: Some(Some(7)),
+        },
+    ] {
+        let json = change.serialize_json();
+        assert_eq!(Change::deserialize_json(&json).unwrap(), change);
+    }
+}
+
+#[test]
+fn skip_serializing_and_skip_deserializing() {
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    #[nserde(deny_unknown_fields)]
+    struct Doc {
+        name: String,
+        #[nserde(skip_deserializing)]
+        version: u32,
+        #[nserde(skip_serializing)]
+        password: String,
+    }
+
+    let doc = Doc {
+        name: "a".to_string(),
+        version: 3,
+        password: "secret".to_string(),
+    };
+    // written but never read
+    let json = doc.serialize_json();
+    assert_eq!(json, r#"{"name":"a","version":3}"#);
+    let back: Doc = DeJson::deserialize_json(&json).unwrap();
+    assert_eq!(back.version, 0);
+    assert_eq!(back.password, "");
+
+    // read but never written
+    let doc: Doc = DeJson::deserialize_json(r#"{"name":"b","password":"hunter2"}"#).unwrap();
+    assert_eq!(doc.password, "hunter2");
+    assert_eq!(doc.serialize_json(), r#"{"name":"b","version":0}"#);
+}
+
+#[test]
+fn custom_error_reason() {
+    use nanoserde::{DeJsonErr, DeJsonErrReason, DeJsonState};
+    use std::str::Chars;
+
+    fn port(s: &mut DeJsonState, i: &mut Chars) -> Result<u16, DeJsonErr> {
+        let port = u16::de_json(s, i)?;
+        if port < 1024 {
+            return Err(s.err_custom(&format!("port {} is reserved", port)));
+        }
+        Ok(port)
+    }
+
+    #[derive(DeJson, Debug)]
+    struct Server {
+        #[nserde(deserialize_with = "port")]
+        port: u16,
+    }
+
+    assert_eq!(
+        Server::deserialize_json(r#"{"port":8080}"#).unwrap().port,
+        8080
+    );
+    let err = Server::deserialize_json(r#"{"port":80}"#).unwrap_err();
+    assert_eq!(
+        err.reason,
+        DeJsonErrReason::Custom("port 80 is reserved".to_string())
+    );
+    assert_eq!(err.msg, "port 80 is reserved");
+
+    let err =
+        std::time::Duration::deserialize_json(r#"{"secs":1,"nanos":1000000000}"#).unwrap_err();
+    assert_eq!(
+        err.reason,
+        DeJsonErrReason::Custom("Duration nanos 1000000000 is not below 1000000000".to_string())
+    );
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "json")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use nanoserde::SerJson;
+
+// this file only holds a single test, so nothing else reallocates concurrently
+struct CountingAllocator;
+
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.fetch_add(1, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn vec_reserves_output() {
+    let items: Vec<u32> = (0..100_000).collect();
+
+    let before = REALLOCS.load(Ordering::SeqCst);
+    let json = items.serialize_json();
+    let reallocs = REALLOCS.load(Ordering::SeqCst) - before;
+
+    assert!(json.starts_with("[0,1,2,"));
+    // growing the output one push at a time takes more than 15 reallocations
+    assert!(reallocs <= 2, "{} reallocations", reallocs);
+}
@@ -0,0 +1,47 @@
+#![no_std]
+
+// `core::time::Duration` has the same `{secs, nanos}` layout with and without the `std` feature
+extern crate alloc;
+
+#[test]
+#[cfg(feature = "json")]
+fn json_duration() {
+    use core::time::Duration;
+    use nanoserde::{DeJson, SerJson};
+
+    #[derive(DeJson, SerJson, PartialEq, Debug)]
+    struct Timeout {
+        after: Duration,
+    }
+
+    let test = Timeout {
+        after: Duration::new(3, 500),
+    };
+    let json = test.serialize_json();
+    assert_eq!(json, r#"{"after":{"secs":3,"nanos":500}}"#);
+    assert_eq!(Timeout::deserialize_json(&json).unwrap(), test);
+    assert!(Duration::deserialize_json(r#"{"secs":3}"#).is_err());
+}
+
+#[test]
+#[cfg(feature = "ron")]
+fn ron_duration() {
+    use core::time::Duration;
+    use nanoserde::{DeRon, SerRon};
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Timeout {
+        after: Duration,
+    }
+
+    let test = Timeout {
+        after: Duration::new(3, 500),
+    };
+    let ron = test.serialize_ron();
+    assert_eq!(Timeout::deserialize_ron(&ron).unwrap(), test);
+    assert_eq!(
+        Duration::deserialize_ron("(secs: 3, nanos: 500)").unwrap(),
+        test.after
+    );
+    assert!(Duration::deserialize_ron("(secs: 3)").is_err());
+}
@@ -376,6 +376,47 @@ fn exponents() {
     assert_eq!(foo.h, 0.01);
 }
 
+#[test]
+fn struct_transparent() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Id {
+        value: u64,
+    }
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Name(String);
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Record {
+        id: Id,
+        names: Vec<Name>,
+    }
+
+    let test = Record {
+        id: Id { value: 7 },
+        names: vec![Name("a".to_string())],
+    };
+    let ron = test.serialize_ron();
+    let out: Record = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    assert_eq!(Id { value: 3 }.serialize_ron(), "3");
+    assert_eq!(Name("b".to_string()).serialize_ron(), "\"b\"");
+    let id: Id = DeRon::deserialize_ron("12").unwrap();
+    assert_eq!(id, Id { value: 12 });
+    assert!(<Id as DeRon>::deserialize_ron("(value: 12)").is_err());
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    #[nserde(transparent)]
+    pub struct Wrapper<T>(T);
+
+    assert_eq!(Wrapper(5u8).serialize_ron(), "5");
+    let out: Wrapper<Vec<u8>> = DeRon::deserialize_ron("[1, 2]").unwrap();
+    assert_eq!(out, Wrapper(vec![1, 2]));
+}
+
 #[test]
 fn ronerror() {
     #[derive(DeRon)]
@@ -397,6 +438,22 @@ fn ronerror() {
     }
 }
 
+#[test]
+fn ronerror_column() {
+    #[derive(DeRon)]
+    #[allow(dead_code)]
+    struct Foo {
+        i: i32,
+        j: i32,
+    }
+
+    let ron = "(\n    i: 1,\n    j: \"string\",\n)";
+
+    let e = <Foo as DeRon>::deserialize_ron(ron).err().unwrap();
+    assert_eq!(e.line, 2);
+    assert!(e.col > 1);
+}
+
 #[test]
 fn de_enum() {
     #[derive(DeRon, PartialEq, Debug)]
@@ -664,3 +721,1131 @@ fn ron_crate() {
     assert_eq!(test.c, None);
     assert_eq!(test.d.unwrap(), "hello");
 }
+
+#[test]
+fn std_enums() {
+    use core::cmp::Ordering;
+    use core::num::FpCategory;
+    use core::ops::Bound;
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Test {
+        a: FpCategory,
+        b: Ordering,
+        c: Bound<u32>,
+        d: Bound<String>,
+        e: Bound<i8>,
+    }
+
+    let test = Test {
+        a: FpCategory::Subnormal,
+        b: Ordering::Greater,
+        c: Bound::Included(5),
+        d: Bound::Excluded("end".to_string()),
+        e: Bound::Unbounded,
+    };
+
+    let ron = SerRon::serialize_ron(&test);
+    let test_deserialized: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    let test: Test = DeRon::deserialize_ron(
+        "(a: Nan, b: Less, c: Excluded(1), d: Included(\"start\"), e: Unbounded)",
+    )
+    .unwrap();
+    assert_eq!(test.a, FpCategory::Nan);
+    assert_eq!(test.b, Ordering::Less);
+    assert_eq!(test.c, Bound::Excluded(1));
+    assert_eq!(test.d, Bound::Included("start".to_string()));
+    assert_eq!(test.e, Bound::Unbounded);
+
+    let orderings = vec![Ordering::Less, Ordering::Equal, Ordering::Greater];
+    let ron = SerRon::serialize_ron(&orderings);
+    assert_eq!(orderings, <Vec<Ordering>>::deserialize_ron(&ron).unwrap());
+
+    assert!(<Ordering as DeRon>::deserialize_ron("Smaller").is_err());
+}
+
+#[test]
+fn duration() {
+    use std::time::Duration;
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Test {
+        a: Duration,
+        b: Vec<Duration>,
+    }
+
+    let test = Test {
+        a: Duration::new(1000, 999_999_999),
+        b: vec![Duration::ZERO, Duration::from_millis(1500), Duration::MAX],
+    };
+
+    let ron = SerRon::serialize_ron(&test);
+    let test_deserialized: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(test, test_deserialized);
+
+    let named: Duration = DeRon::deserialize_ron("(nanos: 999999999, secs: 1000)").unwrap();
+    assert_eq!(named, Duration::new(1000, 999_999_999));
+    let positional: Duration = DeRon::deserialize_ron("(1000, 999999999)").unwrap();
+    assert_eq!(positional, Duration::new(1000, 999_999_999));
+
+    assert!(<Duration as DeRon>::deserialize_ron("(secs: 1, nanos: 1000000000)").is_err());
+    assert!(<Duration as DeRon>::deserialize_ron("(1, 1000000000)").is_err());
+    assert!(<Duration as DeRon>::deserialize_ron("(secs: 1)").is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn system_time() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let ron = SerRon::serialize_ron(&UNIX_EPOCH);
+    assert_eq!(
+        <SystemTime as DeRon>::deserialize_ron(&ron).unwrap(),
+        UNIX_EPOCH
+    );
+
+    let time = UNIX_EPOCH + Duration::new(1_700_000_000, 999_999_999);
+    let ron = SerRon::serialize_ron(&time);
+    assert_eq!(<SystemTime as DeRon>::deserialize_ron(&ron).unwrap(), time);
+
+    let before_epoch = UNIX_EPOCH - Duration::from_secs(1);
+    let ron = SerRon::serialize_ron(&before_epoch);
+    assert_eq!(ron, "None");
+    assert!(<SystemTime as DeRon>::deserialize_ron(&ron).is_err());
+}
+
+#[test]
+fn de_enum_reorder() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub enum Foo {
+        A,
+        C { a: i32, b: String, c: Option<f32> },
+    }
+
+    let ron = r#"[
+        C(b: "qwe", a: 2),
+        C(c: 1.5, b: "asd", a: 3,),
+        A,
+    ]"#;
+
+    let test: Vec<Foo> = DeRon::deserialize_ron(ron).unwrap();
+    assert_eq!(
+        test,
+        vec![
+            Foo::C {
+                a: 2,
+                b: "qwe".to_string(),
+                c: None,
+            },
+            Foo::C {
+                a: 3,
+                b: "asd".to_string(),
+                c: Some(1.5),
+            },
+            Foo::A,
+        ]
+    );
+
+    let serialized = SerRon::serialize_ron(&test);
+    let deserialized: Vec<Foo> = DeRon::deserialize_ron(&serialized).unwrap();
+    assert_eq!(deserialized, test);
+}
+
+#[test]
+fn de_reorder() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Test {
+        pub a: f32,
+        pub b: f32,
+        c: Option<String>,
+        d: Option<String>,
+    }
+
+    let ron = r#"(
+        d: "hello",
+        b: 2.0,
+        a: 1,
+    )"#;
+
+    let test: Test = DeRon::deserialize_ron(ron).unwrap();
+    assert_eq!(
+        test,
+        Test {
+            a: 1.,
+            b: 2.,
+            c: None,
+            d: Some("hello".to_string()),
+        }
+    );
+
+    // serialization keeps the declaration order
+    let serialized = test.serialize_ron();
+    assert!(serialized.find("a:").unwrap() < serialized.find("b:").unwrap());
+    assert!(serialized.find("b:").unwrap() < serialized.find("d:").unwrap());
+    let deserialized: Test = DeRon::deserialize_ron(&serialized).unwrap();
+    assert_eq!(deserialized, test);
+}
+
+#[test]
+fn de_reorder_and_unknown_fields() {
+    #[derive(DeRon, PartialEq, Debug)]
+    pub struct Inner {
+        x: i32,
+    }
+
+    #[derive(DeRon, PartialEq, Debug)]
+    pub struct Test {
+        a: i32,
+        b: String,
+        c: Option<Inner>,
+    }
+
+    let ron = r#"(
+        b: "b",
+        version: 3,
+        c: (x: 1, extra: Some((1, [2, 3], {"k": Unit}))),
+        meta: Meta(tags: ["x", "y"], nested: (a: (b: ()))),
+        a: 5,
+        flag: true,
+        ch: 'c',
+    )"#;
+
+    let test: Test = DeRon::deserialize_ron(ron).unwrap();
+    assert_eq!(
+        test,
+        Test {
+            a: 5,
+            b: "b".to_string(),
+            c: Some(Inner { x: 1 }),
+        }
+    );
+
+    // an unterminated unknown value is still an error
+    assert!(<Test as DeRon>::deserialize_ron("(a: 1, b: \"b\", meta: (1, [2)").is_err());
+}
+
+#[test]
+fn field_alias() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Test {
+        a: i32,
+        #[nserde(alias = "beta")]
+        b: i32,
+    }
+
+    let expected = Test { a: 1, b: 2 };
+    for ron in ["(a: 1, b: 2)", "(a: 1, beta: 2)"] {
+        let test: Test = DeRon::deserialize_ron(ron).unwrap();
+        assert_eq!(test, expected);
+    }
+
+    let ser = expected.serialize_ron();
+    assert!(ser.contains("b:"));
+    assert!(!ser.contains("beta"));
+}
+
+#[test]
+fn de_vec_edge_cases() {
+    for (ron, expected) in [
+        ("[]", vec![]),
+        ("[ ]", vec![]),
+        ("[1]", vec![1]),
+        ("[1,]", vec![1]),
+        ("[1,2]", vec![1, 2]),
+        ("[1,2,]", vec![1, 2]),
+        ("[\n    1,\n    2,\n]", vec![1, 2]),
+    ] {
+        let out: Vec<i32> = DeRon::deserialize_ron(ron).unwrap();
+        assert_eq!(out, expected, "{}", ron);
+    }
+
+    for ron in ["[,]", "[1,,2]", "[1 2]", "[1,2"] {
+        assert!(
+            <Vec<i32> as DeRon>::deserialize_ron(ron).is_err(),
+            "{}",
+            ron
+        );
+    }
+
+    let empty: Vec<i32> = vec![];
+    let out: Vec<i32> = DeRon::deserialize_ron(&empty.serialize_ron()).unwrap();
+    assert_eq!(out, empty);
+
+    let empty: [i32; 0] = [];
+    assert_eq!(empty.serialize_ron(), "()");
+    assert_eq!(empty.as_slice().serialize_ron(), "()");
+    let out: [i32; 0] = DeRon::deserialize_ron("()").unwrap();
+    assert_eq!(out, empty);
+}
+
+#[test]
+fn char_roundtrip() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Test {
+        a: char,
+        b: Vec<char>,
+    }
+
+    for (c, ron) in [
+        ('a', "'a'"),
+        ('\n', r"'\n'"),
+        ('"', "'\"'"),
+        ('\'', r"'\''"),
+        ('\\', r"'\\'"),
+        ('\u{e9}', "'\u{e9}'"),
+        ('\u{1f980}', "'\u{1f980}'"),
+    ] {
+        assert_eq!(c.serialize_ron(), ron);
+        let out: char = DeRon::deserialize_ron(ron).unwrap();
+        assert_eq!(out, c);
+    }
+
+    let out: char = DeRon::deserialize_ron(r"'é'").unwrap();
+    assert_eq!(out, '\u{e9}');
+
+    let test = Test {
+        a: '\'',
+        b: vec!['x', '\n', '\u{1f980}'],
+    };
+    let out: Test = DeRon::deserialize_ron(&test.serialize_ron()).unwrap();
+    assert_eq!(out, test);
+
+    for ron in ["''", "'ab'", "\"a\"", "'a"] {
+        assert!(<char as DeRon>::deserialize_ron(ron).is_err(), "{}", ron);
+    }
+}
+
+#[test]
+fn tuple_7() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Test {
+        t: (u8, i32, String, bool, f64, Vec<u16>, Option<char>),
+    }
+
+    let test = Test {
+        t: (1, -2, "three".to_string(), true, 5.5, vec![6, 6], Some('7')),
+    };
+    let ron = test.serialize_ron();
+    let out: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    let out: Test =
+        DeRon::deserialize_ron("(t: (1, -2, \"three\", true, 5.5, [6, 6], '7'))").unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn skip_serializing_if() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Test {
+        name: String,
+        #[nserde(skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+    }
+
+    let test = Test {
+        name: "a".to_string(),
+        tags: vec![],
+    };
+    let ron = test.serialize_ron();
+    assert!(!ron.contains("tags"));
+    let out: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    let test = Test {
+        name: "b".to_string(),
+        tags: vec!["x".to_string()],
+    };
+    let ron = test.serialize_ron();
+    assert!(ron.contains("tags"));
+    let out: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn quoted_keys() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Test {
+        #[nserde(rename = "max-size")]
+        max_size: u32,
+        #[nserde(rename = "true")]
+        yes: bool,
+        plain: i32,
+    }
+
+    let test = Test {
+        max_size: 10,
+        yes: true,
+        plain: -1,
+    };
+    let ron = test.serialize_ron();
+    assert!(ron.contains("\"max-size\":"), "{}", ron);
+    assert!(ron.contains("\"true\":"), "{}", ron);
+    assert!(ron.contains("plain:"), "{}", ron);
+    let out: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    // bare keys may be written quoted as well
+    let out: Test =
+        DeRon::deserialize_ron(r#"("plain": -1, "max-size": 10, "true": true, "extra": 1)"#)
+            .unwrap();
+    assert_eq!(out, test);
+}
+
+#[test]
+fn rc_and_arc() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Test {
+        a: Rc<String>,
+        b: Arc<Vec<u32>>,
+        c: Option<Rc<i32>>,
+    }
+
+    let test = Test {
+        a: Rc::new("shared".to_string()),
+        b: Arc::new(vec![1, 2]),
+        c: Some(Rc::new(3)),
+    };
+    let ron = test.serialize_ron();
+    let out: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    let rc = Rc::new("hi".to_string());
+    let out: Rc<String> = DeRon::deserialize_ron(&rc.serialize_ron()).unwrap();
+    assert_eq!(out, rc);
+}
+
+#[test]
+fn de_field_default_const() {
+    #[derive(DeRon, PartialEq, Debug)]
+    struct Limits {
+        #[nserde(default = const { Limits::MAX_RETRIES })]
+        retries: u32,
+        #[nserde(default = const { u16::MAX })]
+        port: u16,
+    }
+    impl Limits {
+        const MAX_RETRIES: u32 = 3;
+    }
+
+    let test: Limits = DeRon::deserialize_ron("()").unwrap();
+    assert_eq!(
+        test,
+        Limits {
+            retries: 3,
+            port: u16::MAX,
+        }
+    );
+
+    let test: Limits = DeRon::deserialize_ron("(retries: 1)").unwrap();
+    assert_eq!(test.retries, 1);
+}
+
+#[test]
+fn cow() {
+    use std::borrow::Cow;
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Test {
+        name: Cow<'static, str>,
+        values: Cow<'static, [u32]>,
+    }
+
+    let test = Test {
+        name: Cow::Borrowed("borrowed"),
+        values: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let ron = test.serialize_ron();
+    let out: Test = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+    assert!(matches!(out.name, Cow::Owned(_)));
+
+    let name: Cow<str> = Cow::Owned("owned".to_string());
+    let out: Cow<str> = DeRon::deserialize_ron(&name.serialize_ron()).unwrap();
+    assert_eq!(out, name);
+}
+
+#[test]
+fn nonzero() {
+    use std::num::{NonZeroI64, NonZeroU32, NonZeroU8};
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Handle {
+        id: NonZeroU32,
+        generation: Option<NonZeroU8>,
+        offset: NonZeroI64,
+    }
+
+    let test = Handle {
+        id: NonZeroU32::new(7).unwrap(),
+        generation: NonZeroU8::new(1),
+        offset: NonZeroI64::new(-3).unwrap(),
+    };
+    let ron = test.serialize_ron();
+    let out: Handle = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    let err = <NonZeroU8 as DeRon>::deserialize_ron("0").unwrap_err();
+    assert!(err.msg.contains("NonZeroU8"), "{}", err);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn net_addrs() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Peer {
+        v4: Ipv4Addr,
+        v6: Ipv6Addr,
+        ip: IpAddr,
+        addr: SocketAddr,
+        fallback: Option<SocketAddr>,
+    }
+
+    let test = Peer {
+        v4: Ipv4Addr::new(192, 168, 0, 1),
+        v6: Ipv6Addr::LOCALHOST,
+        ip: IpAddr::V6("fe80::1".parse().unwrap()),
+        addr: "[2001:db8::1]:8080".parse().unwrap(),
+        fallback: Some("10.0.0.1:53".parse().unwrap()),
+    };
+    let ron = test.serialize_ron();
+    assert!(ron.contains(r#"addr:"[2001:db8::1]:8080""#), "{}", ron);
+    let out: Peer = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    assert!(<Ipv4Addr as DeRon>::deserialize_ron(r#""256.0.0.1""#).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn serialize_canonical() {
+    use std::collections::{HashMap, HashSet};
+
+    #[derive(SerRon)]
+    struct Test {
+        map: HashMap<String, HashMap<i32, u8>>,
+        set: HashSet<String>,
+    }
+
+    let build = |keys: &[&str]| Test {
+        map: keys
+            .iter()
+            .map(|key| {
+                let inner = (0..20).map(|i| (i, i as u8)).collect();
+                (key.to_string(), inner)
+            })
+            .collect(),
+        set: keys.iter().map(|key| key.to_string()).collect(),
+    };
+
+    let a = build(&["x", "b", "a", "long-key", "z"]);
+    let b = build(&["z", "long-key", "a", "b", "x"]);
+    assert_eq!(a.serialize_ron_canonical(), b.serialize_ron_canonical());
+
+    let ron = a.serialize_ron_canonical().replace([' ', '\n'], "");
+    assert!(
+        ron.contains(r#"set:["a","b","long-key","x","z",]"#),
+        "{}",
+        ron
+    );
+
+    // hash collections are always sorted in RON
+    assert_eq!(a.serialize_ron(), b.serialize_ron_canonical());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn sort_keys() {
+    use std::collections::HashMap;
+
+    #[derive(SerRon)]
+    struct Scores {
+        #[nserde(sort_keys)]
+        scores: HashMap<String, u32>,
+    }
+
+    #[derive(SerRon)]
+    #[nserde(sort_keys)]
+    struct Report {
+        scores: HashMap<String, u32>,
+        totals: HashMap<String, u32>,
+    }
+
+    let names = ["x", "b", "a", "long-key", "z"];
+    let forward: HashMap<String, u32> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), i as u32))
+        .collect();
+    let backward: HashMap<String, u32> = names
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, name)| (name.to_string(), i as u32))
+        .collect();
+
+    let a = Scores { scores: forward };
+    let b = Scores { scores: backward };
+    assert_eq!(a.serialize_ron(), b.serialize_ron());
+    let ron = a.serialize_ron().replace([' ', '\n'], "");
+    assert!(
+        ron.contains(r#"scores:{"a":2,"b":1,"long-key":3,"x":0,"z":4,}"#),
+        "{}",
+        ron
+    );
+
+    let a = Report {
+        scores: a.scores,
+        totals: (0..3).map(|i| (format!("k{}", i), i)).collect(),
+    };
+    let b = Report {
+        scores: b.scores,
+        totals: (0..3).rev().map(|i| (format!("k{}", i), i)).collect(),
+    };
+    assert_eq!(a.serialize_ron(), b.serialize_ron());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn path_buf() {
+    use std::path::{Path, PathBuf};
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Config {
+        root: PathBuf,
+        cache: Option<PathBuf>,
+    }
+
+    let test = Config {
+        root: PathBuf::from("data/caf\u{e9}/\u{1f980}.txt"),
+        cache: None,
+    };
+    let ron = test.serialize_ron();
+    let out: Config = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    let path: &Path = Path::new("a/b");
+    assert_eq!(path.serialize_ron(), r#""a/b""#);
+}
+
+#[test]
+fn ranges() {
+    use core::ops::{Range, RangeInclusive};
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Selection {
+        lines: Range<usize>,
+        columns: RangeInclusive<i32>,
+        spans: Vec<Range<u8>>,
+    }
+
+    let test = Selection {
+        lines: 3..7,
+        columns: -3..=7,
+        spans: vec![0..1, 5..5],
+    };
+    let ron = test.serialize_ron();
+    let out: Selection = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    let range: RangeInclusive<i32> = DeRon::deserialize_ron("(start: 3, end: 7)").unwrap();
+    assert_eq!(range, 3..=7);
+    assert!(<Range<i32> as DeRon>::deserialize_ron("(end: 7)").is_err());
+}
+
+#[test]
+fn nesting_too_deep() {
+    #[derive(DeRon, PartialEq, Debug)]
+    pub struct Node(Vec<Node>);
+
+    let ron = "([".repeat(10000) + &"])".repeat(10000);
+    let e = <Node as DeRon>::deserialize_ron(&ron).err().unwrap();
+    assert!(e.msg.contains("Nesting too deep"));
+
+    let ron = "([".repeat(30) + &"])".repeat(30);
+    assert!(<Node as DeRon>::deserialize_ron(&ron).is_ok());
+}
+
+#[test]
+fn vec_deque_and_binary_heap() {
+    use std::collections::{BinaryHeap, VecDeque};
+
+    #[derive(DeRon, SerRon)]
+    struct Queues {
+        queue: VecDeque<u32>,
+        heap: BinaryHeap<i32>,
+    }
+
+    let mut queue: VecDeque<u32> = (3..6).collect();
+    queue.push_front(2);
+    queue.push_front(1);
+    let test = Queues {
+        queue,
+        heap: [5, -1, 9, 5, 0].into_iter().collect(),
+    };
+
+    let bytes = test.serialize_ron();
+    let out: Queues = DeRon::deserialize_ron(&bytes).unwrap();
+    assert_eq!(out.queue, [1, 2, 3, 4, 5]);
+    // a heap doesn't keep the insertion order, only the same items
+    assert_eq!(out.heap.into_sorted_vec(), [-1, 0, 5, 5, 9]);
+
+    let heap: BinaryHeap<u8> = DeRon::deserialize_ron(&vec![3u8, 1, 2].serialize_ron()).unwrap();
+    assert_eq!(heap.peek(), Some(&3));
+}
+
+#[test]
+fn relative_instant() {
+    use core::time::Duration;
+    use nanoserde::RelativeInstant;
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Event {
+        name: String,
+        at: RelativeInstant,
+    }
+
+    let test = Event {
+        name: "loaded".to_string(),
+        at: RelativeInstant(Duration::new(3, 250_000_000)),
+    };
+    let out: Event = DeRon::deserialize_ron(&test.serialize_ron()).unwrap();
+    assert_eq!(out, test);
+    assert_eq!(
+        RelativeInstant(Duration::from_millis(1500)).serialize_ron(),
+        Duration::from_millis(1500).serialize_ron()
+    );
+}
+
+#[test]
+fn ser_reference() {
+    fn to_ron<T: SerRon>(value: T) -> String {
+        value.serialize_ron()
+    }
+
+    let items = vec![1, 2, 3];
+    assert_eq!(to_ron(&items), items.serialize_ron());
+    assert_eq!(to_ron("a"), "\"a\"");
+    assert_eq!(Vec::<i32>::deserialize_ron(&to_ron(&items)).unwrap(), items);
+}
+
+#[test]
+fn open_ranges() {
+    use core::ops::{RangeFrom, RangeFull, RangeTo};
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Slicing {
+        from: RangeFrom<usize>,
+        to: RangeTo<i32>,
+        full: RangeFull,
+    }
+
+    let test = Slicing {
+        from: 3..,
+        to: ..-7,
+        full: ..,
+    };
+    let ron = test.serialize_ron();
+    let out: Slicing = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, test);
+
+    assert_eq!(RangeFull.serialize_ron(), "()");
+    assert_eq!(
+        <RangeFrom<i32> as DeRon>::deserialize_ron("(start: 3)").unwrap(),
+        3..
+    );
+    assert!(<RangeFrom<i32> as DeRon>::deserialize_ron("(start: 3, end: 7)").is_err());
+    assert!(<RangeTo<i32> as DeRon>::deserialize_ron("()").is_err());
+}
+
+#[test]
+fn digit_separators() {
+    assert_eq!(u32::deserialize_ron("1_000_000").unwrap(), 1_000_000);
+    assert_eq!(i64::deserialize_ron("-1_000").unwrap(), -1_000);
+    assert_eq!(f64::deserialize_ron("1_000.000_5").unwrap(), 1_000.000_5);
+
+    for invalid in ["1__0", "1_", "-_1", "1_.5", "1._5"] {
+        let err = f64::deserialize_ron(invalid).unwrap_err();
+        assert!(err.msg.starts_with("Cannot parse number"), "{}", invalid);
+    }
+}
+
+#[test]
+fn tuple_position_defaults() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct T(i32, #[nserde(default)] i32);
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    enum Shape {
+        Circle(f32, #[nserde(default = "unit")] String),
+        Line(i32, i32),
+    }
+
+    assert_eq!(T::deserialize_ron("(5)").unwrap(), T(5, 0));
+    assert_eq!(T::deserialize_ron("(5, 6)").unwrap(), T(5, 6));
+    assert!(T::deserialize_ron("()").is_err());
+
+    assert_eq!(
+        Shape::deserialize_ron("Circle(1.5)").unwrap(),
+        Shape::Circle(1.5, "unit".to_string())
+    );
+    let shape = Shape::Circle(2.5, "cm".to_string());
+    assert_eq!(
+        Shape::deserialize_ron(&shape.serialize_ron()).unwrap(),
+        shape
+    );
+    assert!(Shape::deserialize_ron("Line(1)").is_err());
+}
+
+#[test]
+fn malformed_input() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Doc {
+        name: String,
+        tag: char,
+        values: Vec<f64>,
+        pairs: BTreeMap<i32, Option<String>>,
+        nested: Option<Box<Doc>>,
+    }
+
+    let doc = Doc {
+        name: "a \"quoted\" \\ name\n".to_string(),
+        tag: '\'',
+        values: vec![-1.5, 2e-10, 3.0],
+        pairs: [(1, Some("x".to_string())), (-2, None)]
+            .into_iter()
+            .collect(),
+        nested: Some(Box::new(Doc {
+            name: "\u{1F600}".to_string(),
+            tag: '\n',
+            values: vec![],
+            pairs: BTreeMap::new(),
+            nested: None,
+        })),
+    };
+    let ron = doc.serialize_ron();
+    assert_eq!(Doc::deserialize_ron(&ron).unwrap(), doc);
+
+    // every truncation of a valid document fails cleanly instead of panicking or hanging
+    for (end, _) in ron.char_indices() {
+        assert!(
+            Doc::deserialize_ron(&ron[..end]).is_err(),
+            "{}",
+            &ron[..end]
+        );
+    }
+
+    for input in [
+        "-",
+        "+",
+        ".",
+        "1e",
+        "1e-",
+        "--1",
+        "\"abc",
+        "\"abc\\",
+        "\"\\u12\"",
+        "'",
+        "'a",
+        "'\\",
+        "''",
+        "'ab'",
+        "'\\u00'",
+        "@",
+        "#",
+        "/",
+        "/ 1",
+        "/* 1",
+        "/*/ 1",
+        "1 /* 2 *",
+    ] {
+        assert!(f64::deserialize_ron(input).is_err(), "{}", input);
+        assert!(Doc::deserialize_ron(input).is_err(), "{}", input);
+    }
+
+    for input in [
+        "(1; 2)",
+        "(1 2)",
+        "[1, 2",
+        "(a: 1,, b: 2)",
+        "[1, 2]]",
+        "(1, 2) @",
+    ] {
+        assert!(<(i32, i32)>::deserialize_ron(input).is_err(), "{}", input);
+    }
+
+    assert_eq!(f64::deserialize_ron("1e+2").unwrap(), 100.);
+    assert_eq!(f64::deserialize_ron("/**/ 1 /***/").unwrap(), 1.);
+}
+
+#[test]
+fn serialize_into() {
+    #[derive(SerRon)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let mut out = String::new();
+    Point { x: 1, y: 2 }.serialize_ron_into(&mut out);
+    assert_eq!(out, Point { x: 1, y: 2 }.serialize_ron());
+    let capacity = out.capacity();
+
+    out.clear();
+    Point { x: 3, y: 4 }.serialize_ron_into(&mut out);
+    assert_eq!(out, Point { x: 3, y: 4 }.serialize_ron());
+    assert_eq!(out.capacity(), capacity);
+
+    // appends to what is already in the buffer
+    out.clear();
+    out.push('[');
+    true.serialize_ron_into(&mut out);
+    assert_eq!(out, "[true");
+}
+
+#[test]
+fn result_roundtrip() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Outcome {
+        parsed: Result<u32, String>,
+        failed: Result<Vec<u8>, String>,
+    }
+
+    let test = Outcome {
+        parsed: Ok(42),
+        failed: Err("no input".to_string()),
+    };
+    let ron = test.serialize_ron();
+    assert_eq!(Outcome::deserialize_ron(&ron).unwrap(), test);
+
+    assert_eq!(
+        SerRon::serialize_ron(&Result::<u32, String>::Ok(1)),
+        "Ok(1)"
+    );
+    assert_eq!(
+        <Result<u32, String> as DeRon>::deserialize_ron("Err(\"boom\")").unwrap(),
+        Err("boom".to_string())
+    );
+    assert!(<Result<u32, String> as DeRon>::deserialize_ron("Maybe(1)").is_err());
+}
+
+#[test]
+fn phantom_data() {
+    use core::marker::PhantomData;
+
+    // the RON derives don't support generics, the marker still works as a field
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Tagged {
+        a: u32,
+        marker: PhantomData<u64>,
+    }
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Unit;
+
+    let tagged = Tagged {
+        a: 1,
+        marker: PhantomData,
+    };
+    let ron = tagged.serialize_ron();
+    let out: Tagged = DeRon::deserialize_ron(&ron).unwrap();
+    assert_eq!(out, tagged);
+    assert_eq!(SerRon::serialize_ron(&PhantomData::<u64>), "()");
+    assert!(<PhantomData<u64> as DeRon>::deserialize_ron("1").is_err());
+
+    let ron = Unit.serialize_ron();
+    assert_eq!(Unit::deserialize_ron(&ron).unwrap(), Unit);
+}
+
+#[test]
+fn tuple_variant_proxy() {
+    #[derive(PartialEq, Clone, Debug)]
+    #[repr(u32)]
+    enum SomeEnum {
+        One,
+        Two,
+        Three,
+    }
+
+    #[derive(PartialEq, Debug, DeRon, SerRon)]
+    #[nserde(transparent)]
+    pub struct U32(u32);
+
+    impl From<&SomeEnum> for U32 {
+        fn from(e: &SomeEnum) -> U32 {
+            U32(e.clone() as u32)
+        }
+    }
+    impl From<&U32> for SomeEnum {
+        fn from(n: &U32) -> SomeEnum {
+            match n.0 {
+                0 => SomeEnum::One,
+                1 => SomeEnum::Two,
+                2 => SomeEnum::Three,
+                _ => panic!(),
+            }
+        }
+    }
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    enum Test {
+        A(#[nserde(proxy = "U32")] Option<SomeEnum>),
+        B(u8, #[nserde(proxy = "U32")] SomeEnum),
+    }
+
+    for test in [
+        Test::A(Some(SomeEnum::Three)),
+        Test::A(None),
+        Test::B(7, SomeEnum::Two),
+    ] {
+        let ron = SerRon::serialize_ron(&test);
+        let test_deserialized: Test = DeRon::deserialize_ron(&ron).unwrap();
+        assert_eq!(test, test_deserialized);
+    }
+    assert_eq!(SerRon::serialize_ron(&Test::B(7, SomeEnum::Two)), "B(7, 1)");
+}
+
+#[test]
+fn serialize_compact() {
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Inner {
+        x: i32,
+        tags: Vec<String>,
+    }
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    enum Kind {
+        Point { x: i32, y: i32 },
+        Named(String, u8),
+    }
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+        kinds: Vec<Kind>,
+        empty: Vec<u8>,
+        scores: BTreeMap<String, u32>,
+        maybe: Option<u8>,
+    }
+
+    let test = Outer {
+        name: "a, b".to_string(),
+        inner: Inner {
+            x: 1,
+            tags: vec!["t".to_string()],
+        },
+        kinds: vec![Kind::Point { x: 1, y: 2 }, Kind::Named("n".to_string(), 3)],
+        empty: vec![],
+        scores: [("s".to_string(), 4)].into_iter().collect(),
+        maybe: None,
+    };
+
+    let ron = test.serialize_ron_compact();
+    assert_eq!(
+        ron,
+        r#"(name:"a, b",inner:(x:1,tags:["t"]),kinds:[Point(x:1,y:2),Named("n", 3)],empty:[],scores:{"s":4})"#
+    );
+    assert_eq!(Outer::deserialize_ron(&ron).unwrap(), test);
+
+    // the pretty default is unchanged
+    assert!(test.serialize_ron().contains("(\n    name:\"a, b\",\n"));
+
+    // strings and chars keep their contents
+    let test = ("[1,]\n    x".to_string(), '"', ']');
+    let ron = test.serialize_ron_compact();
+    assert_eq!(ron, r#"("[1,]\n    x", '"', ']')"#);
+    assert_eq!(<(String, char, char)>::deserialize_ron(&ron).unwrap(), test);
+}
+
+#[test]
+fn sequences_format_like_vec() {
+    #[derive(SerRon, DeRon, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let items = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+    let vec_ron = items.serialize_ron();
+    assert!(vec_ron.starts_with("[\n    (\n        x:1,"), "{}", vec_ron);
+
+    let set: BTreeSet<Point> = items.iter().cloned().collect();
+    assert_eq!(set.serialize_ron(), vec_ron);
+    let list: LinkedList<Point> = items.iter().cloned().collect();
+    assert_eq!(list.serialize_ron(), vec_ron);
+    #[cfg(feature = "std")]
+    {
+        let set: std::collections::HashSet<Point> = items.iter().take(1).cloned().collect();
+        assert_eq!(set.serialize_ron(), items[..1].to_vec().serialize_ron());
+        assert_eq!(
+            <std::collections::HashSet<Point> as DeRon>::deserialize_ron(&set.serialize_ron())
+                .unwrap(),
+            set
+        );
+    }
+
+    assert_eq!(
+        <BTreeSet<Point> as DeRon>::deserialize_ron(&vec_ron).unwrap(),
+        set
+    );
+    assert_eq!(
+        BTreeSet::<u8>::new().serialize_ron(),
+        Vec::<u8>::new().serialize_ron()
+    );
+}
+
+#[test]
+fn wide_integer_roundtrip() {
+    use std::num::NonZeroU128;
+
+    #[derive(DeRon, SerRon, PartialEq, Debug)]
+    pub struct Test {
+        a: u128,
+        b: i128,
+        c: i128,
+        d: NonZeroU128,
+    }
+
+    let test = Test {
+        a: u128::MAX,
+        b: i128::MIN,
+        c: i128::MAX,
+        d: NonZeroU128::new(u128::MAX).unwrap(),
+    };
+    let ron = test.serialize_ron();
+    assert!(ron.contains("a:340282366920938463463374607431768211455,"));
+    assert!(ron.contains("b:-170141183460469231731687303715884105728,"));
+    assert_eq!(test, DeRon::deserialize_ron(&ron).unwrap());
+
+    assert!(<u128 as DeRon>::deserialize_ron("-1").is_err());
+    assert!(<i64 as DeRon>::deserialize_ron("18446744073709551615").is_err());
+
+    // values too wide for 64 bits are out of range for the smaller integers
+    let err = <u64 as DeRon>::deserialize_ron("18446744073709551616").unwrap_err();
+    assert_eq!(
+        err.reason,
+        nanoserde::DeRonErrReason::OutOfRange(
+            "18446744073709551616>18446744073709551615".to_string()
+        )
+    );
+    let err = <i32 as DeRon>::deserialize_ron("-9223372036854775809").unwrap_err();
+    assert!(
+        matches!(err.reason, nanoserde::DeRonErrReason::OutOfRange(_)),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn duration_nanos_out_of_range() {
+    let err = std::time::Duration::deserialize_ron("(secs: 1, nanos: 1000000000)").unwrap_err();
+    assert_eq!(err.msg, "Duration nanos 1000000000 is not below 1000000000");
+}
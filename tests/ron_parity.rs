@@ -0,0 +1,108 @@
+#![cfg(feature = "ron")]
+//! Round trips every leaf type through RON, so the RON impls keep up with the
+//! ones of the binary and JSON formats.
+use nanoserde::{DeRon, SerRon};
+
+use core::fmt::Debug;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use core::time::Duration;
+
+fn roundtrip<T: SerRon + DeRon + PartialEq + Debug>(value: T) {
+    let ron = value.serialize_ron();
+    let out =
+        T::deserialize_ron(&ron).unwrap_or_else(|e| panic!("{:?} from {}: {}", value, ron, e));
+    assert_eq!(out, value, "{}", ron);
+}
+
+#[test]
+fn integers_128() {
+    for value in [0, 1, u64::MAX as u128, u64::MAX as u128 + 1, u128::MAX] {
+        roundtrip(value);
+    }
+    for value in [
+        0,
+        -1,
+        i64::MIN as i128 - 1,
+        i64::MAX as i128 + 1,
+        i128::MIN,
+        i128::MAX,
+    ] {
+        roundtrip(value);
+    }
+
+    assert_eq!(
+        u128::MAX.serialize_ron(),
+        "340282366920938463463374607431768211455"
+    );
+    assert!(u128::deserialize_ron("-1").is_err());
+    assert!(u128::deserialize_ron("340282366920938463463374607431768211456").is_err());
+    // one past i128::MAX still tokenizes, but is out of range for the field
+    assert!(i128::deserialize_ron("170141183460469231731687303715884105728").is_err());
+}
+
+#[test]
+fn non_zero() {
+    roundtrip(NonZeroU8::new(u8::MAX).unwrap());
+    roundtrip(NonZeroU16::new(2).unwrap());
+    roundtrip(NonZeroU32::new(3).unwrap());
+    roundtrip(NonZeroU64::new(u64::MAX).unwrap());
+    roundtrip(NonZeroU128::new(u128::MAX).unwrap());
+    roundtrip(NonZeroUsize::new(5).unwrap());
+    roundtrip(NonZeroI8::new(i8::MIN).unwrap());
+    roundtrip(NonZeroI16::new(-2).unwrap());
+    roundtrip(NonZeroI32::new(-3).unwrap());
+    roundtrip(NonZeroI64::new(i64::MIN).unwrap());
+    roundtrip(NonZeroI128::new(i128::MIN).unwrap());
+
+    assert!(NonZeroU128::deserialize_ron("0").is_err());
+    assert!(NonZeroI128::deserialize_ron("0").is_err());
+}
+
+#[test]
+fn chars() {
+    for value in ['a', 'é', '😀', '\n', '\r', '\t', '\0', '\\', '\'', '"'] {
+        roundtrip(value);
+    }
+}
+
+#[test]
+fn durations() {
+    roundtrip(Duration::ZERO);
+    roundtrip(Duration::new(1, 999_999_999));
+    roundtrip(Duration::MAX);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn system_time() {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    roundtrip(UNIX_EPOCH);
+    roundtrip(UNIX_EPOCH + Duration::new(1_700_000_000, 123));
+    roundtrip(SystemTime::now());
+}
+
+#[test]
+fn in_struct() {
+    #[derive(SerRon, DeRon, PartialEq, Debug)]
+    struct Leaves {
+        a: u128,
+        b: i128,
+        c: Option<NonZeroU128>,
+        d: char,
+        e: Duration,
+        f: Vec<NonZeroI128>,
+    }
+
+    roundtrip(Leaves {
+        a: u128::MAX,
+        b: i128::MIN,
+        c: NonZeroU128::new(7),
+        d: '\'',
+        e: Duration::from_millis(1500),
+        f: vec![NonZeroI128::new(-1).unwrap()],
+    });
+}
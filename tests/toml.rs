@@ -5,6 +5,7 @@ extern crate alloc;
 use alloc::collections::BTreeMap;
 use nanoserde::Toml;
 use nanoserde::TomlParser;
+use nanoserde::{DeToml, SerToml};
 
 #[test]
 fn de_toml() {
@@ -113,3 +114,485 @@ fn toml_key_chars() {
         ])
     );
 }
+
+#[test]
+fn ser_toml() {
+    #[derive(SerToml)]
+    pub struct Window {
+        title: String,
+        size: [u32; 2],
+        #[nserde(rename = "full-screen")]
+        fullscreen: bool,
+    }
+
+    #[derive(SerToml)]
+    pub struct Audio {
+        volume: f32,
+        device: Option<String>,
+        muted: Option<bool>,
+    }
+
+    #[derive(SerToml)]
+    pub struct Config {
+        window: Window,
+        name: String,
+        audio: Audio,
+        version: u64,
+        offset: i8,
+        tags: Vec<String>,
+        #[nserde(skip)]
+        #[allow(dead_code)]
+        cache: Vec<u8>,
+    }
+
+    let config = Config {
+        window: Window {
+            title: "Say \"hi\"".to_string(),
+            size: [800, 600],
+            fullscreen: false,
+        },
+        name: "demo".to_string(),
+        audio: Audio {
+            volume: 0.5,
+            device: None,
+            muted: Some(true),
+        },
+        version: 3,
+        offset: -2,
+        tags: vec!["a".to_string(), "b".to_string()],
+        cache: vec![1, 2, 3],
+    };
+
+    let toml_str = config.serialize_toml();
+    assert_eq!(
+        toml_str,
+        r#"name = "demo"
+version = 3
+offset = -2
+tags = ["a", "b"]
+
+[window]
+title = "Say \"hi\""
+size = [800, 600]
+full-screen = false
+
+[audio]
+volume = 0.5
+muted = true
+"#
+    );
+
+    let parsed = TomlParser::parse(&toml_str).unwrap();
+    assert_eq!(parsed["name"], Toml::Str("demo".to_string()));
+    assert_eq!(parsed["version"], Toml::Num(3.));
+    assert_eq!(parsed["offset"], Toml::Num(-2.));
+    assert_eq!(
+        parsed["tags"],
+        Toml::SimpleArray(vec![Toml::Str("a".to_string()), Toml::Str("b".to_string())])
+    );
+    assert_eq!(parsed["window.title"], Toml::Str("Say \"hi\"".to_string()));
+    assert_eq!(
+        parsed["window.size"],
+        Toml::SimpleArray(vec![Toml::Num(800.), Toml::Num(600.)])
+    );
+    assert_eq!(parsed["window.full-screen"], Toml::Bool(false));
+    assert_eq!(parsed["audio.volume"], Toml::Num(0.5));
+    assert_eq!(parsed["audio.muted"], Toml::Bool(true));
+    assert!(!parsed.contains_key("audio.device"));
+    assert!(!parsed.contains_key("cache"));
+}
+
+#[test]
+fn de_toml_nested() {
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Server {
+        host: String,
+        port: u16,
+        #[nserde(rename = "max-connections")]
+        max_connections: Option<u32>,
+        #[nserde(default = "30")]
+        timeout: u64,
+    }
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Database {
+        url: String,
+        replicas: Vec<String>,
+        #[nserde(default)]
+        read_only: bool,
+        #[nserde(default = "admin")]
+        user: Option<String>,
+    }
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Config {
+        title: String,
+        ratio: f32,
+        server: Server,
+        database: Database,
+        cache: Option<Database>,
+    }
+
+    let toml_str = r#"
+title = "example"
+ratio = 0.5
+
+[server]
+host = "localhost"
+port = 8080
+max-connections = 100
+
+[database]
+url = "postgres://db"
+replicas = ["a", "b"]
+"#;
+
+    let config: Config = DeToml::deserialize_toml(toml_str).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            title: "example".to_string(),
+            ratio: 0.5,
+            server: Server {
+                host: "localhost".to_string(),
+                port: 8080,
+                max_connections: Some(100),
+                timeout: 30,
+            },
+            database: Database {
+                url: "postgres://db".to_string(),
+                replicas: vec!["a".to_string(), "b".to_string()],
+                read_only: false,
+                user: Some("admin".to_string()),
+            },
+            cache: None,
+        }
+    );
+
+    let missing = "title = \"example\"\nratio = 1\n\n[server]\nhost = \"localhost\"\n";
+    let err = <Config as DeToml>::deserialize_toml(missing).unwrap_err();
+    assert_eq!(err.msg, "Key not found server.port");
+    assert_eq!((err.line, err.col), (5, 0));
+
+    let wrong_type = toml_str.replace("port = 8080", "port = 80.5");
+    let err = <Config as DeToml>::deserialize_toml(&wrong_type).unwrap_err();
+    assert!(err.msg.contains("server.port"), "{}", err.msg);
+    // points at the value rather than the end of the input
+    assert_eq!((err.line, err.col), (6, 7));
+}
+
+#[test]
+fn de_toml_coercion() {
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Pixel {
+        level: u8,
+        offset: i16,
+        scale: f32,
+        visible: bool,
+    }
+
+    let pixel: Pixel =
+        DeToml::deserialize_toml("level = 255\noffset = -300\nscale = 2\nvisible = true").unwrap();
+    assert_eq!(
+        pixel,
+        Pixel {
+            level: 255,
+            offset: -300,
+            scale: 2.0,
+            visible: true,
+        }
+    );
+
+    let err =
+        <Pixel as DeToml>::deserialize_toml("level = 256\noffset = 0\nscale = 1\nvisible = true")
+            .unwrap_err();
+    assert!(
+        err.msg.contains("Value out of range 256 for u8"),
+        "{}",
+        err.msg
+    );
+    let err = <Pixel as DeToml>::deserialize_toml(
+        "level = 1\noffset = -40000\nscale = 1\nvisible = true",
+    )
+    .unwrap_err();
+    assert!(err.msg.contains("out of range"), "{}", err.msg);
+    let err =
+        <Pixel as DeToml>::deserialize_toml("level = 1\noffset = 0\nscale = 1000000000000000000000000000000000000000000000.0\nvisible = true")
+            .unwrap_err();
+    assert!(err.msg.contains("out of range"), "{}", err.msg);
+
+    let err =
+        <Pixel as DeToml>::deserialize_toml("level = 1.5\noffset = 0\nscale = 1\nvisible = true")
+            .unwrap_err();
+    assert!(err.msg.contains("Expected u8"), "{}", err.msg);
+    let err = <Pixel as DeToml>::deserialize_toml("level = 1\noffset = 0\nscale = 1\nvisible = 1")
+        .unwrap_err();
+    assert!(err.msg.contains("Expected bool"), "{}", err.msg);
+}
+
+#[test]
+fn de_toml_default_const() {
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Server {
+        host: String,
+        #[nserde(default = const { Server::DEFAULT_PORT })]
+        port: u16,
+    }
+    impl Server {
+        const DEFAULT_PORT: u16 = 8080;
+    }
+
+    let server: Server = DeToml::deserialize_toml("host = \"localhost\"").unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: "localhost".to_string(),
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn simple_array_typed() {
+    let toml = TomlParser::parse("values = [1, 2, 3]\nmixed = [1, \"a\"]").unwrap();
+
+    let values: Vec<f64> = toml["values"].try_vec().unwrap();
+    assert_eq!(values, [1., 2., 3.]);
+    let values: [f64; 3] = toml["values"].try_array().unwrap();
+    assert_eq!(values, [1., 2., 3.]);
+    let values: [u8; 3] = toml["values"].try_array().unwrap();
+    assert_eq!(values, [1, 2, 3]);
+
+    let err = toml["values"].try_array::<f64, 2>().unwrap_err();
+    assert!(err.msg.contains("length 2"), "{}", err);
+    assert!(toml["values"].try_vec::<String>().is_err());
+    assert!(toml["mixed"].try_vec::<f64>().is_err());
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Color {
+        rgb: [u8; 3],
+    }
+
+    let color: Color = DeToml::deserialize_toml("rgb = [255, 128, 0]").unwrap();
+    assert_eq!(color.rgb, [255, 128, 0]);
+    assert!(<Color as DeToml>::deserialize_toml("rgb = [255, 128]").is_err());
+}
+
+#[test]
+fn inline_table() {
+    let toml = TomlParser::parse(
+        "point = { x = 1, y = 2 }\nempty = {}\nnested = { name = \"a\", tags = [true, false], inner = {z=3} }",
+    )
+    .unwrap();
+
+    let point = toml["point"].table();
+    assert_eq!(point.len(), 2);
+    assert_eq!(point["x"].num(), 1.);
+    assert_eq!(point["y"].num(), 2.);
+    assert!(toml["empty"].table().is_empty());
+
+    let nested = toml["nested"].table();
+    assert_eq!(nested["name"].str(), "a");
+    assert_eq!(
+        nested["tags"],
+        Toml::SimpleArray(vec![Toml::Bool(true), Toml::Bool(false)])
+    );
+    assert_eq!(nested["inner"].table()["z"].num(), 3.);
+
+    assert!(TomlParser::parse("point = { x = 1").is_err());
+    assert!(TomlParser::parse("point = { x 1 }").is_err());
+
+    // the keys are also joined to the key of the table, like those of a section
+    assert_eq!(toml["point.x"], Toml::Num(1.));
+    assert_eq!(toml["nested.inner.z"], Toml::Num(3.));
+}
+
+#[test]
+fn de_toml_inline_table() {
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Shape {
+        name: String,
+        center: Point,
+        offset: Option<Point>,
+    }
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Doc {
+        point: Point,
+        shape: Shape,
+    }
+
+    let doc: Doc = DeToml::deserialize_toml(
+        "point = { x = 1, y = 2 }\n[shape]\nname = \"a\"\ncenter = { x = 3, y = 4 }\n",
+    )
+    .unwrap();
+    assert_eq!(
+        doc,
+        Doc {
+            point: Point { x: 1, y: 2 },
+            shape: Shape {
+                name: "a".to_string(),
+                center: Point { x: 3, y: 4 },
+                offset: None,
+            },
+        }
+    );
+
+    let shape: Shape = DeToml::deserialize_toml(
+        "name = \"b\"\ncenter = { x = 0, y = 0 }\noffset = { x = -1, y = 1 }",
+    )
+    .unwrap();
+    assert_eq!(shape.offset, Some(Point { x: -1, y: 1 }));
+
+    let err = <Doc as DeToml>::deserialize_toml("point = { x = 1 }\nshape = { name = \"c\" }")
+        .unwrap_err();
+    assert_eq!(err.msg, "Key not found point.y");
+
+    // a value of the wrong type points at its inline table
+    let err = <Doc as DeToml>::deserialize_toml(
+        "shape = { name = \"c\", center = { x = 0, y = 0 } }\npoint = { x = \"a\", y = 2 }",
+    )
+    .unwrap_err();
+    assert!(err.msg.contains("point.x"), "{}", err.msg);
+    assert_eq!((err.line, err.col), (1, 8));
+}
+
+#[test]
+fn dotted_keys() {
+    let toml = TomlParser::parse(
+        r#"
+server.port = 8080
+site."google.com" = true
+a . b . c = 1
+[dog."tater"]
+type.name = "pug"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(toml["server.port"], Toml::Num(8080.));
+    assert_eq!(toml["site.google.com"], Toml::Bool(true));
+    assert_eq!(toml["a.b.c"], Toml::Num(1.));
+    assert_eq!(toml["dog.tater.type.name"].str(), "pug");
+
+    let toml = TomlParser::parse("point = { pos.x = 1 }").unwrap();
+    assert_eq!(toml["point"].table()["pos.x"].num(), 1.);
+
+    assert!(TomlParser::parse("a. = 1").is_err());
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Server {
+        port: u16,
+    }
+
+    #[derive(DeToml, Debug, PartialEq)]
+    pub struct Config {
+        server: Server,
+    }
+
+    let config: Config = DeToml::deserialize_toml("server.port = 8080").unwrap();
+    assert_eq!(config.server.port, 8080);
+}
+
+#[test]
+fn get_helpers() {
+    use nanoserde::TomlGet;
+
+    let toml = TomlParser::parse(
+        r#"
+title = "demo"
+[server]
+port = 8080
+tls = false
+point = { x = 1, label = { text = "a" } }
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(toml.get("title"), Some(&Toml::Str("demo".to_string())));
+    assert_eq!(toml.get("missing"), None);
+    assert_eq!(toml.get_str("title"), Some("demo"));
+    assert_eq!(toml.get_num("server.port"), Some(8080.));
+    assert_eq!(toml.get_bool("server.tls"), Some(false));
+    assert_eq!(toml.get_num("server.point.x"), Some(1.));
+    assert_eq!(toml.get_str("server.point.label.text"), Some("a"));
+    assert_eq!(
+        toml.get_path("server.point")
+            .and_then(|point| point.get_num("x")),
+        Some(1.)
+    );
+
+    assert_eq!(toml.get_num("server.missing"), None);
+    assert_eq!(toml.get_num("server.point.y"), None);
+    assert_eq!(toml.get_num("title"), None);
+    assert_eq!(toml.get_bool("server.port.x"), None);
+    assert_eq!(Toml::Num(1.).get("x"), None);
+}
+
+#[test]
+fn ser_toml_array_of_tables() {
+    #[derive(SerToml, DeToml, Debug, PartialEq)]
+    pub struct Point {
+        x: u32,
+        y: u32,
+    }
+
+    #[derive(SerToml, DeToml, Debug, PartialEq)]
+    pub struct Inner {
+        name: String,
+    }
+
+    #[derive(SerToml, DeToml, Debug, PartialEq)]
+    pub struct Shape {
+        id: u32,
+        pts: Vec<Point>,
+        inner: Inner,
+        values: Vec<Option<u32>>,
+        none: Vec<Point>,
+    }
+
+    let shape = Shape {
+        id: 1,
+        pts: vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+        inner: Inner {
+            name: "a".to_string(),
+        },
+        values: vec![Some(1), None, Some(3)],
+        none: vec![],
+    };
+    let toml = shape.serialize_toml();
+    assert_eq!(
+        toml,
+        "id = 1\nvalues = [1, 3]\nnone = []\n\n[[pts]]\nx = 1\ny = 2\n\n[[pts]]\nx = 3\ny = 4\n\n[inner]\nname = \"a\"\n"
+    );
+
+    let parsed = TomlParser::parse(&toml).unwrap();
+    assert_eq!(parsed["pts"].arr().len(), 2);
+    assert_eq!(parsed["pts"].arr()[1]["y"].num(), 4.);
+    assert_eq!(parsed["inner.name"].str(), "a");
+
+    let out: Shape = DeToml::deserialize_toml(&toml).unwrap();
+    assert_eq!(out.pts, shape.pts);
+    assert_eq!(out.inner, shape.inner);
+    assert_eq!(out.values, [Some(1), Some(3)]);
+    assert!(out.none.is_empty());
+
+    // nested in a section, the array takes the section's path
+    #[derive(SerToml, DeToml, Debug, PartialEq)]
+    pub struct Doc {
+        shape: Shape,
+    }
+
+    let doc = Doc { shape };
+    let toml = doc.serialize_toml();
+    assert!(toml.contains("[[shape.pts]]\nx = 1"), "{}", toml);
+    let out: Doc = DeToml::deserialize_toml(&toml).unwrap();
+    assert_eq!(out.shape.pts, doc.shape.pts);
+    assert_eq!(out.shape.inner, doc.shape.inner);
+}